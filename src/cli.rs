@@ -2,14 +2,18 @@ mod artifact;
 mod build;
 mod compose;
 mod container;
+mod drop_in;
+mod export;
 mod generate;
 mod global_args;
 mod image;
 mod install;
 mod k8s;
 mod kube;
+mod lint;
 mod network;
 mod pod;
+mod rewrite;
 pub mod volume;
 
 #[cfg(unix)]
@@ -20,6 +24,7 @@ use std::{
     collections::HashSet,
     env,
     ffi::OsStr,
+    fmt::Display,
     fs,
     io::{self, Write},
     path::{Path, PathBuf},
@@ -35,13 +40,26 @@ use path_clean::PathClean;
 
 use crate::quadlet::{
     self, Downgrade, DowngradeError, GenericSections, Globals, HostPaths, JoinOption,
-    PodmanVersion, Quadlet, Service, Unit,
+    PodmanVersion, Quadlet, RestartConfig, Service, Unit, UnitOption,
 };
 
 use self::{
-    artifact::Artifact, build::Build, compose::Compose, container::Container, generate::Generate,
-    global_args::GlobalArgs, image::Image, install::Install, kube::Kube, network::Network,
-    pod::Pod, volume::Volume,
+    artifact::Artifact,
+    build::Build,
+    compose::Compose,
+    container::Container,
+    drop_in::{DropIn, DropInFile},
+    export::Export,
+    generate::{Connection, Generate},
+    global_args::GlobalArgs,
+    image::Image,
+    install::Install,
+    kube::Kube,
+    lint::Lint,
+    network::Network,
+    pod::Pod,
+    rewrite::Rewrite,
+    volume::Volume,
 };
 
 #[expect(
@@ -205,6 +223,12 @@ pub struct Cli {
     #[command(flatten)]
     unit: Unit,
 
+    /// The \[Service\] section
+    ///
+    /// Options set by the command, e.g. `podman run --restart`, take precedence.
+    #[command(flatten)]
+    service: Service,
+
     /// The \[Install\] section
     #[command(flatten)]
     install: Install,
@@ -227,6 +251,22 @@ pub struct Cli {
     #[arg(long)]
     no_start_with_pod: bool,
 
+    /// Add an option to a section of the generated Quadlet file(s).
+    ///
+    /// In the form `SECTION.KEY=VALUE`, e.g. `--unit-option Service.KillMode=mixed`.
+    /// The section may be `Unit`, `Quadlet`, `Service`, `Install`, or a resource section, e.g.
+    /// `Container`. Options for a resource section are only added to Quadlet files of that type.
+    ///
+    /// Use this to set systemd or Quadlet options which Podlet does not otherwise support.
+    /// A warning is printed if Podlet supports the option directly.
+    ///
+    /// Can be specified multiple times.
+    #[arg(long = "unit-option", value_name = "SECTION.KEY=VALUE")]
+    unit_options: Vec<UnitOption>,
+
+    #[command(flatten)]
+    drop_in: DropIn,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -258,7 +298,40 @@ Image=image
 Quadlet options can be specified in a comma (,) separated list and/or this option can be specified \
 multiple times.";
 
+    /// Run commands which only print to stdout, i.e. `lint` and `export`.
+    ///
+    /// Returns [`None`] for all other commands.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command fails or if file output options are used, as they would be
+    /// silently ignored.
+    fn run_print_only(&self) -> Option<color_eyre::Result<()>> {
+        let (command, run): (_, &dyn Fn() -> color_eyre::Result<()>) = match &self.command {
+            Commands::Lint(lint) => ("lint", &|| lint.run(self.podman_version)),
+            Commands::Export(export) => ("export", &|| export.run()),
+            _ => return None,
+        };
+
+        if self.unit_directory || self.file.is_some() || self.quadlets_file.is_some() {
+            return Some(
+                Err(eyre!(
+                    "`--file`, `--unit-directory`, and `--quadlets-file` cannot be used with the \
+                        `{command}` command"
+                ))
+                .note(format!("`{command}` always prints to stdout."))
+                .suggestion("Redirect stdout to write the output to a file."),
+            );
+        }
+
+        Some(run())
+    }
+
     pub fn print_or_write_files(self) -> color_eyre::Result<()> {
+        if let Some(result) = self.run_print_only() {
+            return result;
+        }
+
         // Determine which Quadlet options to join together into a single line by subtracting the
         // selected options from the set of all possible options.
         let split_options = self.split_options.iter().copied().collect();
@@ -283,6 +356,32 @@ multiple times.";
                     .warning("`compose` can generate multiple files so a directory is needed.")
                     .suggestion("Provide a directory to `--file`."));
                 }
+                if matches!(
+                    self.command,
+                    Commands::Generate {
+                        command: Generate::Kube { .. },
+                        ..
+                    }
+                ) {
+                    return Err(eyre!(
+                        "A file path was provided to `--file` and the `generate kube` command \
+                            was used"
+                    )
+                    .warning(
+                        "`generate kube` also writes Kubernetes YAML so a directory is needed.",
+                    )
+                    .suggestion("Provide a directory to `--file`."));
+                }
+                if matches!(
+                    &self.command,
+                    Commands::Upgrade(rewrite) | Commands::Downgrade(rewrite)
+                        if rewrite.files.len() > 1
+                ) {
+                    return Err(eyre!(
+                        "A file path was provided to `--file` and multiple files are rewritten"
+                    )
+                    .suggestion("Provide a directory to `--file`."));
+                }
             }
             if quadlets_file.is_some()
                 && matches!(self.command, Commands::Compose(Compose { kube: true, .. }))
@@ -309,14 +408,7 @@ multiple times.";
             }
 
             if let Some(quadlets_file) = quadlets_file {
-                let contents = files_to_quadlets_file(&files, &join_options)?;
-                let path = path.to_full(&quadlets_file, "quadlets");
-
-                open_file(&path, overwrite)?
-                    .write_all(contents.as_bytes())
-                    .wrap_err_with(|| format!("error writing to file `{}`", path.display()))?;
-
-                println!("Wrote to file: {}", path.display());
+                write_quadlets_file(files, &path, &quadlets_file, overwrite, &join_options)?;
             } else {
                 for file in files {
                     file.write(&path, overwrite, &join_options)?;
@@ -424,7 +516,9 @@ multiple times.";
             install: self.install.into(),
         };
 
-        let mut files = self.command.try_into_files(self.name, sections)?;
+        let mut files = self
+            .command
+            .try_into_files(self.name, sections, self.podman_version)?;
 
         if let Some(service_name) = self.service_name {
             let mut found_quadlet_file = false;
@@ -450,6 +544,18 @@ multiple times.";
             }
         }
 
+        if !self.service.is_empty() {
+            for file in files.iter_mut().filter_map(File::as_quadlet_file_mut) {
+                file.service.merge(&self.service);
+            }
+        }
+
+        add_unit_options(
+            files.iter_mut().filter_map(File::as_quadlet_file_mut),
+            &self.unit_options,
+            "`--unit-option`",
+        );
+
         let downgrade = self.podman_version < PodmanVersion::LATEST;
         if resolve_dir.is_some() || self.no_start_with_pod || downgrade {
             for file in &mut files {
@@ -472,10 +578,50 @@ multiple times.";
             }
         }
 
+        self.drop_in.apply(&mut files)?;
+
         Ok(files)
     }
 }
 
+/// Add the `options` to the Quadlet `files`.
+///
+/// A warning is printed for each option Podlet supports directly, and for each option which was
+/// not added to any file because none have its resource section. `context` is used in the
+/// warnings, e.g. "`--unit-option`".
+fn add_unit_options<'a>(
+    files: impl IntoIterator<Item = &'a mut quadlet::File>,
+    options: &[UnitOption],
+    context: impl Display,
+) {
+    if options.is_empty() {
+        return;
+    }
+
+    let mut added = vec![false; options.len()];
+    for file in files {
+        for (option, added) in options.iter().zip(&mut added) {
+            *added |= file.add_option(option);
+        }
+    }
+
+    for (option, added) in options.iter().zip(added) {
+        if option.is_modeled() {
+            eprintln!(
+                "Warning: `{option}` from {context} sets an option Podlet supports directly, \
+                    it may conflict with the value set by Podlet"
+            );
+        }
+        if !added {
+            eprintln!(
+                "Warning: `{option}` from {context} was not added, \
+                    no Quadlet file with a [{}] section was generated",
+                option.section
+            );
+        }
+    }
+}
+
 /// [`PathBuf`] pointing to a file or directory
 #[derive(Debug)]
 enum FilePath {
@@ -489,18 +635,71 @@ impl FilePath {
     /// Convert to full file path
     ///
     /// If `self` is a directory, `filename` and `extension` are used to set the filename.
+    /// The `filename` is used as is if `extension` is empty.
     fn to_full(&self, filename: &str, extension: &str) -> Cow<'_, Path> {
         match self {
             Self::Full(path) => path.into(),
             Self::Dir(path) => {
                 let mut path = path.join(filename);
-                path.set_extension(extension);
+                if !extension.is_empty() {
+                    path.set_extension(extension);
+                }
                 path.into()
             }
         }
     }
 }
 
+/// Write the `files` into a `.quadlets` file named `quadlets_file` at `path`, optionally
+/// overwriting any already existing files.
+///
+/// [`File::Content`]s are written next to the `.quadlets` file. Quadlet options in
+/// `join_options` are joined together with a space.
+///
+/// # Errors
+///
+/// Returns an error if the `files` include symlinks, or if there is an error while serializing or
+/// when writing the files.
+fn write_quadlets_file(
+    files: Vec<File>,
+    path: &FilePath,
+    quadlets_file: &str,
+    overwrite: bool,
+    join_options: &HashSet<JoinOption>,
+) -> color_eyre::Result<()> {
+    if files
+        .iter()
+        .any(|file| matches!(file, File::Symlink { .. }))
+    {
+        return Err(
+            eyre!("`--quadlets-file` cannot be used with services with multiple replicas").note(
+                "Instances of a template unit are symlinks to the template's file, \
+                which does not exist when it is part of a `.quadlets` file.",
+            ),
+        );
+    }
+
+    // Content files cannot be included in a `.quadlets` file, they are written next to it.
+    let (content_files, files): (Vec<_>, Vec<_>) = files
+        .into_iter()
+        .partition(|file| matches!(file, File::Content { .. }));
+
+    let contents = files_to_quadlets_file(&files, join_options)?;
+    let quadlets_path = path.to_full(quadlets_file, "quadlets");
+
+    open_file(&quadlets_path, overwrite)?
+        .write_all(contents.as_bytes())
+        .wrap_err_with(|| format!("error writing to file `{}`", quadlets_path.display()))?;
+
+    println!("Wrote to file: {}", quadlets_path.display());
+
+    for file in content_files {
+        file.write(path, overwrite, join_options)?;
+    }
+
+    Ok(())
+}
+
 /// Serialize each [`File`] and join them together in the `.quadlets` file format.
 ///
 /// # Errors
@@ -531,6 +730,9 @@ fn files_to_quadlets_file<'a>(
                     content.push_str(file_name);
                     content.push_str(".yaml");
                 }
+                File::Content { .. } | File::Symlink { .. } | File::DropIn(_) => {
+                    content.push_str(file_name);
+                }
             }
 
             content.push('\n');
@@ -565,14 +767,64 @@ enum Commands {
     ///
     /// When Podlet encounters an unsupported option, an error will be returned.
     /// Modify the compose file to resolve the error.
+    ///
+    /// Quadlet options which cannot be derived from the compose file may be set with the
+    /// `x-podlet` extension, at the top-level or on a service, with `unit`, `quadlet`, `service`,
+    /// and `install` sections. Other compose extensions are ignored with a warning.
     Compose(#[command(flatten)] Compose),
 
     /// Generate a Podman Quadlet file from an existing object.
     ///
     /// Note: these commands require that Podman is installed and is searchable
-    /// from the `PATH` environment variable.
+    /// from the `PATH` environment variable, or that the Podman API socket is available when
+    /// `--api` is used.
+    Generate {
+        #[command(flatten)]
+        connection: Connection,
+
+        #[command(subcommand)]
+        command: Generate,
+    },
+
+    /// Upgrade existing Quadlet files to use newer Quadlet options
+    ///
+    /// Flags in the `PodmanArgs=` Quadlet option are moved to their equivalent Quadlet options
+    /// if they are supported by the version set with `--podman-version`. For example,
+    /// `PodmanArgs=--stop-timeout 20` becomes `StopTimeout=20`.
+    ///
+    /// If the flags of a file cannot be upgraded, a warning is printed and the file is left as is.
+    ///
+    /// Use `--file` or `--unit-directory` with `--overwrite` to rewrite the files in place.
+    Upgrade(#[command(flatten)] Rewrite),
+
+    /// Downgrade existing Quadlet files for an earlier Podman version
+    ///
+    /// The Podman version is set with `--podman-version`, e.g.
+    /// `podlet --podman-version 4.8 downgrade example.container`. Files are rewritten unchanged if
+    /// they already support the version, e.g. when it is the latest version.
+    ///
+    /// Quadlet options which are not supported by the Podman version are moved to
+    /// the `PodmanArgs=` Quadlet option where possible. An error is returned otherwise.
+    ///
+    /// Use `--file` or `--unit-directory` with `--overwrite` to rewrite the files in place.
+    Downgrade(#[command(flatten)] Rewrite),
+
+    /// Check existing Quadlet files for problems
+    ///
+    /// Reports unknown options, options not supported by the version set with `--podman-version`,
+    /// invalid values, references to `.network`, `.volume`, and `.pod` files which do not exist,
+    /// and conflicting options.
+    ///
+    /// Each problem is printed with the file and line it was found on, and a suggestion for
+    /// fixing it when possible. An error is returned if any problems, other than warnings, are
+    /// found.
+    Lint(#[command(flatten)] Lint),
+
+    /// Convert existing Quadlet files into other formats
+    ///
+    /// The converted files are printed to stdout.
     #[command(subcommand)]
-    Generate(Generate),
+    Export(Export),
 }
 
 impl Commands {
@@ -580,11 +832,13 @@ impl Commands {
     ///
     /// # Errors
     ///
-    /// Returns an error if there was an error converting from the compose file or existing object.
+    /// Returns an error if there was an error converting from the compose file or existing object,
+    /// or reading existing Quadlet files.
     fn try_into_files(
         self,
         name: Option<String>,
         sections: GenericSections,
+        podman_version: PodmanVersion,
     ) -> color_eyre::Result<Vec<File>> {
         match self {
             Self::Podman {
@@ -598,12 +852,26 @@ impl Commands {
             Self::Compose(compose) => compose
                 .try_into_files(sections)
                 .wrap_err("error converting compose file"),
-            Self::Generate(command) => Ok(command
-                .try_into_quadlet_files(name, sections)
-                .wrap_err("error creating Quadlet file(s) from an existing object")?
+            Self::Generate {
+                connection,
+                command,
+            } => command
+                .try_into_files(connection, name, sections)
+                .wrap_err("error creating Quadlet file(s) from an existing object"),
+            Self::Upgrade(rewrite) => {
+                let mut files = rewrite.try_into_files(name, sections)?;
+                for file in &mut files {
+                    rewrite::upgrade(file, podman_version);
+                }
+                Ok(files.into_iter().map(Into::into).collect())
+            }
+            Self::Downgrade(rewrite) => Ok(rewrite
+                .try_into_files(name, sections)?
                 .into_iter()
                 .map(Into::into)
                 .collect()),
+            Self::Lint(_) => Err(eyre!("`lint` does not generate files")),
+            Self::Export(_) => Err(eyre!("`export` does not generate Quadlet files")),
         }
     }
 }
@@ -621,9 +889,11 @@ enum PodmanCommands {
         #[command(flatten)]
         container: Box<Container>,
 
-        /// The \[Service\] section
-        #[command(flatten)]
-        service: Service,
+        /// Configure if and when the service should be restarted.
+        ///
+        /// Converts to "Restart=POLICY" in the \[Service\] section.
+        #[arg(long, value_name = "POLICY")]
+        restart: Option<RestartConfig>,
 
         /// Print help
         // Changed from default to support `podman run -h`, i.e. `podman run --hostname`.
@@ -735,7 +1005,7 @@ impl PodmanCommands {
         }: GenericSections,
         globals: Globals,
     ) -> quadlet::File {
-        let service = self.service().cloned().unwrap_or_default();
+        let service = self.service().unwrap_or_default();
         quadlet::File {
             name: name.unwrap_or_else(|| self.name().into()),
             unit,
@@ -744,12 +1014,13 @@ impl PodmanCommands {
             quadlet,
             service,
             install,
+            options: Vec::new(),
         }
     }
 
-    fn service(&self) -> Option<&Service> {
+    fn service(&self) -> Option<Service> {
         match self {
-            Self::Run { service, .. } => Some(service),
+            Self::Run { restart, .. } => restart.map(Into::into),
             _ => None,
         }
     }
@@ -774,6 +1045,20 @@ impl PodmanCommands {
 enum File {
     Quadlet(quadlet::File),
     Kubernetes(k8s::File),
+    /// File written as is, e.g. the contents of a compose config.
+    Content {
+        name: String,
+        contents: String,
+        /// Whether the file is a script which should be written with executable permissions.
+        executable: bool,
+    },
+    /// Symbolic link to another file, e.g. an instance of a template unit.
+    Symlink {
+        /// File name of the link, including its extension.
+        name: String,
+        target: PathBuf,
+    },
+    DropIn(DropInFile),
 }
 
 impl From<quadlet::File> for File {
@@ -793,6 +1078,8 @@ impl File {
         match self {
             Self::Quadlet(file) => &file.name,
             Self::Kubernetes(file) => &file.name,
+            Self::Content { name, .. } | Self::Symlink { name, .. } => name,
+            Self::DropIn(drop_in) => &drop_in.path,
         }
     }
 
@@ -800,6 +1087,7 @@ impl File {
         match self {
             Self::Quadlet(file) => file.resource.extension(),
             Self::Kubernetes(_) => "yaml",
+            Self::Content { .. } | Self::Symlink { .. } | Self::DropIn(_) => "",
         }
     }
 
@@ -807,7 +1095,9 @@ impl File {
     fn as_quadlet_file(&self) -> Option<&quadlet::File> {
         match self {
             Self::Quadlet(file) => Some(file),
-            Self::Kubernetes(_) => None,
+            Self::Kubernetes(_) | Self::Content { .. } | Self::Symlink { .. } | Self::DropIn(_) => {
+                None
+            }
         }
     }
 
@@ -815,7 +1105,9 @@ impl File {
     fn as_quadlet_file_mut(&mut self) -> Option<&mut quadlet::File> {
         match self {
             Self::Quadlet(file) => Some(file),
-            Self::Kubernetes(_) => None,
+            Self::Kubernetes(_) | Self::Content { .. } | Self::Symlink { .. } | Self::DropIn(_) => {
+                None
+            }
         }
     }
 
@@ -851,6 +1143,9 @@ impl File {
             File::Kubernetes(file) => file
                 .serialize_to_yaml()
                 .wrap_err("error serializing Kubernetes YAML file"),
+            File::Content { contents, .. } => Ok(contents.clone()),
+            File::Symlink { target, .. } => Ok(format!("# symlink to {}\n", target.display())),
+            File::DropIn(drop_in) => Ok(drop_in.serialize(join_options)),
         }
     }
 
@@ -868,7 +1163,20 @@ impl File {
         join_options: &HashSet<JoinOption>,
     ) -> color_eyre::Result<()> {
         let path = path.to_full(self.name(), self.extension());
+        if let Self::Symlink { target, .. } = self {
+            return create_symlink(target, &path, overwrite);
+        }
+        if let (Self::DropIn(_), Some(dir)) = (self, path.parent()) {
+            fs::create_dir_all(dir)
+                .wrap_err_with(|| format!("failed to create directory: {}", dir.display()))?;
+        }
         let mut file = open_file(&path, overwrite)?;
+        if let Self::Content {
+            executable: true, ..
+        } = self
+        {
+            set_executable(&file, &path)?;
+        }
         let path = path.display();
 
         let contents = self
@@ -896,6 +1204,68 @@ fn absolute_clean_path(resolve_dir: &Path, path: &Path) -> PathBuf {
     path.clean()
 }
 
+/// Create a symbolic link at `path` pointing to `target`, optionally replacing an existing file.
+///
+/// # Errors
+///
+/// Returns an error if the link could not be created, or if symbolic links are not supported on
+/// the platform.
+fn create_symlink(target: &Path, path: &Path, overwrite: bool) -> color_eyre::Result<()> {
+    #[cfg(unix)]
+    {
+        if overwrite && path.symlink_metadata().is_ok() {
+            fs::remove_file(path)
+                .wrap_err_with(|| format!("failed to remove file: {}", path.display()))?;
+        }
+        std::os::unix::fs::symlink(target, path).map_err(|error| {
+            let path = path.display();
+            match error.kind() {
+                io::ErrorKind::AlreadyExists => {
+                    eyre!("File already exists, not overwriting it: {path}")
+                        .suggestion("Use `--overwrite` if you wish overwrite existing files.")
+                }
+                _ => color_eyre::Report::new(error)
+                    .wrap_err(format!("Failed to create symlink: {path}")),
+            }
+        })?;
+        println!("Linked file: {} -> {}", path.display(), target.display());
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = overwrite;
+        Err(eyre!(
+            "cannot create symlink `{}` to `{}`, symlinks are only supported on Unix",
+            path.display(),
+            target.display()
+        ))
+    }
+}
+
+/// Set the permissions of the `file` at `path` to `rwxr-xr-x` so that it can be executed.
+///
+/// Does nothing on platforms other than Unix.
+///
+/// # Errors
+///
+/// Returns an error if the permissions could not be set.
+fn set_executable(file: &fs::File, path: &Path) -> color_eyre::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        file.set_permissions(fs::Permissions::from_mode(0o755))
+            .wrap_err_with(|| format!("failed to set permissions of file: {}", path.display()))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (file, path);
+        Ok(())
+    }
+}
+
 fn open_file(path: impl AsRef<Path>, overwrite: bool) -> color_eyre::Result<fs::File> {
     fs::File::options()
         .write(true)
@@ -932,7 +1302,9 @@ impl Downgrade for File {
     fn downgrade(&mut self, version: PodmanVersion) -> Result<(), DowngradeError> {
         match self {
             Self::Quadlet(file) => file.downgrade(version),
-            Self::Kubernetes(_) => Ok(()),
+            Self::Kubernetes(_) | Self::Content { .. } | Self::Symlink { .. } | Self::DropIn(_) => {
+                Ok(())
+            }
         }
     }
 }
@@ -999,4 +1371,136 @@ mod tests {
     fn verify_cli() {
         Cli::command().debug_assert();
     }
+
+    #[test]
+    fn print_only_commands_reject_file_out() -> color_eyre::Result<()> {
+        for args in [
+            ["podlet", "--file", "lint", "test.container"].as_slice(),
+            &["podlet", "--unit-directory", "lint", "test.container"],
+            &[
+                "podlet",
+                "--quadlets-file",
+                "test",
+                "export",
+                "compose",
+                "test.yaml",
+            ],
+        ] {
+            let cli = Cli::try_parse_from(args)?;
+            assert!(cli.print_or_write_files().is_err(), "{args:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn service_section() -> color_eyre::Result<()> {
+        let cli = Cli::try_parse_from([
+            "podlet",
+            "--restart=no",
+            "--timeout-start-sec=900",
+            "podman",
+            "run",
+            "--restart=always",
+            "image",
+        ])?;
+
+        let files = cli.try_into_files()?;
+        let [File::Quadlet(file)] = files.as_slice() else {
+            color_eyre::eyre::bail!("expected a single Quadlet file");
+        };
+        assert_eq!(file.service.restart, Some(RestartConfig::Always));
+        assert_eq!(file.service.timeout_start_sec.as_deref(), Some("900"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn unit_section() -> color_eyre::Result<()> {
+        let cli = Cli::try_parse_from([
+            "podlet",
+            "--on-failure=notify@%n.service",
+            "--condition-path-exists=/etc/one",
+            "--condition-path-exists=!/etc/two",
+            "--assert-user=root",
+            "--stop-when-unneeded",
+            "podman",
+            "run",
+            "image",
+        ])?;
+
+        let files = cli.try_into_files()?;
+        let [File::Quadlet(file)] = files.as_slice() else {
+            color_eyre::eyre::bail!("expected a single Quadlet file");
+        };
+        assert_eq!(file.unit.on_failure, ["notify@%n.service"]);
+        assert_eq!(file.unit.condition_path_exists, ["/etc/one", "!/etc/two"]);
+        assert_eq!(file.unit.assert_user, ["root"]);
+        assert!(file.unit.stop_when_unneeded);
+
+        Ok(())
+    }
+
+    #[test]
+    fn install_section() -> color_eyre::Result<()> {
+        for flag in ["--alias=alias.service", "--default-instance=1"] {
+            assert!(
+                Cli::try_parse_from(["podlet", flag, "podman", "run", "image"]).is_err(),
+                "{flag} should require --install"
+            );
+        }
+
+        let cli = Cli::try_parse_from([
+            "podlet",
+            "--install",
+            "--alias=alias.service",
+            "--default-instance=1",
+            "podman",
+            "run",
+            "image",
+        ])?;
+
+        let files = cli.try_into_files()?;
+        let [File::Quadlet(file)] = files.as_slice() else {
+            color_eyre::eyre::bail!("expected a single Quadlet file");
+        };
+        assert_eq!(file.install.alias, ["alias.service"]);
+        assert_eq!(file.install.default_instance.as_deref(), Some("1"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn unit_options() -> color_eyre::Result<()> {
+        let cli = Cli::try_parse_from([
+            "podlet",
+            "--unit-option=Service.KillMode=mixed",
+            "--unit-option=Unit.After=one.service",
+            "--unit-option=Unit.After=two three.service",
+            "--unit-option=Pod.PodName=ignored",
+            "podman",
+            "run",
+            "--restart=always",
+            "image",
+        ])?;
+
+        let files = cli.try_into_files()?;
+        let [File::Quadlet(file)] = files.as_slice() else {
+            color_eyre::eyre::bail!("expected a single Quadlet file");
+        };
+        assert_eq!(file.options.len(), 3);
+        assert_eq!(
+            file.serialize_to_quadlet(&JoinOption::all_set())?,
+            "[Unit]\n\
+            After=one.service \"two three.service\"\n\
+            \n\
+            [Container]\n\
+            Image=image\n\
+            \n\
+            [Service]\n\
+            Restart=always\n\
+            KillMode=mixed\n"
+        );
+
+        Ok(())
+    }
 }
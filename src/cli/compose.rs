@@ -1,24 +1,39 @@
+mod config;
+mod deploy;
+mod extension;
+mod merge;
+mod profile;
+mod replicas;
+mod resolve;
+mod secret;
+
 use std::{
     collections::HashMap,
     fs,
     io::{self, IsTerminal},
     iter, mem,
     path::{Path, PathBuf},
+    process,
 };
 
 use clap::Args;
 use color_eyre::{
     Help,
-    eyre::{OptionExt, WrapErr, bail, ensure, eyre},
+    eyre::{OptionExt, WrapErr, bail, eyre},
 };
 use compose_spec::{
-    Identifier, Network, Networks, Options, Resource, Service, Volumes, service::Command,
+    Identifier, Name, Network, Networks, Options, Resource, Service, ShortOrLong, Volumes,
+    service::{Command, Condition},
 };
 use indexmap::IndexMap;
 
-use crate::quadlet::{self, GenericSections, Globals, container::volume::Source};
+use crate::quadlet::{
+    self, GenericSections, Globals, RestartConfig, ServiceType,
+    container::{Notify, volume::Source},
+};
 
-use super::{Build, Container, File, GlobalArgs, k8s};
+use self::{config::Configs, extension::Extension, resolve::Resolver};
+use super::{Build, Container, File, GlobalArgs, add_unit_options, k8s};
 
 /// Converts a [`Command`] into a [`Vec<String>`], splitting the [`String`](Command::String) variant
 /// as a shell would.
@@ -40,6 +55,7 @@ pub fn command_try_into_vec(command: Command) -> color_eyre::Result<Vec<String>>
 }
 
 /// [`Args`] for the `podlet compose` subcommand.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Args, Debug, Clone, PartialEq, Eq)]
 pub struct Compose {
     /// Create a `.pod` file and link it with each `.container` file.
@@ -71,6 +87,34 @@ pub struct Compose {
     #[arg(long)]
     pub add_container_name: bool,
 
+    /// Mount compose configs as Podman secrets instead of read-only bind mounts.
+    ///
+    /// Each config is mounted from a Podman secret with the same name as the config,
+    /// or the name of an external config. The secrets must be created before the containers
+    /// are started, e.g. with `podman secret create`.
+    #[arg(long, conflicts_with = "kube")]
+    pub config_secrets: bool,
+
+    /// Check that external networks and volumes exist.
+    ///
+    /// Runs `podman network exists` and `podman volume exists` for each external network and
+    /// volume, and returns an error if one does not exist.
+    #[arg(long, conflicts_with = "kube")]
+    pub check_external: bool,
+
+    /// Enable services with the given profile.
+    ///
+    /// Services without `profiles` are always enabled. Services with `profiles` are only converted
+    /// if one of their profiles is enabled, or "*" is given. Networks and volumes only used by
+    /// services which are not enabled are also dropped.
+    ///
+    /// If not given, profiles are read from the comma separated `COMPOSE_PROFILES` environment
+    /// variable.
+    ///
+    /// Can be specified multiple times.
+    #[arg(long = "profile", value_name = "PROFILE")]
+    pub profiles: Vec<String>,
+
     /// The compose file to convert
     ///
     /// If `-` or not provided and stdin is not a terminal,
@@ -94,51 +138,53 @@ impl Compose {
     /// - Reading/deserializing the compose file.
     /// - Converting the compose file to Kubernetes YAML.
     /// - Converting the compose file to Quadlet files.
-    pub fn try_into_files(self, sections: GenericSections) -> color_eyre::Result<Vec<File>> {
+    pub fn try_into_files(self, mut sections: GenericSections) -> color_eyre::Result<Vec<File>> {
         let Self {
             pod,
             kube,
             add_container_name,
+            config_secrets,
+            check_external,
+            profiles,
             compose_file,
         } = self;
 
         let mut options = compose_spec::Compose::options();
         options.apply_merge(true);
-        let compose = read_from_file_or_stdin(compose_file.as_deref(), &options)
+        let mut compose = read_from_file_or_stdin(compose_file.as_deref(), &options)
             .wrap_err("error reading compose file")?;
+
+        // Paths in the compose file are relative to its directory.
+        let compose_file = compose_file.filter(|path| path.as_os_str() != "-");
+        let dir = compose_file
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new(""));
+        Resolver::new(&options, compose_file.as_deref())
+            .resolve(&mut compose, dir)
+            .wrap_err("error resolving `include` and `extends` of compose file")?;
+
         compose
             .validate_all()
             .wrap_err("error validating compose file")?;
 
-        if kube {
-            let mut k8s_file = k8s::File::try_from(compose)
-                .wrap_err("error converting compose file into Kubernetes YAML")?;
-
-            let GenericSections {
-                unit,
-                quadlet,
-                install,
-            } = sections;
-            let kube =
-                quadlet::Kube::new(PathBuf::from(format!("{}-kube.yaml", k8s_file.name)).into());
-            let quadlet_file = quadlet::File {
-                name: k8s_file.name.clone(),
-                unit,
-                resource: kube.into(),
-                globals: Globals::default(),
-                quadlet,
-                service: quadlet::Service::default(),
-                install,
-            };
+        profile::apply(&mut compose, profiles).wrap_err("error applying compose profiles")?;
+
+        // Options from the `x-podlet` extension apply to all files,
+        // options given on the command line take precedence.
+        let (service_section, unit_options) =
+            Extension::take_from(&mut compose.extensions, "the compose file")?
+                .merge_into(&mut sections)?;
+        ignore_resource_extensions(&mut compose);
 
-            k8s_file.name.push_str("-kube");
-            Ok(vec![quadlet_file.into(), k8s_file.into()])
+        let mut files = if kube {
+            compose_try_into_kube_files(compose, sections, service_section)?
         } else {
             let compose_spec::Compose {
                 version: _,
                 name,
                 include,
-                services,
+                mut services,
                 networks,
                 volumes,
                 configs,
@@ -146,33 +192,162 @@ impl Compose {
                 extensions,
             } = compose;
 
+            debug_assert!(include.is_empty(), "`include` is resolved above");
+            debug_assert!(extensions.is_empty(), "extensions are taken above");
+
+            let secrets_script = secret::script(name.as_ref().map(Name::as_str), secrets, dir)
+                .wrap_err("error creating script for compose secrets")?;
+
             let pod_name = pod
                 .then(|| name.ok_or_eyre("`name` is required when using `--pod`"))
                 .transpose()?
                 .map(Into::into);
 
-            ensure!(include.is_empty(), "`include` is not supported");
-            ensure!(configs.is_empty(), "`configs` is not supported");
-            ensure!(
-                secrets.values().all(Resource::is_external),
-                "only external `secrets` are supported",
-            );
-            ensure!(
-                extensions.is_empty(),
-                "compose extensions are not supported"
-            );
+            let (configs, config_files) = Configs::new(configs, config_secrets)
+                .wrap_err("error converting compose configs")?;
+            for (name, service) in &mut services {
+                configs
+                    .apply_to(service)
+                    .wrap_err_with(|| format!("error converting `configs` of service `{name}`"))?;
+            }
+
+            if check_external {
+                check_external_resources(&networks, &volumes)?;
+            }
 
-            parts_try_into_files(
+            let mut files = parts_try_into_files(
                 services,
                 networks,
                 volumes,
                 pod_name,
                 sections,
+                &service_section,
                 add_container_name,
             )
-            .wrap_err("error converting compose file into Quadlet files")
+            .wrap_err("error converting compose file into Quadlet files")?;
+            files.extend(config_files);
+            files.extend(secrets_script);
+
+            files
+        };
+
+        add_unit_options(
+            files.iter_mut().filter_map(File::as_quadlet_file_mut),
+            &unit_options,
+            format_args!("the `{}` extension of the compose file", extension::KEY),
+        );
+
+        Ok(files)
+    }
+}
+
+/// Attempt to convert a `compose` file into a Kubernetes YAML file and a `.kube` Quadlet file.
+///
+/// The `x-podlet` extensions of services are ignored with a warning.
+///
+/// # Errors
+///
+/// Returns an error if there was an error reading the extension of a service or converting the
+/// compose file into Kubernetes YAML.
+fn compose_try_into_kube_files(
+    mut compose: compose_spec::Compose,
+    sections: GenericSections,
+    service_section: quadlet::Service,
+) -> color_eyre::Result<Vec<File>> {
+    for (name, service) in &mut compose.services {
+        let context = format_args!("service `{name}`");
+        if !Extension::take_from(&mut service.extensions, context)?.is_empty() {
+            eprintln!(
+                "Warning: `{}` of service `{name}` cannot be used with `--kube` and \
+                        was ignored",
+                extension::KEY
+            );
+        }
+    }
+
+    let mut k8s_file = k8s::File::try_from(compose)
+        .wrap_err("error converting compose file into Kubernetes YAML")?;
+
+    let GenericSections {
+        unit,
+        quadlet,
+        install,
+    } = sections;
+    let kube = quadlet::Kube::new(PathBuf::from(format!("{}-kube.yaml", k8s_file.name)).into());
+    let quadlet_file = quadlet::File {
+        name: k8s_file.name.clone(),
+        unit,
+        resource: kube.into(),
+        globals: Globals::default(),
+        quadlet,
+        service: service_section,
+        install,
+        options: Vec::new(),
+    };
+
+    k8s_file.name.push_str("-kube");
+    Ok(vec![quadlet_file.into(), k8s_file.into()])
+}
+
+/// Remove the extensions of the compose file's networks, volumes, configs, and secrets, printing a
+/// warning for each one.
+fn ignore_resource_extensions(compose: &mut compose_spec::Compose) {
+    for (name, network) in &mut compose.networks {
+        if let Some(Resource::Compose(network)) = network {
+            extension::ignore(&mut network.extensions, format_args!("network `{name}`"));
+        }
+    }
+    for (name, volume) in &mut compose.volumes {
+        if let Some(Resource::Compose(volume)) = volume {
+            extension::ignore(&mut volume.extensions, format_args!("volume `{name}`"));
+        }
+    }
+    for (name, config) in &mut compose.configs {
+        if let Resource::Compose(config) = config {
+            extension::ignore(&mut config.extensions, format_args!("config `{name}`"));
+        }
+    }
+    for (name, secret) in &mut compose.secrets {
+        if let Resource::Compose(secret) = secret {
+            extension::ignore(&mut secret.extensions, format_args!("secret `{name}`"));
+        }
+    }
+}
+
+/// Check that the external `networks` and `volumes` exist with `podman network exists` and
+/// `podman volume exists`.
+///
+/// # Errors
+///
+/// Returns an error if an external network or volume does not exist or there was an error running
+/// Podman.
+fn check_external_resources(networks: &Networks, volumes: &Volumes) -> color_eyre::Result<()> {
+    let networks = networks
+        .iter()
+        .filter_map(|(ident, network)| match network {
+            Some(Resource::External { name }) => Some(("network", ident, name)),
+            _ => None,
+        });
+    let volumes = volumes.iter().filter_map(|(ident, volume)| match volume {
+        Some(Resource::External { name }) => Some(("volume", ident, name)),
+        _ => None,
+    });
+    for (kind, ident, name) in networks.chain(volumes) {
+        let name = name.as_deref().unwrap_or(ident.as_str());
+
+        let status = process::Command::new("podman")
+            .args([kind, "exists", name])
+            .status()
+            .wrap_err_with(|| format!("error running `podman {kind} exists {name}`"))
+            .note("ensure Podman is installed and available on $PATH")?;
+        match status.code() {
+            Some(0) => {}
+            Some(1) => bail!("external {kind} `{name}` does not exist"),
+            _ => bail!("`podman {kind} exists {name}` exited unsuccessfully"),
         }
     }
+
+    Ok(())
 }
 
 /// Read and deserialize a [`compose_spec::Compose`] from a file at the given [`Path`], stdin, or a
@@ -260,33 +435,36 @@ fn read_from_stdin(options: &Options) -> color_eyre::Result<compose_spec::Compos
 ///
 /// Returns an error if a [`Service`], [`Network`], or [`Volume`](compose_spec::Volume) could not be
 /// converted into a [`quadlet::File`].
+///
+/// Services with `scale` or `deploy.replicas` set are converted into template units, see
+/// [`replicas::apply()`].
 fn parts_try_into_files(
-    services: IndexMap<Identifier, Service>,
+    mut services: IndexMap<Identifier, Service>,
     networks: Networks,
     volumes: Volumes,
     pod_name: Option<String>,
     sections: GenericSections,
+    service_section: &quadlet::Service,
     add_container_name: bool,
 ) -> color_eyre::Result<Vec<File>> {
-    // Get a map of volumes to whether the volume has options associated with it for use in
-    // converting a service into a Quadlet file. Extra volume options must be specified in a
-    // separate Quadlet file which is referenced from the container Quadlet file.
-    let volume_has_options = volumes
-        .iter()
-        .map(|(name, volume)| {
-            let has_options = volume
+    let resource_names = ResourceNames::new(&networks, &volumes);
+
+    let mut service_replicas = HashMap::new();
+    for (ident, service) in &mut services {
+        if let Some(replicas) = replicas::take(ident, service)? {
+            let name = pod_name
                 .as_ref()
-                .and_then(Resource::as_compose)
-                .is_some_and(|volume| !volume.is_empty());
-            (name.clone(), has_options)
-        })
-        .collect();
+                .map_or_else(|| ident.to_string(), |pod| format!("{pod}-{ident}"));
+            service_replicas.insert(name, replicas);
+        }
+    }
 
     let mut pod_ports = Vec::new();
     let mut files = services_try_into_quadlet_files(
         services,
         &sections,
-        &volume_has_options,
+        service_section,
+        &resource_names,
         pod_name.as_deref(),
         &mut pod_ports,
         add_container_name,
@@ -296,6 +474,8 @@ fn parts_try_into_files(
     .map(|result| result.map(Into::into))
     .collect::<Result<Vec<File>, _>>()?;
 
+    replicas::apply(&mut files, &service_replicas);
+
     if let Some(name) = pod_name {
         let GenericSections {
             unit,
@@ -314,6 +494,7 @@ fn parts_try_into_files(
             quadlet,
             service: quadlet::Service::default(),
             install,
+            options: Vec::new(),
         };
         files.push(pod.into());
     }
@@ -321,18 +502,103 @@ fn parts_try_into_files(
     Ok(files)
 }
 
+/// Names used to reference the compose file's networks and volumes from `.container` files.
+#[derive(Debug, Default)]
+struct ResourceNames {
+    /// Map of network [`Identifier`]s to the name used in `Network=`.
+    networks: HashMap<Identifier, String>,
+
+    /// Map of volume [`Identifier`]s to the name used as the source in `Volume=`.
+    ///
+    /// Named volumes which are not in the map are used as is.
+    volumes: HashMap<Identifier, String>,
+}
+
+impl ResourceNames {
+    /// Create [`ResourceNames`] from the compose file's `networks` and `volumes`.
+    ///
+    /// Networks are referenced by their generated `.network` file. Volumes with options are
+    /// referenced by their generated `.volume` file, as extra volume options must be specified in a
+    /// separate Quadlet file. External networks and volumes are referenced by their name.
+    fn new(networks: &Networks, volumes: &Volumes) -> Self {
+        let networks = networks
+            .iter()
+            .map(|(ident, network)| {
+                let name = match network {
+                    Some(Resource::External { name }) => {
+                        name.clone().unwrap_or_else(|| ident.to_string())
+                    }
+                    _ => format!("{ident}.network"),
+                };
+                (ident.clone(), name)
+            })
+            .collect();
+
+        let volumes = volumes
+            .iter()
+            .filter_map(|(ident, volume)| {
+                let name = match volume {
+                    Some(Resource::External { name }) => {
+                        name.clone().unwrap_or_else(|| ident.to_string())
+                    }
+                    Some(Resource::Compose(volume)) if !volume.is_empty() => {
+                        format!("{ident}.volume")
+                    }
+                    _ => return None,
+                };
+                Some((ident.clone(), name))
+            })
+            .collect();
+
+        Self { networks, volumes }
+    }
+
+    /// Reference networks and named volumes in the `container` by their name.
+    fn apply_to(&self, container: &mut quadlet::Container) {
+        for network in &mut container.network {
+            let (ident, options) = network
+                .split_once(':')
+                .map_or((network.as_str(), None), |(ident, options)| {
+                    (ident, Some(options))
+                });
+            let name = ident
+                .strip_suffix(".network")
+                .and_then(|ident| self.networks.get(ident));
+            if let Some(name) = name {
+                *network =
+                    options.map_or_else(|| name.clone(), |options| format!("{name}:{options}"));
+            }
+        }
+
+        for volume in &mut container.volume {
+            if let Some(Source::NamedVolume(source)) = &mut volume.source {
+                if let Some(name) = self.volumes.get(source.as_str()) {
+                    source.clone_from(name);
+                }
+            }
+        }
+    }
+}
+
 /// Attempt to convert Compose [`Service`]s into [`quadlet::File`]s.
 ///
-/// `volume_has_options` should be a map from volume [`Identifier`]s to whether the volume has any
-/// options set. It is used to determine whether to link to a [`quadlet::Volume`] in the created
-/// [`quadlet::Container`].
+/// `resource_names` is used to reference the compose file's networks and volumes from the created
+/// [`quadlet::Container`]s.
 ///
 /// If `pod_name` is [`Some`] and a service has any published ports, they are taken from the
 /// created [`quadlet::Container`] and added to `pod_ports`.
 ///
+/// If other services depend on a service with a [`Condition`], its [`quadlet::Container`] file is
+/// changed so that the condition is met when the service is active, see
+/// [`apply_dependency_conditions()`].
+///
+/// The `x-podlet` [`Extension`] of each service is applied to its [`quadlet::Container`] file.
+/// Options not set by the service or its extension are taken from `service_section`.
+///
 /// # Errors
 ///
-/// Returns an error if there was an error [adding](Unit::add_dependency()) a service
+/// Returns an error if there was an error reading a service's [`Extension`],
+/// [adding](Unit::add_dependency()) a service
 /// [`Dependency`](compose_spec::service::Dependency) to the [`Unit`], converting the
 /// [`Build`](compose_spec::service::Build) section into a [`quadlet::Build`] file, or converting
 /// the [`Service`] into a [`quadlet::Container`] file.
@@ -343,12 +609,26 @@ fn services_try_into_quadlet_files<'a>(
         quadlet,
         install,
     }: &'a GenericSections,
-    volume_has_options: &'a HashMap<Identifier, bool>,
+    service_section: &'a quadlet::Service,
+    resource_names: &'a ResourceNames,
     pod_name: Option<&'a str>,
     pod_ports: &'a mut Vec<String>,
     add_container_name: bool,
 ) -> impl Iterator<Item = color_eyre::Result<quadlet::File>> + 'a {
+    let dependency_conditions = dependency_conditions(&services);
+
     services.into_iter().flat_map(move |(name, mut service)| {
+        let conditions = dependency_conditions
+            .get(&name)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let extension =
+            match Extension::take_from(&mut service.extensions, format_args!("service `{name}`")) {
+                Ok(extension) => extension,
+                Err(error) => return iter::once(Err(error)).chain(None),
+            };
+
         if service.image.is_some() && service.build.is_some() {
             return iter::once(Err(eyre!(
                 "error converting service `{name}`: `image` and `build` cannot both be set"
@@ -372,30 +652,97 @@ fn services_try_into_quadlet_files<'a>(
                 quadlet: *quadlet,
                 service: quadlet::Service::default(),
                 install: install.clone(),
+                options: Vec::new(),
             })
         });
         if let Some(result @ Err(_)) = build {
             return iter::once(result).chain(None);
         }
 
+        let ident = name.clone();
         let container = service_try_into_quadlet_file(
             service,
             name,
             sections.clone(),
-            volume_has_options,
+            resource_names,
             pod_name,
             pod_ports,
             add_container_name,
-        );
+        )
+        .and_then(|mut container| {
+            apply_dependency_conditions(&mut container, conditions);
+            extension.apply_to(&mut container, format_args!("service `{ident}`"))?;
+            container.service.merge(service_section);
+            Ok(container)
+        });
 
         iter::once(container).chain(build)
     })
 }
 
+/// Returns a map of service [`Identifier`]s to the [`Condition`]s other services depend on them
+/// with.
+fn dependency_conditions(
+    services: &IndexMap<Identifier, Service>,
+) -> HashMap<Identifier, Vec<Condition>> {
+    let mut conditions: HashMap<Identifier, Vec<Condition>> = HashMap::new();
+    for service in services.values() {
+        if let ShortOrLong::Long(dependencies) = &service.depends_on {
+            for (ident, dependency) in dependencies {
+                conditions
+                    .entry(ident.clone())
+                    .or_default()
+                    .push(dependency.condition);
+            }
+        }
+    }
+    conditions
+}
+
+/// Change a service's container `file` so that the `conditions` other services depend on it with
+/// are met when its unit is active.
+///
+/// For [`Condition::ServiceHealthy`], `Notify=healthy` is set so the unit becomes active once the
+/// container's health check passes. For [`Condition::ServiceCompletedSuccessfully`], the unit is
+/// set to `Type=oneshot` with `RemainAfterExit=true` so it becomes active once the container has
+/// exited successfully.
+fn apply_dependency_conditions(file: &mut quadlet::File, conditions: &[Condition]) {
+    for condition in conditions {
+        match condition {
+            Condition::ServiceStarted => {}
+            Condition::ServiceHealthy => {
+                if let quadlet::Resource::Container(container) = &mut file.resource {
+                    if container.health_cmd.is_none() {
+                        eprintln!(
+                            "Warning: `{}` is depended on with `{condition}` but has no \
+                                health check, make sure its image defines one",
+                            file.name
+                        );
+                    }
+                    container.notify = Notify::Healthy;
+                }
+            }
+            Condition::ServiceCompletedSuccessfully => {
+                if file.service.restart.is_some_and(|restart| {
+                    !matches!(restart, RestartConfig::No | RestartConfig::OnFailure)
+                }) {
+                    eprintln!(
+                        "Warning: `{}` is depended on with `{condition}`, which uses \
+                            `Type=oneshot`, but systemd only supports `Restart=no` or \
+                            `Restart=on-failure` for oneshot services",
+                        file.name
+                    );
+                }
+                file.service.r#type = Some(ServiceType::Oneshot);
+                file.service.remain_after_exit = Some(true);
+            }
+        }
+    }
+}
+
 /// Attempt to convert a compose [`Service`] into a [`quadlet::File`].
 ///
-/// `volume_has_options` should be a map from volume [`Identifier`]s to whether the volume has any
-/// options set. It is used to determine whether to link to a [`quadlet::Volume`] in the created
+/// `resource_names` is used to reference the compose file's networks and volumes from the created
 /// [`quadlet::Container`].
 ///
 /// If `pod_name` is [`Some`] and the `service` has any published ports, they are taken from the
@@ -404,8 +751,8 @@ fn services_try_into_quadlet_files<'a>(
 /// # Errors
 ///
 /// Returns an error if there was an error [adding](Unit::add_dependency()) a service
-/// [`Dependency`](compose_spec::service::Dependency) to the [`Unit`] or converting the [`Service`]
-/// into a [`quadlet::Container`].
+/// [`Dependency`](compose_spec::service::Dependency) to the [`Unit`], converting the service's
+/// `deploy` section, or converting the [`Service`] into a [`quadlet::Container`].
 fn service_try_into_quadlet_file(
     mut service: Service,
     name: Identifier,
@@ -414,7 +761,7 @@ fn service_try_into_quadlet_file(
         quadlet,
         install,
     }: GenericSections,
-    volume_has_options: &HashMap<Identifier, bool>,
+    resource_names: &ResourceNames,
     pod_name: Option<&str>,
     pod_ports: &mut Vec<String>,
     add_container_name: bool,
@@ -438,7 +785,8 @@ fn service_try_into_quadlet_file(
 
     let global_args = GlobalArgs::from_compose(&mut service);
 
-    let restart = service.restart;
+    let mut service_section: quadlet::Service = service.restart.map(Into::into).unwrap_or_default();
+    service_section.merge(&deploy::take(&name, &mut service, &mut unit)?);
 
     let mut container = Container::try_from(service)
         .map(quadlet::Container::from)
@@ -450,20 +798,7 @@ fn service_try_into_quadlet_file(
         container.container_name = Some(name.to_string());
     }
 
-    // For each named volume, check to see if it has any options set.
-    // If it does, add `.volume` to the source to link this `.container` file to the generated
-    // `.volume` file.
-    for volume in &mut container.volume {
-        if let Some(Source::NamedVolume(source)) = &mut volume.source {
-            let volume_has_options = volume_has_options
-                .get(source.as_str())
-                .copied()
-                .unwrap_or_default();
-            if volume_has_options {
-                source.push_str(".volume");
-            }
-        }
-    }
+    resource_names.apply_to(&mut container);
 
     let name = if let Some(pod_name) = pod_name {
         container.pod = Some(format!("{pod_name}.pod"));
@@ -479,13 +814,16 @@ fn service_try_into_quadlet_file(
         resource: container.into(),
         globals: global_args.into(),
         quadlet,
-        service: restart.map(Into::into).unwrap_or_default(),
+        service: service_section,
         install,
+        options: Vec::new(),
     })
 }
 
 /// Attempt to convert compose [`Networks`] into an [`Iterator`] of [`quadlet::File`]s.
 ///
+/// External networks are filtered out as they are referenced by name.
+///
 /// # Errors
 ///
 /// The [`Iterator`] returns an [`Err`] if a [`Network`] could not be converted into a
@@ -498,19 +836,23 @@ fn networks_try_into_quadlet_files(
         install,
     }: &GenericSections,
 ) -> impl Iterator<Item = color_eyre::Result<quadlet::File>> {
-    networks.into_iter().map(move |(name, network)| {
+    networks.into_iter().filter_map(move |(name, network)| {
         let network = match network {
             Some(Resource::Compose(network)) => network,
             None => Network::default(),
-            Some(Resource::External { .. }) => {
-                bail!("external networks (`{name}`) are not supported");
+            // External networks are referenced by name and do not need a `.network` file.
+            Some(Resource::External { .. }) => return None,
+        };
+        let network = match quadlet::Network::try_from(network) {
+            Ok(network) => network,
+            Err(error) => {
+                return Some(Err(error).wrap_err_with(|| {
+                    format!("error converting network `{name}` into a Quadlet network")
+                }));
             }
         };
-        let network = quadlet::Network::try_from(network).wrap_err_with(|| {
-            format!("error converting network `{name}` into a Quadlet network")
-        })?;
 
-        Ok(quadlet::File {
+        Some(Ok(quadlet::File {
             name: name.into(),
             unit: unit.clone(),
             resource: network.into(),
@@ -518,14 +860,16 @@ fn networks_try_into_quadlet_files(
             quadlet: *quadlet,
             service: quadlet::Service::default(),
             install: install.clone(),
-        })
+            options: Vec::new(),
+        }))
     })
 }
 
 /// Attempt to convert compose [`Volumes`] into an [`Iterator`] of [`quadlet::File`]s.
 ///
 /// [`Volume`](compose_spec::Volume)s which are [empty](compose_spec::Volume::is_empty()) are
-/// filtered out as they do not need a `.volume` Quadlet file to define extra options.
+/// filtered out as they do not need a `.volume` Quadlet file to define extra options. External
+/// volumes are also filtered out as they are referenced by name.
 ///
 /// # Errors
 ///
@@ -554,11 +898,117 @@ fn volumes_try_into_quadlet_files(
                         quadlet: *quadlet,
                         service: quadlet::Service::default(),
                         install: install.clone(),
+                        options: Vec::new(),
                     })
             }),
-            Resource::External { .. } => {
-                Some(Err(eyre!("external volumes (`{name}`) are not supported")))
-            }
+            Resource::External { .. } => None,
         })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_names() -> color_eyre::Result<()> {
+        let networks: Networks = serde_yaml::from_str(
+            "
+            app:
+            shared:
+              external: true
+              name: proxy
+            ",
+        )?;
+        let volumes: Volumes = serde_yaml::from_str(
+            "
+            data:
+            options:
+              driver: local
+            external:
+              external: true
+            ",
+        )?;
+        let resource_names = ResourceNames::new(&networks, &volumes);
+
+        let mut container = quadlet::Container {
+            network: vec![
+                "app.network".into(),
+                "shared.network:alias=web".into(),
+                "host".into(),
+            ],
+            volume: vec![
+                "data:/data".parse()?,
+                "options:/options".parse()?,
+                "external:/external".parse()?,
+            ],
+            ..quadlet::Container::default()
+        };
+        resource_names.apply_to(&mut container);
+
+        assert_eq!(
+            container.network,
+            ["app.network", "proxy:alias=web", "host"]
+        );
+        let volumes: Vec<_> = container.volume.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            volumes,
+            [
+                "data:/data",
+                "options.volume:/options",
+                "external:/external"
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn dependency_conditions() -> color_eyre::Result<()> {
+        let compose: compose_spec::Compose = serde_yaml::from_str(
+            "
+            services:
+              web:
+                image: web
+                depends_on:
+                  db:
+                    condition: service_healthy
+                  migrate:
+                    condition: service_completed_successfully
+              db:
+                image: db
+                healthcheck:
+                  test: check
+              migrate:
+                image: migrate
+            ",
+        )?;
+        let files = parts_try_into_files(
+            compose.services,
+            Networks::default(),
+            Volumes::default(),
+            None,
+            GenericSections::default(),
+            &quadlet::Service::default(),
+            false,
+        )?;
+
+        let [
+            File::Quadlet(web),
+            File::Quadlet(db),
+            File::Quadlet(migrate),
+        ] = files.as_slice()
+        else {
+            bail!("expected three Quadlet files");
+        };
+        assert_eq!(web.unit.after, ["db.service", "migrate.service"]);
+        let quadlet::Resource::Container(db) = &db.resource else {
+            bail!("expected a container");
+        };
+        assert_eq!(db.notify, Notify::Healthy);
+        assert_eq!(migrate.service.r#type, Some(ServiceType::Oneshot));
+        assert_eq!(migrate.service.remain_after_exit, Some(true));
+
+        Ok(())
+    }
+}
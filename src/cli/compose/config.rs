@@ -0,0 +1,303 @@
+//! Conversion of compose [`Configs`](compose_spec::Configs) into bind mounts or Podman secrets, see
+//! [`Configs`].
+
+use std::{
+    collections::HashMap,
+    env, mem,
+    path::{Component, Path, PathBuf},
+};
+
+use color_eyre::eyre::{OptionExt, WrapErr, bail, eyre};
+use compose_spec::{
+    Identifier, Resource, Service, ShortOrLong,
+    config::{Config, Source},
+    service::{
+        ConfigOrSecret,
+        volumes::{self, AbsolutePath, HostPath, ShortOptions, ShortVolume},
+    },
+};
+
+use crate::cli::File;
+
+use super::extension;
+
+/// Compose configs, mapped to how they are mounted into containers.
+///
+/// File-based configs are bind mounted read-only at their target, or mounted as a Podman secret
+/// with the same name as the config. Configs with `content` or from an `environment` variable are
+/// written to a file next to the generated Quadlet files, which is then used as for file-based
+/// configs.
+#[derive(Debug, Default)]
+pub struct Configs {
+    /// Map of config names to their source.
+    sources: HashMap<Identifier, ConfigSource>,
+}
+
+/// How a config is mounted into a container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ConfigSource {
+    /// Bind mount the file at the host path.
+    HostPath(HostPath),
+
+    /// Mount the Podman secret with the given name.
+    Secret(String),
+}
+
+impl Configs {
+    /// Create [`Configs`] from the compose file's top-level `configs`.
+    ///
+    /// If `as_secrets` is `true`, configs are mounted as Podman secrets instead of being bind
+    /// mounted. The contents of configs with `content` or from an `environment` variable are
+    /// returned as [`File`]s which should be written next to the Quadlet files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a config is external and `as_secrets` is `false`, or if the environment
+    /// variable of a config is not set.
+    pub fn new(
+        configs: compose_spec::Configs,
+        as_secrets: bool,
+    ) -> color_eyre::Result<(Self, Vec<File>)> {
+        let mut files = Vec::new();
+        let sources = configs
+            .into_iter()
+            .map(|(name, config)| {
+                let Config {
+                    source,
+                    labels,
+                    extensions,
+                } = match config {
+                    Resource::Compose(config) => config,
+                    Resource::External { name: external } if as_secrets => {
+                        let secret = external.unwrap_or_else(|| name.to_string());
+                        return Ok((name, ConfigSource::Secret(secret)));
+                    }
+                    Resource::External { .. } => {
+                        return Err(eyre!(
+                            "external configs (`{name}`) are only supported as Podman secrets"
+                        ));
+                    }
+                };
+
+                if !labels.is_empty() {
+                    eprintln!(
+                        "Warning: `labels` of config `{name}` are not supported and were ignored"
+                    );
+                }
+                debug_assert!(extensions.is_empty(), "extensions are taken beforehand");
+
+                let path = match source {
+                    Source::File(path) => host_path(path),
+                    Source::Environment(var) => {
+                        let contents = env::var(&var).wrap_err_with(|| {
+                            format!(
+                                "error reading environment variable `{var}` for config `{name}`"
+                            )
+                        })?;
+                        files.push(File::Content {
+                            name: name.to_string(),
+                            contents,
+                            executable: false,
+                        });
+                        host_path(name.as_str().into())
+                    }
+                    Source::Content(contents) => {
+                        files.push(File::Content {
+                            name: name.to_string(),
+                            contents,
+                            executable: false,
+                        });
+                        host_path(name.as_str().into())
+                    }
+                }
+                .wrap_err_with(|| format!("invalid file path for config `{name}`"))?;
+
+                let source = if as_secrets {
+                    eprintln!(
+                        "Warning: config `{name}` is mounted as a Podman secret, create it with \
+                            `podman secret create {name} {}`",
+                        path.as_path().display()
+                    );
+                    ConfigSource::Secret(name.to_string())
+                } else {
+                    ConfigSource::HostPath(path)
+                };
+
+                Ok((name, source))
+            })
+            .collect::<color_eyre::Result<_>>()?;
+
+        Ok((Self { sources }, files))
+    }
+
+    /// Take the `configs` of a compose [`Service`] and add them to its `volumes` or `secrets`.
+    ///
+    /// Configs are mounted at their `target`, or `/{config}` if not set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a config is not defined in the compose file or its target is invalid.
+    pub fn apply_to(&self, service: &mut Service) -> color_eyre::Result<()> {
+        for config in mem::take(&mut service.configs) {
+            let ConfigOrSecret {
+                source,
+                target,
+                uid,
+                gid,
+                mode,
+                mut extensions,
+            } = config.into_long();
+
+            extension::ignore(&mut extensions, format_args!("config `{source}`"));
+
+            let target =
+                Path::new("/").join(target.unwrap_or_else(|| PathBuf::from(source.as_str())));
+
+            match self
+                .sources
+                .get(&source)
+                .ok_or_eyre(format!("config `{source}` is not defined"))?
+            {
+                ConfigSource::HostPath(path) => {
+                    if uid.is_some() || gid.is_some() || mode.is_some() {
+                        eprintln!(
+                            "Warning: `uid`, `gid`, and `mode` of config `{source}` cannot be \
+                                applied to a bind mount and were ignored"
+                        );
+                    }
+
+                    let container_path = AbsolutePath::new(target)
+                        .wrap_err_with(|| format!("invalid target for config `{source}`"))?;
+                    let options = ShortOptions {
+                        read_only: true,
+                        ..ShortOptions::new(volumes::Source::HostPath(path.clone()))
+                    };
+                    service.volumes.insert(ShortOrLong::Short(ShortVolume {
+                        container_path,
+                        options: Some(options),
+                    }));
+                }
+                ConfigSource::Secret(secret) => {
+                    let secret = Identifier::new(secret.as_str())
+                        .wrap_err_with(|| format!("invalid secret name for config `{source}`"))?;
+                    service.secrets.push(ShortOrLong::Long(ConfigOrSecret {
+                        source: secret,
+                        target: Some(target),
+                        uid,
+                        gid,
+                        mode,
+                        extensions,
+                    }));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Create a [`HostPath`] from a `path`, prefixing relative paths with `./`.
+///
+/// # Errors
+///
+/// Returns an error if the path is empty.
+fn host_path(path: PathBuf) -> color_eyre::Result<HostPath> {
+    let path = match path.components().next() {
+        None => bail!("path is empty"),
+        Some(Component::Normal(_)) => Path::new(".").join(path),
+        Some(_) => path,
+    };
+    HostPath::new(path).map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bind_mount() -> color_eyre::Result<()> {
+        let configs = serde_yaml::from_str(
+            "
+            app:
+              file: app.conf
+            inline:
+              content: test
+            ",
+        )?;
+        let (configs, files) = Configs::new(configs, false)?;
+        assert!(matches!(
+            files.as_slice(),
+            [File::Content { name, contents, .. }] if name == "inline" && contents == "test"
+        ));
+
+        let mut service: Service = serde_yaml::from_str(
+            "
+            configs:
+              - app
+              - source: inline
+                target: /etc/inline.conf
+            ",
+        )?;
+        configs.apply_to(&mut service)?;
+        assert!(service.configs.is_empty());
+
+        let volumes: Vec<String> = service
+            .volumes
+            .iter()
+            .filter_map(|volume| match volume {
+                ShortOrLong::Short(volume) => Some(volume.to_string()),
+                ShortOrLong::Long(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            volumes,
+            ["./app.conf:/app:ro", "./inline:/etc/inline.conf:ro"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn secret() -> color_eyre::Result<()> {
+        let configs = serde_yaml::from_str(
+            "
+            app:
+              file: ./app.conf
+            external:
+              external: true
+              name: other
+            ",
+        )?;
+        let (configs, files) = Configs::new(configs, true)?;
+        assert!(files.is_empty());
+
+        let mut service: Service = serde_yaml::from_str("configs: [app, external]")?;
+        configs.apply_to(&mut service)?;
+
+        let secrets: Vec<_> = service
+            .secrets
+            .into_iter()
+            .map(|secret| {
+                let secret = secret.into_long();
+                (secret.source.to_string(), secret.target)
+            })
+            .collect();
+        assert_eq!(
+            secrets,
+            [
+                (String::from("app"), Some(PathBuf::from("/app"))),
+                (String::from("other"), Some(PathBuf::from("/external"))),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn external_bind_mount() -> color_eyre::Result<()> {
+        let configs = serde_yaml::from_str("app: { external: true }")?;
+        assert!(Configs::new(configs, false).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,310 @@
+//! Conversion of the `deploy` section of compose services, see [`take()`].
+
+use color_eyre::eyre::{bail, ensure};
+use compose_spec::{
+    Identifier, Service, duration,
+    service::{
+        Deploy,
+        deploy::{
+            Mode, Resources, RestartCondition, RestartPolicy,
+            resources::{Limits, Reservations},
+        },
+    },
+};
+
+use crate::quadlet::{self, RestartConfig};
+
+/// Take the `deploy` section of a compose [`Service`].
+///
+/// `deploy.resources` limits and reservations are moved to the equivalent service-level fields
+/// (`cpus`, `mem_limit`, `pids_limit`, and `mem_reservation`). The `[Service]` section options
+/// converted from `deploy.restart_policy` are returned, its start limit is set in the `[Unit]`
+/// section `unit`.
+///
+/// `deploy.replicas` must be taken beforehand, see [`super::replicas::take()`].
+///
+/// # Errors
+///
+/// Returns an error if an unsupported `deploy` option is set, or if a resource is set both in
+/// `deploy.resources` and at the service level with different values.
+pub fn take(
+    name: &Identifier,
+    service: &mut Service,
+    unit: &mut quadlet::Unit,
+) -> color_eyre::Result<quadlet::Service> {
+    let Some(Deploy {
+        endpoint_mode,
+        labels,
+        mode,
+        placement,
+        replicas,
+        resources,
+        restart_policy,
+        rollback_config,
+        update_config,
+        extensions,
+    }) = service.deploy.take()
+    else {
+        return Ok(quadlet::Service::default());
+    };
+
+    debug_assert!(replicas.is_none(), "replicas are taken beforehand");
+    ensure!(
+        endpoint_mode.is_none(),
+        "`deploy.endpoint_mode` of service `{name}` is not supported, \
+            there is no service discovery outside of Swarm"
+    );
+    ensure!(
+        labels.is_empty(),
+        "`deploy.labels` of service `{name}` are not supported, use `labels` instead"
+    );
+    ensure!(
+        !matches!(mode, Some(Mode::Global)),
+        "`deploy.mode: global` of service `{name}` is not supported, \
+            a Quadlet container runs on a single host"
+    );
+    ensure!(
+        placement.is_none(),
+        "`deploy.placement` of service `{name}` is not supported, \
+            a Quadlet container runs on a single host"
+    );
+    ensure!(
+        rollback_config.is_none() && update_config.is_none(),
+        "`deploy.rollback_config` and `deploy.update_config` of service `{name}` are not \
+            supported, use `AutoUpdate=` with `podman auto-update` instead"
+    );
+    ensure!(
+        extensions.is_empty(),
+        "compose extensions in `deploy` of service `{name}` are not supported"
+    );
+
+    if let Some(resources) = resources {
+        apply_resources(name, service, resources)?;
+    }
+
+    restart_policy.map_or_else(
+        || Ok(quadlet::Service::default()),
+        |restart_policy| restart_policy_into_service(name, restart_policy, unit),
+    )
+}
+
+/// Move the limits and reservations of `deploy.resources` into the equivalent fields of the
+/// `service`.
+///
+/// # Errors
+///
+/// Returns an error if an unsupported option is set, or if a field is already set to a different
+/// value.
+fn apply_resources(
+    name: &Identifier,
+    service: &mut Service,
+    Resources {
+        limits,
+        reservations,
+        extensions,
+    }: Resources,
+) -> color_eyre::Result<()> {
+    ensure!(
+        extensions.is_empty(),
+        "compose extensions in `deploy.resources` of service `{name}` are not supported"
+    );
+
+    if let Some(Limits {
+        cpus,
+        memory,
+        pids,
+        extensions,
+    }) = limits
+    {
+        ensure!(
+            extensions.is_empty(),
+            "compose extensions in `deploy.resources.limits` of service `{name}` are not supported"
+        );
+        merge(name, "cpus", &mut service.cpus, cpus)?;
+        merge(name, "mem_limit", &mut service.mem_limit, memory)?;
+        merge(name, "pids_limit", &mut service.pids_limit, pids)?;
+    }
+
+    if let Some(Reservations {
+        cpus,
+        memory,
+        devices,
+        generic_resources,
+        extensions,
+    }) = reservations
+    {
+        ensure!(
+            cpus.is_none(),
+            "`deploy.resources.reservations.cpus` of service `{name}` is not supported, \
+                use `cpu_shares` to set the container's relative CPU weight instead"
+        );
+        ensure!(
+            devices.is_empty(),
+            "`deploy.resources.reservations.devices` of service `{name}` are not supported, \
+                use `devices` instead"
+        );
+        ensure!(
+            generic_resources.is_empty(),
+            "`deploy.resources.reservations.generic_resources` of service `{name}` are not \
+                supported"
+        );
+        ensure!(
+            extensions.is_empty(),
+            "compose extensions in `deploy.resources.reservations` of service `{name}` are not \
+                supported"
+        );
+        merge(
+            name,
+            "mem_reservation",
+            &mut service.mem_reservation,
+            memory,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Set the service-level `field` to the `value` from `deploy.resources`.
+///
+/// # Errors
+///
+/// Returns an error if the `field` is already set to a different value.
+fn merge<T: PartialEq>(
+    name: &Identifier,
+    key: &str,
+    field: &mut Option<T>,
+    value: Option<T>,
+) -> color_eyre::Result<()> {
+    match (field.as_ref(), value) {
+        (Some(current), Some(value)) if *current != value => bail!(
+            "`{key}` and its equivalent in `deploy.resources` of service `{name}` are set to \
+                different values"
+        ),
+        (None, value @ Some(_)) => *field = value,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Convert `deploy.restart_policy` into `[Service]` section options.
+///
+/// `max_attempts` and `window` are set as the start limit of the `[Unit]` section `unit`. The
+/// initial start counts towards the limit, so the burst is one more than `max_attempts`. Without a
+/// `window` the attempts are limited over the whole lifetime of the service manager.
+///
+/// # Errors
+///
+/// Returns an error if an extension is set.
+fn restart_policy_into_service(
+    name: &Identifier,
+    RestartPolicy {
+        condition,
+        delay,
+        max_attempts,
+        window,
+        extensions,
+    }: RestartPolicy,
+    unit: &mut quadlet::Unit,
+) -> color_eyre::Result<quadlet::Service> {
+    ensure!(
+        extensions.is_empty(),
+        "compose extensions in `deploy.restart_policy` of service `{name}` are not supported"
+    );
+
+    let restart = match condition.unwrap_or_default() {
+        RestartCondition::None => RestartConfig::No,
+        RestartCondition::OnFailure => RestartConfig::OnFailure,
+        RestartCondition::Any => RestartConfig::Always,
+    };
+
+    if let Some(max_attempts) = max_attempts {
+        unit.start_limit_burst = Some(max_attempts.saturating_add(1));
+        unit.start_limit_interval_sec =
+            Some(window.map_or_else(|| String::from("infinity"), duration::to_string));
+    } else if window.is_some() {
+        eprintln!(
+            "Warning: `deploy.restart_policy.window` of service `{name}` was ignored \
+                as `max_attempts` is not set"
+        );
+    }
+
+    Ok(quadlet::Service {
+        restart: Some(restart),
+        restart_sec: delay.map(duration::to_string),
+        ..quadlet::Service::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use compose_spec::service::{ByteValue, Limit};
+
+    use super::*;
+
+    #[test]
+    fn resources_and_restart_policy() -> color_eyre::Result<()> {
+        let name = Identifier::new("test")?;
+        let mut service: Service = serde_yaml::from_str(
+            "
+            mem_limit: 1g
+            deploy:
+              resources:
+                limits:
+                  cpus: 0.5
+                  memory: 1g
+                  pids: 100
+                reservations:
+                  memory: 512m
+              restart_policy:
+                condition: on-failure
+                delay: 5s
+                max_attempts: 3
+            ",
+        )?;
+
+        let mut unit = quadlet::Unit::default();
+        let service_section = take(&name, &mut service, &mut unit)?;
+        assert!(service.deploy.is_none());
+        assert_eq!(service.cpus, Some(0.5.try_into()?));
+        assert_eq!(service.mem_limit, Some(ByteValue::Gigabytes(1)));
+        assert_eq!(service.pids_limit, Some(Limit::Value(100)));
+        assert_eq!(service.mem_reservation, Some(ByteValue::Megabytes(512)));
+        assert_eq!(
+            service_section,
+            quadlet::Service {
+                restart: Some(RestartConfig::OnFailure),
+                restart_sec: Some(String::from("5s")),
+                ..quadlet::Service::default()
+            }
+        );
+        assert_eq!(unit.start_limit_burst, Some(4));
+        assert_eq!(unit.start_limit_interval_sec.as_deref(), Some("infinity"));
+
+        let mut service: Service =
+            serde_yaml::from_str("deploy: { restart_policy: { max_attempts: 2, window: 2m } }")?;
+        let mut unit = quadlet::Unit::default();
+        take(&name, &mut service, &mut unit)?;
+        assert_eq!(unit.start_limit_burst, Some(3));
+        assert_eq!(unit.start_limit_interval_sec.as_deref(), Some("2m"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported() -> color_eyre::Result<()> {
+        let name = Identifier::new("test")?;
+        for deploy in [
+            "deploy: { mode: global }",
+            "deploy: { placement: { constraints: [node.role==manager] } }",
+            "{ mem_limit: 1g, deploy: { resources: { limits: { memory: 2g } } } }",
+        ] {
+            let mut service: Service = serde_yaml::from_str(deploy)?;
+            assert!(
+                take(&name, &mut service, &mut quadlet::Unit::default()).is_err(),
+                "{deploy}"
+            );
+        }
+
+        Ok(())
+    }
+}
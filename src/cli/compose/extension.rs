@@ -0,0 +1,480 @@
+//! The `x-podlet` compose extension, see [`Extension`].
+
+use std::{collections::HashSet, fmt::Display, iter, mem};
+
+use color_eyre::eyre::{WrapErr, ensure};
+use compose_spec::Extensions;
+use indexmap::IndexMap;
+use serde::{
+    Deserialize, Deserializer,
+    de::{DeserializeOwned, Error, Unexpected},
+};
+use serde_yaml::{Mapping, Value};
+
+use crate::{
+    cli::add_unit_options,
+    quadlet::{
+        self, GenericSections, Globals, Install, JoinOption, Quadlet, Resource, ResourceKind, Unit,
+        UnitOption,
+    },
+    serde::quadlet::{Entry, QuoteWhitespace, Section, option_names},
+};
+
+/// Key of the [`Extension`] in compose extensions.
+pub const KEY: &str = "x-podlet";
+
+/// Quadlet options which cannot be derived from a compose file, set with the `x-podlet` compose
+/// extension.
+///
+/// May be set at the top-level of the compose file, where it applies to all generated Quadlet
+/// files, or on a service, where it applies to the service's `.container` file and takes
+/// precedence.
+///
+/// Each section is a map of Quadlet option names to values. On a service, the `container` section
+/// sets options of the `[Container]` section, replacing the values converted from the service.
+/// Options which podlet does not otherwise support are set with a list of `Section.Key=Value`
+/// strings in `options`, e.g.
+///
+/// ```yaml
+/// x-podlet:
+///   unit:
+///     Description: My service
+///   container:
+///     Notify: healthy
+///   install:
+///     WantedBy:
+///       - default.target
+///   options:
+///     - Service.KillMode=mixed
+/// ```
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Extension {
+    /// The `[Unit]` section.
+    #[serde(deserialize_with = "section")]
+    pub unit: Unit,
+
+    /// The `[Quadlet]` section.
+    #[serde(deserialize_with = "section")]
+    pub quadlet: Quadlet,
+
+    /// The `[Service]` section.
+    #[serde(deserialize_with = "section")]
+    pub service: quadlet::Service,
+
+    /// Options of the `[Container]` section, only used on services.
+    #[serde(deserialize_with = "container_section")]
+    pub container: IndexMap<String, Vec<String>>,
+
+    /// The `[Install]` section.
+    #[serde(deserialize_with = "section")]
+    pub install: Install,
+
+    /// Additional options for any section.
+    pub options: Vec<UnitOption>,
+}
+
+impl Extension {
+    /// Remove and deserialize the `x-podlet` extension from `extensions`.
+    ///
+    /// All other extensions are removed and a warning is printed for each one. `context` is used
+    /// in the warnings and errors, e.g. "service `web`".
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `x-podlet` extension could not be deserialized.
+    pub fn take_from(
+        extensions: &mut Extensions,
+        context: impl Display,
+    ) -> color_eyre::Result<Self> {
+        let extension = extensions
+            .shift_remove(KEY)
+            .map(serde_yaml::from_value)
+            .transpose()
+            .wrap_err_with(|| format!("error reading `{KEY}` of {context}"))?
+            .unwrap_or_default();
+
+        ignore(extensions, context);
+
+        Ok(extension)
+    }
+
+    /// Returns `true` if all sections are empty.
+    pub fn is_empty(&self) -> bool {
+        let Self {
+            unit,
+            quadlet,
+            service,
+            container,
+            install,
+            options,
+        } = self;
+
+        unit.is_empty()
+            && quadlet.is_empty()
+            && service.is_empty()
+            && container.is_empty()
+            && install.is_empty()
+            && options.is_empty()
+    }
+
+    /// Merge the extension's [`GenericSections`] into `sections`.
+    ///
+    /// Options already set in `sections` take precedence. The `[Service]` section and additional
+    /// options are returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `container` section is set, as it only applies to services.
+    pub fn merge_into(
+        self,
+        sections: &mut GenericSections,
+    ) -> color_eyre::Result<(quadlet::Service, Vec<UnitOption>)> {
+        let Self {
+            unit,
+            quadlet,
+            service,
+            container,
+            install,
+            options,
+        } = self;
+
+        ensure!(
+            container.is_empty(),
+            "`container` can only be set in the `{KEY}` extension of a service"
+        );
+
+        sections.unit.merge(unit);
+        sections.quadlet.merge(quadlet);
+        sections.install.merge(install);
+
+        Ok((service, options))
+    }
+
+    /// Apply the extension to a Quadlet `file`.
+    ///
+    /// Options set in the extension take precedence over those already set in the `file`.
+    /// `context` is used in errors and in warnings about the additional options, e.g.
+    /// "service `web`".
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `container` section is set and the `file` is not a `.container`
+    /// file, or if its options could not be set.
+    pub fn apply_to(
+        self,
+        file: &mut quadlet::File,
+        context: impl Display,
+    ) -> color_eyre::Result<()> {
+        let Self {
+            mut unit,
+            mut quadlet,
+            mut service,
+            container,
+            mut install,
+            options,
+        } = self;
+
+        if !container.is_empty() {
+            set_container_options(file, container).wrap_err_with(|| {
+                format!("error setting `container` options from the `{KEY}` extension of {context}")
+            })?;
+        }
+
+        unit.merge(mem::take(&mut file.unit));
+        file.unit = unit;
+
+        quadlet.merge(file.quadlet);
+        file.quadlet = quadlet;
+
+        service.merge(&file.service);
+        file.service = service;
+
+        install.merge(mem::take(&mut file.install));
+        file.install = install;
+
+        add_unit_options(
+            iter::once(file),
+            &options,
+            format_args!("the `{KEY}` extension of {context}"),
+        );
+
+        Ok(())
+    }
+}
+
+/// Set the `options` of the `[Container]` section of a `.container` `file`.
+///
+/// The values replace any already set for each option.
+///
+/// # Errors
+///
+/// Returns an error if the `file` is not a `.container` file or if the options have invalid
+/// values.
+fn set_container_options(
+    file: &mut quadlet::File,
+    options: IndexMap<String, Vec<String>>,
+) -> color_eyre::Result<()> {
+    ensure!(
+        matches!(file.resource, Resource::Container(_)),
+        "the `[Container]` section can only be set for a `.container` file"
+    );
+
+    // Round-trip through the Quadlet format so that each option is reset with an empty value
+    // before setting the new values.
+    let current =
+        crate::serde::quadlet::to_string((&file.resource, &file.globals), &HashSet::new())?;
+    let mut entries: Vec<Entry> = crate::serde::quadlet::from_str(&current)?
+        .into_iter()
+        .flat_map(|section| section.entries)
+        .collect();
+    entries.extend(container_entries(options));
+
+    let section = Section {
+        name: ResourceKind::Container.section_name().to_owned(),
+        line: 0,
+        entries,
+    };
+    let (resource, globals) = Resource::deserialize_section(ResourceKind::Container, &section)?;
+    file.resource = resource;
+    file.globals = globals;
+
+    Ok(())
+}
+
+/// Convert `[Container]` section `options` into Quadlet file [`Entry`]s.
+///
+/// Each option is reset with an empty value before its values are set.
+fn container_entries(options: IndexMap<String, Vec<String>>) -> impl Iterator<Item = Entry> {
+    options.into_iter().flat_map(|(key, values)| {
+        let reset = Entry {
+            key: key.clone(),
+            value: String::new(),
+            line: 0,
+        };
+        let values = values.into_iter().map(move |value| Entry {
+            key: key.clone(),
+            value,
+            line: 0,
+        });
+        iter::once(reset).chain(values)
+    })
+}
+
+/// Remove all `extensions`, printing a warning for each one.
+///
+/// `context` is used in the warnings, e.g. "network `app`".
+pub fn ignore(extensions: &mut Extensions, context: impl Display) {
+    for (key, _) in extensions.drain(..) {
+        eprintln!(
+            "Warning: compose extension `{key}` of {context} is not supported and was ignored"
+        );
+    }
+}
+
+/// Deserialize a Quadlet section from a map of Quadlet option names to values.
+///
+/// # Errors
+///
+/// Returns an error if an option is not one of the section's options.
+fn section<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let section = Mapping::deserialize(deserializer)?;
+
+    let options = option_names::<T>();
+    for option in section.keys() {
+        match option.as_str() {
+            Some(option) if options.contains(&option) => {}
+            Some(option) => return Err(D::Error::unknown_field(option, options)),
+            None => {
+                return Err(D::Error::invalid_type(
+                    Unexpected::Other("non-string key"),
+                    &"a Quadlet option name",
+                ));
+            }
+        }
+    }
+
+    serde_yaml::from_value(Value::Mapping(section)).map_err(D::Error::custom)
+}
+
+/// Deserialize the options of a `[Container]` section from a map of Quadlet option names to a
+/// value or list of values.
+///
+/// The values are converted to their Quadlet form, i.e. values of [`JoinOption`]s are quoted if
+/// they contain whitespace, and `null` only resets the option.
+///
+/// # Errors
+///
+/// Returns an error if an option is not a [`quadlet::Container`] or [`Globals`] option, or if a
+/// value is invalid for its option.
+fn container_section<'de, D>(deserializer: D) -> Result<IndexMap<String, Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let section = IndexMap::<String, Value>::deserialize(deserializer)?;
+
+    let mut options = option_names::<quadlet::Container>().to_vec();
+    options.extend_from_slice(option_names::<Globals>());
+
+    let section = section
+        .into_iter()
+        .map(|(key, value)| {
+            if !options.contains(&key.as_str()) {
+                return Err(D::Error::custom(format_args!(
+                    "unknown option `{key}=` for the `[Container]` section"
+                )));
+            }
+
+            let values = match value {
+                Value::Null => Vec::new(),
+                Value::Sequence(values) => values,
+                value => vec![value],
+            };
+            let join = key.parse::<JoinOption>().is_ok();
+            let values = values
+                .into_iter()
+                .map(|value| {
+                    let value = match value {
+                        Value::Bool(value) => value.to_string(),
+                        Value::Number(value) => value.to_string(),
+                        Value::String(value) => value,
+                        _ => {
+                            return Err(D::Error::invalid_type(
+                                Unexpected::Other("non-scalar value"),
+                                &"a string, number, or boolean",
+                            ));
+                        }
+                    };
+                    Ok(if join {
+                        QuoteWhitespace(&value).to_string()
+                    } else {
+                        value
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+
+            Ok((key, values))
+        })
+        .collect::<Result<IndexMap<_, _>, _>>()?;
+
+    // Check that the values are valid for their options.
+    let check = Section {
+        name: ResourceKind::Container.section_name().to_owned(),
+        line: 0,
+        entries: container_entries(section.clone()).collect(),
+    };
+    Resource::deserialize_section(ResourceKind::Container, &check).map_err(D::Error::custom)?;
+
+    Ok(section)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_from() -> color_eyre::Result<()> {
+        let mut extensions: Extensions = serde_yaml::from_str(
+            "
+            x-podlet:
+              unit:
+                Description: test
+                Wants: [other.service]
+              quadlet:
+                DefaultDependencies: false
+              service:
+                Restart: on-failure
+              install:
+                WantedBy: [default.target]
+              options:
+                - Service.KillMode=mixed
+            x-other: true
+            ",
+        )?;
+
+        let extension = Extension::take_from(&mut extensions, "test")?;
+        assert!(extensions.is_empty());
+        assert_eq!(extension.unit.description.as_deref(), Some("test"));
+        assert_eq!(extension.unit.wants, ["other.service"]);
+        assert!(!extension.quadlet.default_dependencies);
+        assert!(!extension.service.is_empty());
+        assert_eq!(extension.install.wanted_by, ["default.target"]);
+        assert_eq!(extension.options, ["Service.KillMode=mixed".parse()?]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn container_section() -> color_eyre::Result<()> {
+        let mut extensions: Extensions = serde_yaml::from_str(
+            "
+            x-podlet:
+              container:
+                Notify: healthy
+                Environment: [FOO=bar, 'BAZ=a b']
+                ReadOnly: true
+                Label: null
+            ",
+        )?;
+        let extension = Extension::take_from(&mut extensions, "test")?;
+
+        let mut file = quadlet::File::deserialize_from_quadlet(
+            String::from("test"),
+            "[Container]\nImage=test\nEnvironment=ONE=1\nLabel=app=test\nPodmanArgs=--tty\n",
+        )?;
+        extension.apply_to(&mut file, "test")?;
+
+        assert_eq!(
+            crate::serde::quadlet::to_string(&file, &HashSet::new())?,
+            "[Container]\n\
+            Environment=FOO=bar\n\
+            Environment=\"BAZ=a b\"\n\
+            Image=test\n\
+            Notify=healthy\n\
+            PodmanArgs=--tty\n\
+            ReadOnly=true\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn container_section_errors() -> color_eyre::Result<()> {
+        for extension in [
+            "x-podlet: { container: { Imag: test } }",
+            "x-podlet: { container: { Notify: maybe } }",
+            "x-podlet: { container: { Environment: [{ FOO: bar }] } }",
+        ] {
+            let mut extensions: Extensions = serde_yaml::from_str(extension)?;
+            assert!(
+                Extension::take_from(&mut extensions, "test").is_err(),
+                "{extension}"
+            );
+        }
+
+        let mut extensions: Extensions =
+            serde_yaml::from_str("x-podlet: { container: { ReadOnly: true } }")?;
+        let extension = Extension::take_from(&mut extensions, "test")?;
+        assert!(
+            extension
+                .merge_into(&mut GenericSections::default())
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_option() -> color_eyre::Result<()> {
+        let mut extensions: Extensions =
+            serde_yaml::from_str("x-podlet: { unit: { Descripton: test } }")?;
+
+        assert!(Extension::take_from(&mut extensions, "test").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+//! Merging of compose [`Service`]s for `extends`, see [`merge_services()`].
+//!
+//! Follows the [compose-spec merge rules](https://github.com/compose-spec/compose-spec/blob/main/13-merge.md):
+//! mappings are merged, sequences are appended, and other values are overridden.
+
+use std::mem;
+
+use color_eyre::eyre::WrapErr;
+use compose_spec::{
+    ItemOrList, ListOrMap, Service, ShortOrLong,
+    service::{EnvFile, NetworkConfig, ports, volumes},
+};
+use serde_yaml::Value;
+
+/// Service fields which are overridden instead of appended to, even though they are sequences.
+const OVERRIDE_FIELDS: &[&str] = &["command", "entrypoint", "test"];
+
+/// Merge `service` into its `base` service.
+///
+/// Both services are converted into their long syntax so they can be merged. Afterwards, ports
+/// and volumes are converted back into their short syntax where possible.
+///
+/// # Errors
+///
+/// Returns an error if a map key is invalid or the merged service is invalid.
+pub fn merge_services(base: Service, service: Service) -> color_eyre::Result<Service> {
+    let base = service_into_value(base)?;
+    let service = service_into_value(service)?;
+
+    let mut service: Service =
+        serde_yaml::from_value(merge(base, service, None)).wrap_err("merged service is invalid")?;
+
+    service.ports = ports::into_short_iter(mem::take(&mut service.ports))
+        .map(|port| port.map_or_else(ShortOrLong::Long, ShortOrLong::Short))
+        .collect();
+    service.volumes = volumes::into_short_iter(mem::take(&mut service.volumes))
+        .map(|volume| volume.map_or_else(ShortOrLong::Long, ShortOrLong::Short))
+        .collect();
+
+    Ok(service)
+}
+
+/// Convert a [`Service`] into a [`Value`], normalizing fields which may be in a short or long
+/// syntax into their long syntax.
+///
+/// # Errors
+///
+/// Returns an error if a map key is invalid.
+fn service_into_value(mut service: Service) -> color_eyre::Result<Value> {
+    for list_or_map in [
+        &mut service.environment,
+        &mut service.labels,
+        &mut service.annotations,
+        &mut service.sysctls,
+    ] {
+        *list_or_map = ListOrMap::Map(mem::take(list_or_map).into_map()?);
+    }
+
+    service.build = service
+        .build
+        .map(|build| ShortOrLong::Long(build.into_long()));
+    service.depends_on = ShortOrLong::Long(mem::take(&mut service.depends_on).into_long());
+    service.env_file = service
+        .env_file
+        .map(|env_file| EnvFile::List(env_file.into_list()));
+    service.ports = ports::into_long_iter(mem::take(&mut service.ports))
+        .map(ShortOrLong::Long)
+        .collect();
+    service.volumes = volumes::into_long_iter(mem::take(&mut service.volumes))
+        .map(ShortOrLong::Long)
+        .collect();
+    for configs_or_secrets in [&mut service.configs, &mut service.secrets] {
+        *configs_or_secrets = mem::take(configs_or_secrets)
+            .into_iter()
+            .map(|config_or_secret| ShortOrLong::Long(config_or_secret.into_long()))
+            .collect();
+    }
+
+    service.dns = service.dns.map(|dns| ItemOrList::List(dns.into_list()));
+    service.dns_search = service
+        .dns_search
+        .map(|dns_search| ItemOrList::List(dns_search.into_list()));
+    service.tmpfs = service
+        .tmpfs
+        .map(|tmpfs| ItemOrList::List(tmpfs.into_list()));
+
+    if let Some(NetworkConfig::Networks(networks)) = &mut service.network_config {
+        *networks = ShortOrLong::Long(mem::take(networks).into_long());
+    }
+
+    serde_yaml::to_value(service).map_err(Into::into)
+}
+
+/// Merge `value` into `base`.
+///
+/// `field` is the name of the field the values are for, if any.
+fn merge(base: Value, value: Value, field: Option<&str>) -> Value {
+    match (base, value) {
+        (Value::Mapping(mut base), Value::Mapping(value)) => {
+            for (key, value) in value {
+                if let Some(base) = base.get_mut(&key) {
+                    *base = merge(mem::take(base), value, key.as_str());
+                } else {
+                    base.insert(key, value);
+                }
+            }
+            Value::Mapping(base)
+        }
+        (Value::Sequence(mut base), Value::Sequence(value))
+            if field.is_none_or(|field| !OVERRIDE_FIELDS.contains(&field)) =>
+        {
+            let unique_fields = field.and_then(unique_fields);
+            for item in value {
+                if let Some(fields) = unique_fields {
+                    let key = unique_key(&item, fields);
+                    base.retain(|base| unique_key(base, fields) != key);
+                } else if base.contains(&item) {
+                    continue;
+                }
+                base.push(item);
+            }
+            Value::Sequence(base)
+        }
+        (_, value) => value,
+    }
+}
+
+/// Returns the fields which uniquely identify the items of a sequence `field`.
+///
+/// Items with the same unique fields are overridden instead of appended.
+fn unique_fields(field: &str) -> Option<&'static [&'static str]> {
+    match field {
+        "ports" => Some(&["host_ip", "target", "published", "protocol"]),
+        "volumes" => Some(&["target"]),
+        "configs" | "secrets" => Some(&["source", "target"]),
+        _ => None,
+    }
+}
+
+/// Returns the values of the unique `fields` of an `item`.
+fn unique_key<'a>(item: &'a Value, fields: &[&str]) -> Vec<Option<&'a Value>> {
+    fields.iter().map(|field| item.get(field)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use compose_spec::Identifier;
+
+    use super::*;
+
+    #[test]
+    fn merge_rules() -> color_eyre::Result<()> {
+        let base: Service = serde_yaml::from_str(
+            "
+            image: base
+            command: [base]
+            environment:
+              - ONE=1
+              - TWO=2
+            ports:
+              - 8080:80
+            volumes:
+              - data:/data
+              - ./config:/config
+            cap_add: [NET_ADMIN]
+            ",
+        )?;
+        let service: Service = serde_yaml::from_str(
+            "
+            command: [service]
+            environment:
+              TWO: two
+            ports:
+              - 8443:443
+            volumes:
+              - other:/data
+            cap_add: [NET_ADMIN, SYS_ADMIN]
+            ",
+        )?;
+        let expected: Service = serde_yaml::from_str(
+            "
+            image: base
+            command: [service]
+            environment:
+              ONE: 1
+              TWO: two
+            ports:
+              - 8080:80
+              - 8443:443
+            volumes:
+              - ./config:/config
+              - other:/data
+            cap_add: [NET_ADMIN, SYS_ADMIN]
+            ",
+        )?;
+
+        let merged = merge_services(base, service)?;
+        assert_eq!(service_into_value(merged)?, service_into_value(expected)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn depends_on() -> color_eyre::Result<()> {
+        let base: Service = serde_yaml::from_str("depends_on: [one]")?;
+        let service: Service = serde_yaml::from_str("depends_on: [two]")?;
+
+        let merged = merge_services(base, service)?.depends_on.into_long();
+        assert_eq!(
+            merged.keys().collect::<Vec<_>>(),
+            [&Identifier::new("one")?, &Identifier::new("two")?]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+//! Filtering of compose services by their `profiles`, see [`apply()`].
+
+use std::{collections::HashSet, env, mem};
+
+use color_eyre::eyre::ensure;
+use compose_spec::{
+    Compose, Identifier, Service, ShortOrLong,
+    service::{
+        NetworkConfig,
+        volumes::{Mount, ShortOptions, ShortVolume, Source, mount},
+    },
+};
+use indexmap::IndexMap;
+
+/// Environment variable with a comma separated list of active profiles, used if no profiles are
+/// given.
+pub const ENV_VAR: &str = "COMPOSE_PROFILES";
+
+/// Remove the services of the `compose` file which are not enabled by the active `profiles`.
+///
+/// If `profiles` is empty, the active profiles are read from the [`ENV_VAR`] environment variable.
+/// Services without `profiles` are always enabled, and the profile "*" enables all services.
+///
+/// Networks and volumes which were only used by removed services are also removed.
+///
+/// # Errors
+///
+/// Returns an error if an enabled service depends on a removed service.
+pub fn apply(compose: &mut Compose, mut profiles: Vec<String>) -> color_eyre::Result<()> {
+    if profiles.is_empty() {
+        if let Ok(var) = env::var(ENV_VAR) {
+            profiles = var
+                .split(',')
+                .map(str::trim)
+                .filter(|profile| !profile.is_empty())
+                .map(Into::into)
+                .collect();
+        }
+    }
+    let all = profiles.iter().any(|profile| profile == "*");
+
+    let (enabled, disabled): (IndexMap<_, _>, IndexMap<_, _>) = mem::take(&mut compose.services)
+        .into_iter()
+        .partition(|(_, service)| {
+            all || service.profiles.is_empty()
+                || service
+                    .profiles
+                    .iter()
+                    .any(|profile| profiles.iter().any(|active| active == profile.as_str()))
+        });
+
+    for (name, service) in &enabled {
+        for dependency in depends_on(service) {
+            ensure!(
+                !disabled.contains_key(dependency),
+                "service `{name}` depends on service `{dependency}`, \
+                    which is not enabled by the active profiles"
+            );
+        }
+    }
+
+    let mut networks = HashSet::new();
+    let mut volumes = HashSet::new();
+    for service in disabled.values() {
+        networks.extend(service_networks(service));
+        volumes.extend(service_volumes(service));
+    }
+    for service in enabled.values() {
+        for network in service_networks(service) {
+            networks.remove(network);
+        }
+        for volume in service_volumes(service) {
+            volumes.remove(volume);
+        }
+    }
+    compose
+        .networks
+        .retain(|name, _| !networks.contains(name.as_str()));
+    compose
+        .volumes
+        .retain(|name, _| !volumes.contains(name.as_str()));
+
+    compose.services = enabled;
+
+    Ok(())
+}
+
+/// Returns the names of the services the `service` depends on.
+fn depends_on(service: &Service) -> Box<dyn Iterator<Item = &Identifier> + '_> {
+    match &service.depends_on {
+        ShortOrLong::Short(services) => Box::new(services.iter()),
+        ShortOrLong::Long(services) => Box::new(services.keys()),
+    }
+}
+
+/// Returns the names of the networks the `service` is connected to.
+///
+/// Services without networks are connected to the "default" network.
+fn service_networks(service: &Service) -> Vec<&str> {
+    match &service.network_config {
+        None => vec!["default"],
+        Some(NetworkConfig::Networks(ShortOrLong::Short(networks))) => {
+            networks.iter().map(Identifier::as_str).collect()
+        }
+        Some(NetworkConfig::Networks(ShortOrLong::Long(networks))) => {
+            networks.keys().map(Identifier::as_str).collect()
+        }
+        Some(NetworkConfig::NetworkMode(_)) => Vec::new(),
+    }
+}
+
+/// Returns the names of the named volumes the `service` mounts.
+fn service_volumes(service: &Service) -> impl Iterator<Item = &str> {
+    service.volumes.iter().filter_map(|volume| match volume {
+        ShortOrLong::Short(ShortVolume {
+            options:
+                Some(ShortOptions {
+                    source: Source::Volume(source),
+                    ..
+                }),
+            ..
+        })
+        | ShortOrLong::Long(Mount::Volume(mount::Volume {
+            source: Some(source),
+            ..
+        })) => Some(source.as_str()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPOSE: &str = "
+        services:
+          app:
+            image: app
+            volumes:
+              - data:/data
+          debug:
+            image: debug
+            profiles: [debug]
+            networks: [debug]
+            volumes:
+              - data:/data
+              - tools:/tools
+        networks:
+          debug:
+        volumes:
+          data:
+          tools:
+        ";
+
+    #[test]
+    fn inactive_profile() -> color_eyre::Result<()> {
+        let mut compose: Compose = serde_yaml::from_str(COMPOSE)?;
+        apply(&mut compose, vec![String::from("other")])?;
+
+        assert_eq!(compose.services.keys().collect::<Vec<_>>(), ["app"]);
+        assert!(compose.networks.is_empty());
+        assert_eq!(compose.volumes.keys().collect::<Vec<_>>(), ["data"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn active_profile() -> color_eyre::Result<()> {
+        let mut compose: Compose = serde_yaml::from_str(COMPOSE)?;
+        apply(&mut compose, vec![String::from("debug")])?;
+
+        assert_eq!(compose.services.len(), 2);
+        assert_eq!(compose.networks.len(), 1);
+        assert_eq!(compose.volumes.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn disabled_dependency() -> color_eyre::Result<()> {
+        let mut compose: Compose = serde_yaml::from_str(
+            "
+            services:
+              app:
+                image: app
+                depends_on: [debug]
+              debug:
+                image: debug
+                profiles: [debug]
+            ",
+        )?;
+        assert!(apply(&mut compose, vec![String::from("other")]).is_err());
+
+        Ok(())
+    }
+}
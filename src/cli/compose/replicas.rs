@@ -0,0 +1,224 @@
+//! Conversion of compose services with multiple replicas into template units, see [`take()`] and
+//! [`apply()`].
+
+use std::{collections::HashMap, mem};
+
+use color_eyre::eyre::ensure;
+use compose_spec::{
+    Identifier, Service,
+    service::{Deploy, ports},
+};
+
+use crate::{
+    cli::File,
+    quadlet::{self, Unit},
+};
+
+/// Take the number of replicas from the `scale` or `deploy.replicas` fields of a compose
+/// [`Service`].
+///
+/// `deploy` is set to [`None`] if it is empty afterwards. A warning is printed if the service
+/// publishes host ports which would collide between replicas.
+///
+/// # Errors
+///
+/// Returns an error if both `scale` and `deploy.replicas` are set to different values, or if the
+/// number of replicas is 0.
+pub fn take(name: &Identifier, service: &mut Service) -> color_eyre::Result<Option<u64>> {
+    let scale = service.scale.take();
+    let replicas = service
+        .deploy
+        .as_mut()
+        .and_then(|deploy| deploy.replicas.take());
+    if service.deploy.as_ref().is_some_and(Deploy::is_empty) {
+        service.deploy = None;
+    }
+
+    let replicas = match (scale, replicas) {
+        (Some(scale), Some(replicas)) => {
+            ensure!(
+                scale == replicas,
+                "`scale` and `deploy.replicas` of service `{name}` are set to different values"
+            );
+            Some(scale)
+        }
+        (scale, replicas) => scale.or(replicas),
+    };
+    ensure!(
+        replicas != Some(0),
+        "service `{name}` has 0 replicas, which cannot be converted; \
+            remove the service or disable it with `profiles`"
+    );
+
+    if let Some(replicas) = replicas.filter(|replicas| *replicas > 1) {
+        let collisions: Vec<String> = ports::into_long_iter(service.ports.clone())
+            .filter_map(|port| {
+                port.published
+                    .filter(|published| u64::from(published.size()) < replicas)
+                    .map(|published| published.to_string())
+            })
+            .collect();
+        if !collisions.is_empty() {
+            eprintln!(
+                "Warning: service `{name}` has {replicas} replicas but publishes host port(s) {}, \
+                    which will collide between instances",
+                collisions.join(", ")
+            );
+        }
+    }
+
+    // A single replica does not need a template.
+    Ok(replicas.filter(|replicas| *replicas != 1))
+}
+
+/// Turn the container Quadlet files in `replicas`, a map of file names to the number of replicas,
+/// into template units.
+///
+/// Each template file is renamed to "{name}@" and its `ContainerName=` is suffixed with "-%i".
+/// A symlink to the template is added for each instance, numbered from 1. Dependencies on the
+/// service in all files' `[Unit]` sections are replaced with dependencies on each instance.
+pub fn apply(files: &mut Vec<File>, replicas: &HashMap<String, u64>) {
+    let mut instances = Vec::new();
+    for file in files.iter_mut().filter_map(File::as_quadlet_file_mut) {
+        let Some(&replicas) = replicas.get(&file.name) else {
+            continue;
+        };
+        let quadlet::Resource::Container(container) = &mut file.resource else {
+            continue;
+        };
+
+        let container_name = container
+            .container_name
+            .take()
+            .unwrap_or_else(|| file.name.clone());
+        container.container_name = Some(format!("{container_name}-%i"));
+
+        file.name.push('@');
+        let template = format!("{}.container", file.name);
+        instances.extend((1..=replicas).map(|instance| File::Symlink {
+            name: format!("{}{instance}.container", file.name),
+            target: template.clone().into(),
+        }));
+    }
+
+    for file in files.iter_mut().filter_map(File::as_quadlet_file_mut) {
+        replace_dependencies(&mut file.unit, replicas);
+    }
+
+    files.extend(instances);
+}
+
+/// Replace dependencies on services with `replicas` in the `unit` with dependencies on each of the
+/// service's instances.
+fn replace_dependencies(unit: &mut Unit, replicas: &HashMap<String, u64>) {
+    let Unit {
+        wants,
+        requires,
+        binds_to,
+        part_of,
+        upholds,
+        conflicts,
+        before,
+        after,
+        on_failure,
+        on_success,
+        ..
+    } = unit;
+
+    for list in [
+        wants, requires, binds_to, part_of, upholds, conflicts, before, after, on_failure,
+        on_success,
+    ] {
+        *list = mem::take(list)
+            .into_iter()
+            .flat_map(|dependency| {
+                let instances = dependency
+                    .strip_suffix(".service")
+                    .and_then(|name| replicas.get(name).map(|replicas| (name, *replicas)));
+                match instances {
+                    Some((name, replicas)) => (1..=replicas)
+                        .map(|instance| format!("{name}@{instance}.service"))
+                        .collect(),
+                    None => vec![dependency],
+                }
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::bail;
+
+    use crate::quadlet::{GenericSections, Globals};
+
+    use super::*;
+
+    #[test]
+    fn take_replicas() -> color_eyre::Result<()> {
+        let name = Identifier::new("test")?;
+
+        let mut service: Service = serde_yaml::from_str("deploy: { replicas: 3 }")?;
+        assert_eq!(take(&name, &mut service)?, Some(3));
+        assert!(service.deploy.is_none());
+
+        let mut service: Service = serde_yaml::from_str("scale: 1")?;
+        assert_eq!(take(&name, &mut service)?, None);
+
+        let mut service: Service = serde_yaml::from_str("{ scale: 2, deploy: { replicas: 3 } }")?;
+        assert!(take(&name, &mut service).is_err());
+
+        let mut service: Service = serde_yaml::from_str("scale: 0")?;
+        assert!(take(&name, &mut service).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn template() -> color_eyre::Result<()> {
+        let GenericSections {
+            unit,
+            quadlet,
+            install,
+        } = GenericSections::default();
+        let worker = quadlet::File {
+            name: String::from("worker"),
+            unit: unit.clone(),
+            resource: quadlet::Container::default().into(),
+            globals: Globals::default(),
+            quadlet,
+            service: quadlet::Service::default(),
+            install: install.clone(),
+            options: Vec::new(),
+        };
+        let mut app = quadlet::File {
+            name: String::from("app"),
+            ..worker.clone()
+        };
+        app.unit.requires.push(String::from("worker.service"));
+        app.unit.after.push(String::from("worker.service"));
+
+        let mut files = vec![worker.into(), app.into()];
+        apply(&mut files, &HashMap::from([(String::from("worker"), 2)]));
+
+        let [
+            File::Quadlet(worker),
+            File::Quadlet(app),
+            File::Symlink { name, target },
+            File::Symlink { .. },
+        ] = files.as_slice()
+        else {
+            bail!("expected two Quadlet files and two symlinks");
+        };
+        assert_eq!(worker.name, "worker@");
+        let quadlet::Resource::Container(container) = &worker.resource else {
+            bail!("expected a container");
+        };
+        assert_eq!(container.container_name.as_deref(), Some("worker-%i"));
+        assert_eq!(app.unit.requires, ["worker@1.service", "worker@2.service"]);
+        assert_eq!(name, "worker@1.container");
+        assert_eq!(target.as_os_str(), "worker@.container");
+
+        Ok(())
+    }
+}
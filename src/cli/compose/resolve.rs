@@ -0,0 +1,477 @@
+//! Resolution of `include` and `extends` in compose files, see [`Resolver`].
+
+use std::{
+    fs, mem,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    Help,
+    eyre::{WrapErr, ensure, eyre},
+};
+use compose_spec::{
+    Identifier, Include, Options, Resource, Service, ShortOrLong,
+    service::{
+        Build, EnvFile, Extends,
+        build::Context,
+        volumes::{HostPath, Mount, ShortOptions, ShortVolume, Source, mount::Bind},
+    },
+};
+use indexmap::IndexMap;
+use path_clean::PathClean;
+
+use super::merge::merge_services;
+
+/// Resolves the `include` and `extends` fields of compose files.
+///
+/// Included compose files and services extended from other files are read relative to the
+/// directory of the compose file which references them. Relative paths in them are rebased so
+/// that they are relative to the directory of the top-level compose file.
+pub struct Resolver<'a> {
+    /// Options for reading compose files.
+    options: &'a Options,
+
+    /// Canonical paths of the compose files currently being read, used to detect cycles.
+    files: Vec<PathBuf>,
+}
+
+impl<'a> Resolver<'a> {
+    /// Create a [`Resolver`] which reads compose files with the given `options`.
+    ///
+    /// `file` is the path of the top-level compose file, if it was read from a file.
+    pub fn new(options: &'a Options, file: Option<&Path>) -> Self {
+        Self {
+            options,
+            files: file
+                .and_then(|file| file.canonicalize().ok())
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Resolve the `include` and `extends` fields of the `compose` file.
+    ///
+    /// `dir` is the directory of the `compose` file, paths in it are relative to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a referenced compose file could not be read, a resource of an included
+    /// compose file conflicts with an existing one, or an extended service does not exist.
+    pub fn resolve(
+        &mut self,
+        compose: &mut compose_spec::Compose,
+        dir: &Path,
+    ) -> color_eyre::Result<()> {
+        for include in mem::take(&mut compose.include) {
+            let include = include.into_long();
+            let paths = include.path.clone().into_list();
+            self.include(compose, include, dir).wrap_err_with(|| {
+                let paths: Vec<_> = paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                format!("error including `{}`", paths.join("`, `"))
+            })?;
+        }
+
+        let names: Vec<Identifier> = compose.services.keys().cloned().collect();
+        for name in names {
+            self.extend(&mut compose.services, &name, dir, &mut Vec::new())
+                .wrap_err_with(|| format!("error resolving `extends` of service `{name}`"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Read and resolve the compose file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be read or resolved, or is already being read.
+    fn read(&mut self, path: &Path) -> color_eyre::Result<compose_spec::Compose> {
+        let canonical = path
+            .canonicalize()
+            .wrap_err_with(|| format!("could not read compose file `{}`", path.display()))?;
+        ensure!(
+            !self.files.contains(&canonical),
+            "compose file `{}` references itself",
+            path.display()
+        );
+
+        let file = fs::File::open(path)
+            .wrap_err_with(|| format!("could not open compose file `{}`", path.display()))?;
+        let mut compose = self
+            .options
+            .from_yaml_reader(file)
+            .wrap_err_with(|| format!("file `{}` is not a valid compose file", path.display()))?;
+
+        self.files.push(canonical);
+        let result = self.resolve(&mut compose, path.parent().unwrap_or(Path::new("")));
+        self.files.pop();
+
+        result.map(|()| compose)
+    }
+
+    /// Add the resources of an [`Include`]d compose project to the `compose` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an included file could not be read or a resource conflicts with an
+    /// existing one.
+    fn include(
+        &mut self,
+        compose: &mut compose_spec::Compose,
+        Include {
+            path,
+            project_directory,
+            env_file,
+        }: Include,
+        dir: &Path,
+    ) -> color_eyre::Result<()> {
+        if env_file.is_some() {
+            eprintln!(
+                "Warning: `env_file` of `include` was ignored as interpolation is not supported"
+            );
+        }
+
+        // Relative paths in the included files are relative to the project directory, which
+        // defaults to the directory of the first file.
+        let paths = path.into_list();
+        let project_directory = project_directory.map_or_else(
+            || {
+                paths
+                    .first()
+                    .and_then(|path| path.parent())
+                    .map(Path::to_owned)
+                    .unwrap_or_default()
+            },
+            PathBuf::from,
+        );
+
+        // Multiple paths are merged together, later files override earlier ones.
+        let mut included = compose_spec::Compose::default();
+        for path in paths {
+            let compose = self.read(&dir.join(&path))?;
+            override_compose(&mut included, compose)?;
+        }
+        rebase_compose_paths(&mut included, &project_directory)?;
+
+        add_resources(&mut compose.services, included.services, "service")?;
+        add_resources(&mut compose.networks, included.networks, "network")?;
+        add_resources(&mut compose.volumes, included.volumes, "volume")?;
+        add_resources(&mut compose.configs, included.configs, "config")?;
+        add_resources(&mut compose.secrets, included.secrets, "secret")?;
+        for (key, value) in included.extensions {
+            compose.extensions.entry(key).or_insert(value);
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the `extends` field of the service `name` in `services`.
+    ///
+    /// `chain` is the names of the services currently being resolved, used to detect cycles.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extended service does not exist, its compose file could not be
+    /// read, or the services could not be merged.
+    fn extend(
+        &mut self,
+        services: &mut IndexMap<Identifier, Service>,
+        name: &Identifier,
+        dir: &Path,
+        chain: &mut Vec<Identifier>,
+    ) -> color_eyre::Result<()> {
+        let service = services
+            .get_mut(name)
+            .ok_or_else(|| eyre!("service `{name}` does not exist"))?;
+        let Some(Extends {
+            service: base_name,
+            file,
+        }) = service.extends.take()
+        else {
+            return Ok(());
+        };
+
+        let base = if let Some(file) = file {
+            let mut compose = self.read(&dir.join(&file))?;
+            let mut base = compose.services.swap_remove(&base_name).ok_or_else(|| {
+                eyre!(
+                    "service `{base_name}` does not exist in `{}`",
+                    file.display()
+                )
+            })?;
+            rebase_service_paths(&mut base, file.parent().unwrap_or(Path::new("")))?;
+            base
+        } else {
+            ensure!(
+                base_name != *name && !chain.contains(&base_name),
+                "`extends` of service `{name}` is cyclic"
+            );
+            chain.push(name.clone());
+            self.extend(services, &base_name, dir, chain)?;
+            chain.pop();
+            services
+                .get(&base_name)
+                .cloned()
+                .ok_or_else(|| eyre!("service `{base_name}` does not exist"))?
+        };
+
+        let service = services
+            .get_mut(name)
+            .ok_or_else(|| eyre!("service `{name}` does not exist"))?;
+        *service = merge_services(base, mem::take(service))
+            .wrap_err_with(|| format!("error merging service `{name}` into `{base_name}`"))?;
+
+        Ok(())
+    }
+}
+
+/// Merge `compose` into `base`, the resources of `compose` override those in `base`.
+///
+/// # Errors
+///
+/// Returns an error if services with the same name could not be merged.
+fn override_compose(
+    base: &mut compose_spec::Compose,
+    compose: compose_spec::Compose,
+) -> color_eyre::Result<()> {
+    for (name, service) in compose.services {
+        let service = match base.services.swap_remove(&name) {
+            Some(base) => merge_services(base, service)
+                .wrap_err_with(|| format!("error merging service `{name}`"))?,
+            None => service,
+        };
+        base.services.insert(name, service);
+    }
+    base.networks.extend(compose.networks);
+    base.volumes.extend(compose.volumes);
+    base.configs.extend(compose.configs);
+    base.secrets.extend(compose.secrets);
+    base.extensions.extend(compose.extensions);
+
+    Ok(())
+}
+
+/// Add included `resources` to `existing`.
+///
+/// # Errors
+///
+/// Returns an error if a resource already exists.
+fn add_resources<T>(
+    existing: &mut IndexMap<Identifier, T>,
+    resources: IndexMap<Identifier, T>,
+    kind: &str,
+) -> color_eyre::Result<()> {
+    for (name, resource) in resources {
+        if existing.contains_key(&name) {
+            return Err(eyre!("{kind} `{name}` is already defined"))
+                .suggestion("Rename one of the conflicting resources.");
+        }
+        existing.insert(name, resource);
+    }
+    Ok(())
+}
+
+/// Rebase relative paths in a `compose` file to `dir`.
+///
+/// # Errors
+///
+/// Returns an error if a rebased bind mount path is invalid.
+fn rebase_compose_paths(compose: &mut compose_spec::Compose, dir: &Path) -> color_eyre::Result<()> {
+    for (name, service) in &mut compose.services {
+        rebase_service_paths(service, dir)
+            .wrap_err_with(|| format!("error rebasing paths of service `{name}`"))?;
+    }
+
+    for config in compose.configs.values_mut() {
+        if let Resource::Compose(compose_spec::Config {
+            source: compose_spec::config::Source::File(path),
+            ..
+        }) = config
+        {
+            *path = rebase(path, dir);
+        }
+    }
+
+    for secret in compose.secrets.values_mut() {
+        if let Resource::Compose(compose_spec::Secret {
+            source: compose_spec::secret::Source::File(path),
+            ..
+        }) = secret
+        {
+            *path = rebase(path, dir);
+        }
+    }
+
+    Ok(())
+}
+
+/// Rebase relative paths in a `service` to `dir`.
+///
+/// Rebases the build context, environment files, and bind mount sources.
+///
+/// # Errors
+///
+/// Returns an error if a rebased bind mount path is invalid.
+fn rebase_service_paths(service: &mut Service, dir: &Path) -> color_eyre::Result<()> {
+    if dir.as_os_str().is_empty() {
+        return Ok(());
+    }
+
+    match &mut service.build {
+        Some(ShortOrLong::Short(context)) => rebase_context(context, dir),
+        Some(ShortOrLong::Long(Build {
+            context,
+            additional_contexts,
+            ..
+        })) => {
+            rebase_context(
+                context.get_or_insert_with(|| Context::Path(".".into())),
+                dir,
+            );
+            additional_contexts
+                .values_mut()
+                .for_each(|context| rebase_context(context, dir));
+        }
+        None => {}
+    }
+
+    if let Some(env_file) = &mut service.env_file {
+        match env_file {
+            EnvFile::Single(path) => *path = rebase(path, dir),
+            EnvFile::List(list) => {
+                for env_file in list {
+                    match env_file {
+                        ShortOrLong::Short(path) => *path = rebase(path, dir),
+                        ShortOrLong::Long(config) => config.path = rebase(&config.path, dir),
+                    }
+                }
+            }
+        }
+    }
+
+    service.volumes = mem::take(&mut service.volumes)
+        .into_iter()
+        .map(|mut volume| {
+            match &mut volume {
+                ShortOrLong::Short(ShortVolume {
+                    options:
+                        Some(ShortOptions {
+                            source: Source::HostPath(source),
+                            ..
+                        }),
+                    ..
+                })
+                | ShortOrLong::Long(Mount::Bind(Bind { source, .. })) => {
+                    *source = HostPath::new(rebase(source.as_path(), dir))?;
+                }
+                _ => {}
+            }
+            Ok(volume)
+        })
+        .collect::<color_eyre::Result<_>>()?;
+
+    Ok(())
+}
+
+/// Rebase a build [`Context`] path to `dir`.
+fn rebase_context(context: &mut Context, dir: &Path) {
+    if let Context::Path(path) = context {
+        *path = rebase(path, dir);
+    }
+}
+
+/// Rebase a relative `path` to `dir`.
+///
+/// Relative paths are kept starting with `.` as compose requires for bind mount sources.
+fn rebase(path: &Path, dir: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_owned();
+    }
+
+    let path = dir.join(path).clean();
+    if path.is_absolute() || path.starts_with("..") || path == Path::new(".") {
+        path
+    } else {
+        Path::new(".").join(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::OptionExt;
+
+    use super::*;
+
+    #[test]
+    fn extends_same_file() -> color_eyre::Result<()> {
+        let options = compose_spec::Compose::options();
+        let mut compose = options.from_yaml_str(
+            "
+            services:
+              web:
+                extends:
+                  service: base
+                image: web
+              base:
+                extends:
+                  service: common
+                image: base
+                environment:
+                  - ONE=1
+              common:
+                labels:
+                  - label
+            ",
+        )?;
+
+        Resolver::new(&options, None).resolve(&mut compose, Path::new(""))?;
+
+        let web = compose
+            .services
+            .get("web")
+            .ok_or_eyre("web service does not exist")?;
+        assert!(web.extends.is_none());
+        assert_eq!(web.image.as_ref().map(AsRef::as_ref), Some("web"));
+        assert!(!web.environment.is_empty());
+        assert!(!web.labels.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn extends_cycle() -> color_eyre::Result<()> {
+        let options = compose_spec::Compose::options();
+        let mut compose = options.from_yaml_str(
+            "
+            services:
+              one:
+                extends:
+                  service: two
+              two:
+                extends:
+                  service: one
+            ",
+        )?;
+
+        assert!(
+            Resolver::new(&options, None)
+                .resolve(&mut compose, Path::new(""))
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rebase_paths() {
+        let dir = Path::new("sub");
+        assert_eq!(rebase(Path::new("./data"), dir), Path::new("./sub/data"));
+        assert_eq!(rebase(Path::new("../data"), dir), Path::new("./data"));
+        assert_eq!(rebase(Path::new("../../data"), dir), Path::new("../data"));
+        assert_eq!(rebase(Path::new("/data"), dir), Path::new("/data"));
+        assert_eq!(rebase(Path::new(".."), dir), Path::new("."));
+    }
+}
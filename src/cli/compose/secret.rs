@@ -0,0 +1,152 @@
+//! Creation of non-external compose [`Secrets`], see [`script()`].
+
+use std::{env, path::Path};
+
+use color_eyre::eyre::WrapErr;
+use compose_spec::{
+    Resource, Secrets,
+    secret::{Secret, Source},
+};
+use path_clean::PathClean;
+
+use crate::{cli::File, escape::command_join};
+
+/// Create a shell script which creates each non-external secret with `podman secret create`.
+///
+/// The script is named "{name}-secrets.sh", or "secrets.sh" if `name` is [`None`]. Relative secret
+/// file paths are resolved from `dir`, the directory of the compose file, and the current
+/// directory. Secrets from an environment variable read the variable when the script is run.
+///
+/// Returns [`None`] if there are no non-external secrets.
+///
+/// # Errors
+///
+/// Returns an error if the current directory could not be read.
+pub fn script(
+    name: Option<&str>,
+    secrets: Secrets,
+    dir: &Path,
+) -> color_eyre::Result<Option<File>> {
+    let mut commands = Vec::new();
+    for (secret_name, secret) in secrets {
+        let Resource::Compose(Secret {
+            source,
+            labels,
+            driver,
+            driver_opts,
+            extensions,
+        }) = secret
+        else {
+            continue;
+        };
+        debug_assert!(extensions.is_empty(), "extensions are taken beforehand");
+
+        let mut command = vec![
+            String::from("podman"),
+            String::from("secret"),
+            String::from("create"),
+            String::from("--replace"),
+        ];
+        for label in labels.into_list() {
+            command.extend([String::from("--label"), label]);
+        }
+        if let Some(driver) = driver {
+            command.extend([String::from("--driver"), driver]);
+        }
+        for (key, value) in driver_opts {
+            command.extend([String::from("--driver-opts"), format!("{key}={value}")]);
+        }
+
+        match source {
+            Source::File(path) => {
+                let path = env::current_dir()
+                    .wrap_err("current working directory could not be read")?
+                    .join(dir)
+                    .join(path)
+                    .clean();
+                command.extend([secret_name.into(), path.display().to_string()]);
+            }
+            Source::Environment(var) => {
+                command.extend([String::from("--env"), secret_name.into(), var]);
+            }
+        }
+
+        commands.push(command_join(command));
+    }
+
+    if commands.is_empty() {
+        return Ok(None);
+    }
+
+    let contents = format!(
+        "#!/bin/sh\n\
+        # Creates the Podman secrets used by the generated Quadlet files.\n\
+        # Run this script before starting the services.\n\
+        set -e\n\n\
+        {}\n",
+        commands.join("\n")
+    );
+
+    let name = name.map_or_else(
+        || String::from("secrets.sh"),
+        |name| format!("{name}-secrets.sh"),
+    );
+    eprintln!(
+        "Warning: run the generated `{name}` script to create the compose file's secrets \
+            before starting the services"
+    );
+
+    Ok(Some(File::Content {
+        name,
+        contents,
+        executable: true,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::bail;
+
+    use super::*;
+
+    #[test]
+    fn create_secrets() -> color_eyre::Result<()> {
+        let secrets = serde_yaml::from_str(
+            "
+            file:
+              file: /run/secret
+              labels: [one=1]
+            env:
+              environment: SECRET
+            external:
+              external: true
+            ",
+        )?;
+
+        let Some(File::Content {
+            name,
+            contents,
+            executable,
+        }) = script(Some("test"), secrets, Path::new(""))?
+        else {
+            bail!("script should be created");
+        };
+        assert_eq!(name, "test-secrets.sh");
+        assert!(executable);
+        assert!(
+            contents.contains("podman secret create --replace --label 'one=1' file /run/secret\n")
+        );
+        assert!(contents.contains("podman secret create --replace --env env SECRET\n"));
+        assert!(!contents.contains("external"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn only_external() -> color_eyre::Result<()> {
+        let secrets = serde_yaml::from_str("external: { external: true }")?;
+        assert!(script(None, secrets, Path::new(""))?.is_none());
+
+        Ok(())
+    }
+}
@@ -45,7 +45,8 @@ impl From<compose_spec::Service> for Service {
             cgroup,
             cgroup_parent,
             command,
-            configs,
+            // Taken in `crate::cli::compose::config::Configs::apply_to()`.
+            configs: _,
             container_name,
             credential_spec,
             // Taken in `crate::cli::compose::service_try_into_quadlet_file()`.
@@ -90,7 +91,8 @@ impl From<compose_spec::Service> for Service {
             platform,
             ports,
             privileged,
-            profiles,
+            // Services are filtered by their profiles in `crate::cli::compose::profile::apply()`.
+            profiles: _,
             pull_policy,
             read_only,
             // Taken for the `[Service]` section.
@@ -130,7 +132,6 @@ impl From<compose_spec::Service> for Service {
                 build,
                 cpu_count,
                 cpu_percent,
-                configs,
                 credential_spec,
                 deploy,
                 develop,
@@ -140,7 +141,6 @@ impl From<compose_spec::Service> for Service {
                 isolation,
                 links,
                 logging_extensions,
-                profiles,
                 scale,
                 volumes_from,
                 extensions,
@@ -225,7 +225,6 @@ pub struct Unsupported {
     build: Option<ShortOrLong<Context, Build>>,
     cpu_count: Option<u64>,
     cpu_percent: Option<Percent>,
-    configs: Vec<ShortOrLong<Identifier, ConfigOrSecret>>,
     credential_spec: Option<CredentialSpec>,
     deploy: Option<Deploy>,
     develop: Option<Develop>,
@@ -235,7 +234,6 @@ pub struct Unsupported {
     isolation: Option<String>,
     links: IndexSet<Link>,
     logging_extensions: Extensions,
-    profiles: IndexSet<Identifier>,
     scale: Option<u64>,
     volumes_from: IndexSet<VolumesFrom>,
     extensions: Extensions,
@@ -253,7 +251,6 @@ impl Unsupported {
             build,
             cpu_count,
             cpu_percent,
-            configs,
             credential_spec,
             deploy,
             develop,
@@ -263,7 +260,6 @@ impl Unsupported {
             isolation,
             links,
             logging_extensions,
-            profiles,
             scale,
             volumes_from,
             extensions,
@@ -275,7 +271,6 @@ impl Unsupported {
             ("build", build.is_none()),
             ("cpu_count", cpu_count.is_none()),
             ("cpu_percent", cpu_percent.is_none()),
-            ("configs", configs.is_empty()),
             ("credential_spec", credential_spec.is_none()),
             ("deploy", deploy.is_none()),
             ("develop", develop.is_none()),
@@ -284,7 +279,6 @@ impl Unsupported {
             ("external_links", external_links.is_empty()),
             ("isolation", isolation.is_none()),
             ("links", links.is_empty()),
-            ("profiles", profiles.is_empty()),
             ("scale", scale.is_none()),
             ("volumes_from", volumes_from.is_empty()),
         ];
@@ -0,0 +1,506 @@
+//! Generation of Quadlet drop-in files, see [`DropIn`] and [`DropInFile`].
+
+use std::{collections::HashSet, fmt::Write, iter, path::PathBuf};
+
+use clap::Args;
+use color_eyre::eyre::{WrapErr, bail, ensure};
+use indexmap::IndexMap;
+
+use crate::{
+    quadlet::{self, Globals, Install, JoinOption, Quadlet, ResourceKind, Unit},
+    serde::quadlet::reset_option_names,
+};
+
+use super::{File, rewrite::read_quadlet_file};
+
+/// Options for generating drop-in files instead of whole Quadlet files.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct DropIn {
+    /// Generate drop-in files instead of whole Quadlet files
+    ///
+    /// Provide the name of the drop-in file (without the `.conf` extension). Each generated Quadlet
+    /// file, e.g. `web.container`, is written as a drop-in to `web.container.d/NAME.conf`.
+    ///
+    /// Use `--drop-in-base` to only include the options which differ from an existing Quadlet file.
+    #[arg(
+        id = "drop_in",
+        long = "drop-in",
+        value_name = "NAME",
+        conflicts_with = "quadlets_file"
+    )]
+    name: Option<String>,
+
+    /// Existing Quadlet file the drop-in overrides
+    ///
+    /// Only options which differ from the base file are included in the drop-in. Options which can
+    /// be set multiple times, with values which cannot be appended to the base file's values or
+    /// which are only set in the base file, are reset first by setting them to an empty value.
+    /// Other options only set in the base file cannot be reset and are kept, as are the dependencies
+    /// of the `[Unit]` section, e.g. `After=`.
+    ///
+    /// The drop-in directory is named after the base file, e.g. `--drop-in-base web.container`
+    /// results in `web.container.d/NAME.conf`.
+    ///
+    /// Generated Quadlet files are matched to base files by name and type. If a single Quadlet file
+    /// is generated and a single base file is given, they are always matched.
+    ///
+    /// Can be specified multiple times.
+    #[arg(
+        id = "drop_in_base",
+        long = "drop-in-base",
+        value_name = "BASE_FILE",
+        requires = "drop_in"
+    )]
+    bases: Vec<PathBuf>,
+
+    /// Generate top-level drop-ins shared by all Quadlet files of the same type
+    ///
+    /// For example, `container.d/NAME.conf` instead of `web.container.d/NAME.conf`.
+    #[arg(id = "drop_in_shared", long = "drop-in-shared", requires = "drop_in")]
+    shared: bool,
+}
+
+impl DropIn {
+    /// Convert the Quadlet files in `files` into drop-in files, if a drop-in name was given.
+    ///
+    /// Other files are left as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a base file could not be read, a base file does not match any generated
+    /// Quadlet file, or if multiple drop-ins would be written to the same path.
+    pub fn apply(self, files: &mut [File]) -> color_eyre::Result<()> {
+        let Self {
+            name,
+            bases,
+            shared,
+        } = self;
+        let Some(name) = name else {
+            return Ok(());
+        };
+
+        let mut bases = bases
+            .iter()
+            .map(|path| {
+                read_quadlet_file(path)
+                    .wrap_err_with(|| format!("error reading drop-in base `{}`", path.display()))
+            })
+            .collect::<color_eyre::Result<Vec<_>>>()?;
+
+        let single_pair = bases.len() == 1
+            && files
+                .iter()
+                .filter(|file| matches!(file, File::Quadlet(_)))
+                .count()
+                == 1;
+
+        let mut paths = HashSet::new();
+        for file in files.iter_mut() {
+            let File::Quadlet(quadlet_file) = file else {
+                continue;
+            };
+
+            let kind = ResourceKind::from(&quadlet_file.resource);
+            let base = if single_pair {
+                bases.pop()
+            } else {
+                bases
+                    .iter()
+                    .position(|base| {
+                        base.name == quadlet_file.name && ResourceKind::from(&base.resource) == kind
+                    })
+                    .map(|index| bases.swap_remove(index))
+            };
+
+            if let Some(base) = &base {
+                let base_kind = ResourceKind::from(&base.resource);
+                ensure!(
+                    base_kind == kind,
+                    "drop-in base `{}.{}` is a {base_kind} unit, but a {kind} unit was generated",
+                    base.name,
+                    base.resource.extension(),
+                );
+            }
+
+            let unit_name = base.as_ref().map_or(&quadlet_file.name, |base| &base.name);
+            let path = if shared {
+                format!("{}.d/{name}.conf", kind.as_str())
+            } else {
+                format!("{unit_name}.{}.d/{name}.conf", kind.as_str())
+            };
+            ensure!(
+                paths.insert(path.clone()),
+                "multiple drop-ins would be written to `{path}`"
+            );
+
+            let drop_in = DropInFile::new(path, quadlet_file, base.as_ref())?;
+            if drop_in.is_empty() {
+                eprintln!(
+                    "Warning: drop-in `{}` is empty, the generated {kind} unit does not differ \
+                        from its base",
+                    drop_in.path
+                );
+            }
+            *file = File::DropIn(drop_in);
+        }
+
+        if let Some(base) = bases.first() {
+            bail!(
+                "drop-in base `{}.{}` does not match any generated Quadlet file",
+                base.name,
+                base.resource.extension()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A Quadlet drop-in file which overrides the options of a Quadlet file or all Quadlet files of a
+/// type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DropInFile {
+    /// Path of the drop-in relative to the Quadlet directory, e.g. "web.container.d/10-site.conf".
+    pub path: String,
+
+    /// The values of each option, keyed by section and option name.
+    ///
+    /// An empty value resets the option.
+    options: IndexMap<(String, String), Vec<String>>,
+}
+
+impl DropInFile {
+    /// Create a drop-in at `path` with the options of the Quadlet `file` which differ from the
+    /// `base`, or all options if there is no `base`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either file could not be serialized.
+    pub fn new(
+        path: String,
+        file: &quadlet::File,
+        base: Option<&quadlet::File>,
+    ) -> color_eyre::Result<Self> {
+        let mut options = file
+            .option_values()
+            .wrap_err("error serializing Quadlet file")?;
+
+        if let Some(base) = base {
+            let base_options = base
+                .option_values()
+                .wrap_err("error serializing drop-in base")?;
+            let reset = reset_options(ResourceKind::from(&file.resource));
+            let reset = |(section, key): &(String, String)| {
+                reset.contains(&(section.as_str(), key.as_str()))
+            };
+
+            for (option @ (section, key), base_values) in &base_options {
+                // Options podlet does not support are only set in the base and are kept as is.
+                let supported = !base
+                    .options
+                    .iter()
+                    .any(|unit_option| unit_option.section == *section && unit_option.key == *key);
+                let values = options.get(option);
+                if supported && !reset(option) && values.is_none() {
+                    eprintln!(
+                        "Warning: `{key}=` of the [{section}] section is only set in the drop-in \
+                            base, but cannot be reset by a drop-in"
+                    );
+                } else if section == "Unit" && UNIT_DEPENDENCIES.contains(&key.as_str()) {
+                    let dropped: Vec<_> = base_values
+                        .iter()
+                        .filter(|value| values.is_some_and(|values| !values.contains(value)))
+                        .map(String::as_str)
+                        .collect();
+                    if !dropped.is_empty() {
+                        eprintln!(
+                            "Warning: dependencies `{key}={}` of the [Unit] section are only set \
+                                in the drop-in base, but cannot be removed by a drop-in",
+                            dropped.join(" ")
+                        );
+                    }
+                }
+            }
+
+            options = diff(&options, &base_options, reset);
+        }
+
+        Ok(Self { path, options })
+    }
+
+    /// Returns `true` if the drop-in does not set any options.
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+
+    /// Serialize the drop-in to the Quadlet file format.
+    ///
+    /// Values of the Quadlet options in `join_options` are joined together with a space.
+    pub fn serialize(&self, join_options: &HashSet<JoinOption>) -> String {
+        let mut sections: IndexMap<&str, String> = IndexMap::new();
+        for ((section, key), values) in &self.options {
+            let output = sections.entry(section).or_default();
+
+            let values = match values.split_first() {
+                Some((reset, values)) if reset.is_empty() => {
+                    writeln!(output, "{key}=").expect("write to String never fails");
+                    values
+                }
+                _ => values.as_slice(),
+            };
+
+            let join = key
+                .parse()
+                .is_ok_and(|option| join_options.contains(&option));
+            if join && !values.is_empty() {
+                writeln!(output, "{key}={}", values.join(" "))
+                    .expect("write to String never fails");
+            } else {
+                for value in values {
+                    writeln!(output, "{key}={value}").expect("write to String never fails");
+                }
+            }
+        }
+
+        sections
+            .into_iter()
+            .map(|(section, options)| format!("[{section}]\n{options}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Dependency options of the `[Unit]` section.
+///
+/// systemd does not reset these with an empty value, so a drop-in can only add dependencies.
+const UNIT_DEPENDENCIES: [&str; 10] = [
+    "After",
+    "Requires",
+    "Wants",
+    "BindsTo",
+    "PartOf",
+    "Upholds",
+    "Before",
+    "Conflicts",
+    "OnFailure",
+    "OnSuccess",
+];
+
+/// Returns the `(section, option)` pairs of a Quadlet file of the resource `kind` which are reset by
+/// an empty value, see [`reset_option_names()`].
+///
+/// The [`UNIT_DEPENDENCIES`] are not included.
+fn reset_options(kind: ResourceKind) -> HashSet<(&'static str, &'static str)> {
+    let mut unit = reset_option_names::<Unit>();
+    unit.retain(|option| !UNIT_DEPENDENCIES.contains(option));
+
+    let mut resource = kind.reset_option_names();
+    resource.extend(reset_option_names::<Globals>());
+
+    [
+        ("Unit", unit),
+        (kind.section_name(), resource),
+        ("Quadlet", reset_option_names::<Quadlet>()),
+        ("Service", reset_option_names::<quadlet::Service>()),
+        ("Install", reset_option_names::<Install>()),
+    ]
+    .into_iter()
+    .flat_map(|(section, options)| options.into_iter().map(move |option| (section, option)))
+    .collect()
+}
+
+/// Returns the `options` which differ from the `base` options.
+///
+/// Values of options which are reset by an empty value, according to `reset`, are appended to the
+/// base values if they extend them. Otherwise, they are reset with an empty value first, as are
+/// such options only set in the `base`. Other options only set in the `base` are not included, as
+/// they cannot be reset.
+fn diff(
+    options: &IndexMap<(String, String), Vec<String>>,
+    base: &IndexMap<(String, String), Vec<String>>,
+    reset: impl Fn(&(String, String)) -> bool,
+) -> IndexMap<(String, String), Vec<String>> {
+    let mut diff = IndexMap::new();
+
+    for (option, values) in options {
+        let Some(base_values) = base.get(option) else {
+            diff.insert(option.clone(), values.clone());
+            continue;
+        };
+        if base_values == values {
+            continue;
+        }
+        let values = match values.strip_prefix(base_values.as_slice()) {
+            _ if !reset(option) => values.clone(),
+            Some(appended) => appended.to_vec(),
+            None => iter::once(String::new())
+                .chain(values.iter().cloned())
+                .collect(),
+        };
+        diff.insert(option.clone(), values);
+    }
+
+    for option in base.keys() {
+        if !options.contains_key(option) && reset(option) {
+            diff.insert(option.clone(), vec![String::new()]);
+        }
+    }
+
+    // Keep the options of each section together.
+    diff.sort_by_cached_key(|(section, _), _| {
+        options
+            .keys()
+            .chain(base.keys())
+            .position(|(other, _)| other == section)
+    });
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::quadlet::Container;
+
+    use super::*;
+
+    #[test]
+    fn diff_base() -> color_eyre::Result<()> {
+        let base = quadlet::File::new(
+            "web",
+            Container {
+                image: String::from("image"),
+                environment: vec![String::from("ONE=one")],
+                label: vec![String::from("label")],
+                ..Container::default()
+            },
+        );
+        let mut file = quadlet::File::new(
+            "web",
+            Container {
+                image: String::from("image"),
+                environment: vec![String::from("ONE=one"), String::from("TWO=two")],
+                ..Container::default()
+            },
+        );
+        file.service.restart_sec = Some(String::from("5"));
+
+        let drop_in = DropInFile::new(
+            String::from("web.container.d/override.conf"),
+            &file,
+            Some(&base),
+        )?;
+        assert_eq!(
+            drop_in.serialize(&HashSet::new()),
+            "[Container]\n\
+            Environment=TWO=two\n\
+            Label=\n\
+            \n\
+            [Service]\n\
+            RestartSec=5\n"
+        );
+
+        assert!(DropInFile::new(String::new(), &base, Some(&base))?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn reset() -> color_eyre::Result<()> {
+        let base = quadlet::File::new(
+            "web",
+            Container {
+                image: String::from("image"),
+                environment: vec![String::from("ONE=one"), String::from("TWO=two")],
+                ..Container::default()
+            },
+        );
+        let file = quadlet::File::new(
+            "web",
+            Container {
+                image: String::from("other"),
+                environment: vec![String::from("THREE=three"), String::from("FOUR=four")],
+                ..Container::default()
+            },
+        );
+
+        let drop_in = DropInFile::new(String::new(), &file, Some(&base))?;
+        assert_eq!(
+            drop_in.serialize(&JoinOption::all_set()),
+            "[Container]\n\
+            Environment=\n\
+            Environment=THREE=three FOUR=four\n\
+            Image=other\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn base_only_options() -> color_eyre::Result<()> {
+        let base = quadlet::File::deserialize_from_quadlet(
+            String::from("web"),
+            "[Container]\n\
+            Image=image\n\
+            ContainerName=web\n\
+            Label=app=web\n\
+            PodmanArgs=--tty\n\
+            \n\
+            [Service]\n\
+            KillMode=mixed\n",
+        )?;
+        let file = quadlet::File::new(
+            "web",
+            Container {
+                image: String::from("image"),
+                ..Container::default()
+            },
+        );
+
+        // Single-valued and unsupported options cannot be reset.
+        let drop_in = DropInFile::new(String::new(), &file, Some(&base))?;
+        assert_eq!(
+            drop_in.serialize(&HashSet::new()),
+            "[Container]\n\
+            Label=\n\
+            PodmanArgs=\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn unit_dependencies() -> color_eyre::Result<()> {
+        let mut base = quadlet::File::new(
+            "web",
+            Container {
+                image: String::from("image"),
+                ..Container::default()
+            },
+        );
+        base.unit.after = vec![String::from("a.service")];
+        let mut file = base.clone();
+        file.unit.after = vec![String::from("b.service")];
+
+        // Dependencies cannot be reset, so the base's are kept and the new ones added.
+        let drop_in = DropInFile::new(String::new(), &file, Some(&base))?;
+        assert_eq!(
+            drop_in.serialize(&HashSet::new()),
+            "[Unit]\nAfter=b.service\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn container_reset_options() {
+        let reset = reset_options(ResourceKind::Container);
+        assert!(reset.contains(&("Container", "Environment")));
+        assert!(reset.contains(&("Container", "PodmanArgs")));
+        assert!(reset.contains(&("Unit", "Documentation")));
+        assert!(!reset.contains(&("Unit", "After")));
+        assert!(!reset.contains(&("Unit", "Wants")));
+        assert!(!reset.contains(&("Container", "Image")));
+        assert!(!reset.contains(&("Container", "ContainerName")));
+        assert!(!reset.contains(&("Service", "Restart")));
+    }
+}
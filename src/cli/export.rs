@@ -0,0 +1,59 @@
+//! Provides the `podlet export` subcommand, see [`Export`].
+//!
+//! `podlet export` is the reverse of the other commands, it converts existing Quadlet files into
+//! other formats.
+
+mod compose;
+mod kube;
+
+use clap::Subcommand;
+
+pub use self::{compose::Compose, kube::Kube};
+
+/// [`Subcommand`] for `podlet export`
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum Export {
+    /// Convert Quadlet files into a compose file
+    ///
+    /// Each `.container` file becomes a service, each `.network` file a network, and each
+    /// `.volume` file a volume. `.image` and `.build` files are used for the image of the
+    /// services which reference them. The containers of a `.pod` file share the network of the
+    /// first container in the pod, which is given the pod's published ports and networks.
+    ///
+    /// `Requires=`, `Wants=`, and `BindsTo=` dependencies on other given `.container` files,
+    /// in the [Unit] section, become `depends_on`.
+    ///
+    /// Quadlet options without a compose equivalent are ignored with a warning.
+    ///
+    /// The compose file is printed to stdout.
+    Compose(Compose),
+
+    /// Convert Quadlet files, or a `podman run` command, into Kubernetes YAML
+    ///
+    /// The `.container` files become the containers of a single pod, and named volumes with
+    /// options become persistent volume claims. The name of the pod is set with `--name`, or
+    /// taken from the `.pod` file or the only `.container` file.
+    ///
+    /// The result can be used with a `.kube` Quadlet file, i.e. `podman kube play`, or on a
+    /// Kubernetes cluster.
+    ///
+    /// Options without a Kubernetes equivalent are ignored with a warning.
+    ///
+    /// The Kubernetes YAML is printed to stdout.
+    #[allow(clippy::doc_markdown)]
+    Kube(Kube),
+}
+
+impl Export {
+    /// Read the Quadlet files and print them in the selected format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file could not be read or converted.
+    pub fn run(&self) -> color_eyre::Result<()> {
+        match self {
+            Self::Compose(compose) => compose.run(),
+            Self::Kube(kube) => kube.run(),
+        }
+    }
+}
@@ -0,0 +1,1079 @@
+//! Provides the `podlet export compose` subcommand, see [`Compose`].
+//!
+//! The Quadlet files are converted into YAML values which are then deserialized into
+//! [`compose_spec`] types, so that the compose short syntax can be reused for values which share
+//! their format with Quadlet options, e.g. `PublishPort=` and `ports`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+use color_eyre::{
+    Help,
+    eyre::{WrapErr, ensure, eyre},
+};
+use compose_spec::{Identifier, service::build::Context};
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::{
+    cli::rewrite::{entries, read_quadlet_file},
+    quadlet::{
+        self, Build, Resource,
+        container::{Dns, volume::Source},
+    },
+};
+
+/// Quadlet options of a `.container` file which are converted into a compose service.
+const CONTAINER_OPTIONS: &[&str] = &[
+    "AddCapability",
+    "AddDevice",
+    "AddHost",
+    "Annotation",
+    "AppArmor",
+    "ContainerName",
+    "DNS",
+    "DNSOption",
+    "DNSSearch",
+    "DropCapability",
+    "Entrypoint",
+    "Environment",
+    "EnvironmentFile",
+    "Exec",
+    "ExposeHostPort",
+    "Group",
+    "GroupAdd",
+    "HealthCmd",
+    "HealthInterval",
+    "HealthRetries",
+    "HealthStartPeriod",
+    "HealthTimeout",
+    "HostName",
+    "Image",
+    "Label",
+    "LogDriver",
+    "LogOpt",
+    "Memory",
+    "Network",
+    "NoNewPrivileges",
+    "PidsLimit",
+    "Pod",
+    "PublishPort",
+    "Pull",
+    "ReadOnly",
+    "RunInit",
+    "SeccompProfile",
+    "Secret",
+    "SecurityLabelDisable",
+    "SecurityLabelFileType",
+    "SecurityLabelLevel",
+    "SecurityLabelNested",
+    "SecurityLabelType",
+    "ShmSize",
+    "StopSignal",
+    "StopTimeout",
+    "Sysctl",
+    "Tmpfs",
+    "Ulimit",
+    "User",
+    "UserNS",
+    "Volume",
+    "WorkingDir",
+];
+
+/// Quadlet options of a `.pod` file which are given to the first container in the pod.
+const POD_OPTIONS: &[&str] = &["Network", "PublishPort"];
+
+/// Quadlet options of a `.network` file which are converted into a compose network.
+const NETWORK_OPTIONS: &[&str] = &[
+    "Driver",
+    "Gateway",
+    "IPAMDriver",
+    "IPRange",
+    "IPv6",
+    "Internal",
+    "Label",
+    "Options",
+    "Subnet",
+];
+
+/// Quadlet options of a `.volume` file which are converted into a compose volume.
+const VOLUME_OPTIONS: &[&str] = &[
+    "Copy", "Device", "Driver", "Group", "Image", "Label", "Options", "Type", "User",
+];
+
+/// Quadlet options of a `.build` file which are converted into a compose service `build`.
+const BUILD_OPTIONS: &[&str] = &[
+    "BuildArg",
+    "File",
+    "ImageTag",
+    "Label",
+    "SetWorkingDirectory",
+    "Target",
+];
+
+/// Quadlet options of an `.image` file which are used for a compose service `image`.
+const IMAGE_OPTIONS: &[&str] = &["Image"];
+
+/// Values of `Network=` which are a network mode rather than a network.
+const NETWORK_MODES: &[&str] = &[
+    "bridge",
+    "container",
+    "host",
+    "none",
+    "ns",
+    "pasta",
+    "private",
+    "slirp4netns",
+];
+
+/// [`Args`] for the `podlet export compose` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct Compose {
+    /// The Quadlet files to convert
+    ///
+    /// The type of each file is determined by its extension, e.g. `.container` or `.network`.
+    /// The name of the file, without the extension, is used for the name of the service, network,
+    /// or volume.
+    #[arg(required = true, value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+}
+
+impl Compose {
+    /// Read the Quadlet files, convert them into a compose file, and print it to stdout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file could not be read, or the files could not be converted.
+    pub fn run(&self) -> color_eyre::Result<()> {
+        let files = self
+            .files
+            .iter()
+            .map(|path| read_quadlet_file(path))
+            .collect::<color_eyre::Result<Vec<_>>>()?;
+
+        let compose = files_try_into_compose(&files)?;
+        let compose = serde_yaml::to_string(&compose).wrap_err("error serializing compose file")?;
+        print!("{compose}");
+
+        Ok(())
+    }
+}
+
+/// Convert Quadlet `files` into a [`compose_spec::Compose`].
+///
+/// A warning is printed for each Quadlet option which cannot be converted.
+///
+/// # Errors
+///
+/// Returns an error if a file references a `.network`, `.volume`, `.pod`, `.image`, or `.build`
+/// file which is not one of the `files`, or if an option's value is not valid in a compose file.
+pub fn files_try_into_compose(
+    files: &[quadlet::File],
+) -> color_eyre::Result<compose_spec::Compose> {
+    let mut exporter = Exporter::new(files)?;
+
+    let mut services = IndexMap::new();
+    for file in files {
+        if let Resource::Container(container) = &file.resource {
+            let service = exporter.service(file, container).wrap_err_with(|| {
+                format!(
+                    "error converting `{}.container` into a compose service",
+                    file.name
+                )
+            })?;
+            let name = Identifier::new(file.name.as_str())
+                .wrap_err_with(|| format!("`{}` is not a valid service name", file.name))?;
+            services.insert(name, service);
+        }
+    }
+
+    let Exporter {
+        networks,
+        volumes,
+        secrets,
+        ..
+    } = exporter;
+
+    let compose = compose_spec::Compose {
+        services,
+        networks: serde_yaml::from_value(Value::Mapping(networks))
+            .wrap_err("error converting networks")?,
+        volumes: serde_yaml::from_value(Value::Mapping(volumes))
+            .wrap_err("error converting volumes")?,
+        secrets: serde_yaml::from_value(Value::Mapping(secrets))
+            .wrap_err("error converting secrets")?,
+        ..compose_spec::Compose::default()
+    };
+    compose
+        .validate_all()
+        .wrap_err("error validating exported compose file")?;
+
+    Ok(compose)
+}
+
+/// State for converting Quadlet files into compose services.
+#[derive(Default)]
+struct Exporter<'a> {
+    /// Compose service names, keyed by the unit names a container can be referred to by.
+    services: HashMap<String, &'a str>,
+
+    /// Unit names of the given files which are not containers.
+    resource_units: HashSet<String>,
+
+    /// Names of the given `.network` files.
+    network_files: HashSet<&'a str>,
+
+    /// Names of the given `.volume` files.
+    volume_files: HashSet<&'a str>,
+
+    /// Pods and the name of their first container, keyed by the `.pod` file name.
+    pods: HashMap<String, (&'a quadlet::Pod, Option<&'a str>)>,
+
+    /// Images, keyed by the `.image` file name.
+    images: HashMap<String, &'a str>,
+
+    /// Builds, keyed by the `.build` file name.
+    builds: HashMap<String, &'a Build>,
+
+    /// Top-level compose networks.
+    networks: Mapping,
+
+    /// Top-level compose volumes.
+    volumes: Mapping,
+
+    /// Top-level compose secrets.
+    secrets: Mapping,
+}
+
+impl<'a> Exporter<'a> {
+    /// Index the `files` by the names they can be referenced by and convert the networks and
+    /// volumes.
+    fn new(files: &'a [quadlet::File]) -> color_eyre::Result<Self> {
+        let mut exporter = Self::default();
+
+        for file in files {
+            let file_name = format!("{}.{}", file.name, file.resource.extension());
+            let unit_names = [file.service_name(), file_name.clone()].into_iter().chain(
+                file.globals
+                    .service_name
+                    .as_ref()
+                    .map(|service_name| format!("{service_name}.service")),
+            );
+
+            match &file.resource {
+                Resource::Container(_) => {
+                    exporter
+                        .services
+                        .extend(unit_names.map(|unit| (unit, file.name.as_str())));
+                    continue;
+                }
+                Resource::Network(network) => {
+                    warn_unsupported(file, NETWORK_OPTIONS)?;
+                    let network = network_try_into_value(&file_name, network)
+                        .wrap_err_with(|| format!("error converting `{file_name}`"))?;
+                    exporter.networks.insert(file.name.as_str().into(), network);
+                    exporter.network_files.insert(&file.name);
+                }
+                Resource::Volume(volume) => {
+                    warn_unsupported(file, VOLUME_OPTIONS)?;
+                    let volume = volume_try_into_value(volume)
+                        .wrap_err_with(|| format!("error converting `{file_name}`"))?;
+                    exporter.volumes.insert(file.name.as_str().into(), volume);
+                    exporter.volume_files.insert(&file.name);
+                }
+                Resource::Pod(pod) => {
+                    warn_unsupported(file, POD_OPTIONS)?;
+                    exporter.pods.insert(file_name, (pod, None));
+                }
+                Resource::Image(image) => {
+                    warn_unsupported(file, IMAGE_OPTIONS)?;
+                    exporter.images.insert(file_name, &image.image);
+                }
+                Resource::Build(build) => {
+                    warn_unsupported(file, BUILD_OPTIONS)?;
+                    exporter.builds.insert(file_name, build);
+                }
+                Resource::Kube(_) | Resource::Artifact(_) => {
+                    eprintln!("Warning: `{file_name}` cannot be exported and was skipped");
+                }
+            }
+
+            exporter.resource_units.extend(unit_names);
+        }
+
+        // The first container in each pod joins the pod's networks, the rest share its network.
+        for file in files {
+            if let Resource::Container(container) = &file.resource {
+                if let Some((_, first @ None)) = container
+                    .pod
+                    .as_ref()
+                    .and_then(|pod| exporter.pods.get_mut(pod))
+                {
+                    *first = Some(&file.name);
+                }
+            }
+        }
+        for (pod, (_, first)) in &exporter.pods {
+            if first.is_none() {
+                eprintln!("Warning: `{pod}` has no containers and was skipped");
+            }
+        }
+
+        Ok(exporter)
+    }
+
+    /// Convert a Quadlet `container` `file` into a compose service.
+    fn service(
+        &mut self,
+        file: &quadlet::File,
+        container: &quadlet::Container,
+    ) -> color_eyre::Result<compose_spec::Service> {
+        warn_unsupported(file, CONTAINER_OPTIONS)?;
+
+        let mut service = Mapping::new();
+        self.image(&mut service, &container.image)?;
+        insert_container_options(&mut service, &file.name, container)?;
+        self.networks(&mut service, &file.name, container)?;
+        self.volumes(&mut service, container)?;
+        self.secrets(&mut service, &file.name, &container.secret)?;
+        insert(&mut service, "depends_on", self.depends_on(file))?;
+
+        if let Some(restart) = file.service.restart {
+            match serde_yaml::to_value(restart)?.as_str() {
+                Some(restart @ ("no" | "always" | "on-failure")) => {
+                    insert(&mut service, "restart", restart)?;
+                }
+                restart => eprintln!(
+                    "Warning: `Restart={}` of `{}.container` cannot be exported and was \
+                        ignored",
+                    restart.unwrap_or_default(),
+                    file.name,
+                ),
+            }
+        }
+
+        serde_yaml::from_value(Value::Mapping(service)).map_err(Into::into)
+    }
+
+    /// Set the service `image`, or `build` if the `image` refers to a `.build` file.
+    fn image(&self, service: &mut Mapping, image: &str) -> color_eyre::Result<()> {
+        match Path::new(image).extension().and_then(OsStr::to_str) {
+            Some("image") => {
+                let image = self
+                    .images
+                    .get(image)
+                    .ok_or_else(|| not_found(image, "Image"))?;
+                insert(service, "image", image)?;
+            }
+            Some("build") => {
+                let build = self
+                    .builds
+                    .get(image)
+                    .ok_or_else(|| not_found(image, "Image"))?;
+                insert(service, "build", build_try_into_value(build)?)?;
+                insert(service, "image", build.image_tag.first())?;
+            }
+            _ => insert(service, "image", image)?,
+        }
+
+        Ok(())
+    }
+
+    /// Set the service `ports`, `networks`, and `network_mode`.
+    ///
+    /// If the container is the first in its pod, the pod's published ports and networks are
+    /// included. Otherwise, the container shares the network of the first container in the pod.
+    fn networks(
+        &mut self,
+        service: &mut Mapping,
+        name: &str,
+        container: &quadlet::Container,
+    ) -> color_eyre::Result<()> {
+        let mut ports: Vec<&str> = container.publish_port.iter().map(String::as_str).collect();
+        let mut networks: Vec<&str> = container.network.iter().map(String::as_str).collect();
+        let mut network_mode = None;
+
+        if let Some(pod) = &container.pod {
+            let (pod, first) = self.pods.get(pod).ok_or_else(|| not_found(pod, "Pod"))?;
+            if *first == Some(name) {
+                ports.extend(pod.publish_port.iter().map(String::as_str));
+                networks.extend(pod.network.iter().map(String::as_str));
+            } else if let Some(first) = first {
+                network_mode = Some(format!("service:{first}"));
+            }
+        }
+
+        let mut service_networks = Vec::new();
+        for network in networks {
+            let (network_name, options) = network
+                .split_once(':')
+                .map_or((network, None), |(name, options)| (name, Some(options)));
+
+            if let Some(network_name) = network_name.strip_suffix(".network") {
+                ensure!(
+                    self.network_files.contains(network_name),
+                    not_found(network, "Network")
+                );
+                if options.is_some() {
+                    eprintln!(
+                        "Warning: options of `Network={network}` in `{name}.container` cannot be \
+                            exported and were ignored"
+                    );
+                }
+                service_networks.push(network_name);
+            } else if NETWORK_MODES.contains(&network_name) {
+                ensure!(
+                    network_mode.is_none(),
+                    "only one network mode can be exported to compose"
+                );
+                network_mode = Some(network.to_owned());
+            } else {
+                // Existing networks not managed by Quadlet.
+                if !self.networks.contains_key(network) {
+                    self.networks.insert(network.into(), external());
+                }
+                service_networks.push(network);
+            }
+        }
+
+        ensure!(
+            network_mode.is_none() || service_networks.is_empty(),
+            "a network mode and networks cannot both be exported to compose"
+        );
+
+        insert(service, "ports", ports)?;
+        insert(service, "networks", service_networks)?;
+        insert(service, "network_mode", network_mode)?;
+
+        Ok(())
+    }
+
+    /// Set the service `volumes`, declaring named volumes which are not from a `.volume` file as
+    /// external.
+    fn volumes(
+        &mut self,
+        service: &mut Mapping,
+        container: &quadlet::Container,
+    ) -> color_eyre::Result<()> {
+        let mut volumes = Vec::with_capacity(container.volume.len());
+        for volume in &container.volume {
+            let mut volume = volume.clone();
+            if let Some(Source::NamedVolume(source)) = &mut volume.source {
+                if let Some(name) = source.strip_suffix(".volume") {
+                    ensure!(
+                        self.volume_files.contains(name),
+                        not_found(source, "Volume")
+                    );
+                    *source = name.to_owned();
+                } else if !self.volumes.contains_key(source.as_str()) {
+                    // Existing volumes not managed by Quadlet.
+                    self.volumes.insert(source.as_str().into(), external());
+                }
+            }
+            volumes.push(volume.to_string());
+        }
+
+        insert(service, "volumes", volumes)?;
+        Ok(())
+    }
+
+    /// Set the service `secrets`, declaring each as an external secret.
+    fn secrets(
+        &mut self,
+        service: &mut Mapping,
+        name: &str,
+        secrets: &[String],
+    ) -> color_eyre::Result<()> {
+        let mut service_secrets = Vec::with_capacity(secrets.len());
+        for secret in secrets {
+            let (secret_name, options) = secret
+                .split_once(',')
+                .map_or((secret.as_str(), None), |(name, options)| {
+                    (name, Some(options))
+                });
+            if options.is_some() {
+                eprintln!(
+                    "Warning: options of `Secret={secret}` in `{name}.container` cannot be \
+                        exported and were ignored"
+                );
+            }
+            if !self.secrets.contains_key(secret_name) {
+                self.secrets.insert(secret_name.into(), external());
+            }
+            service_secrets.push(secret_name);
+        }
+
+        insert(service, "secrets", service_secrets)?;
+        Ok(())
+    }
+
+    /// Convert the `file`'s `Requires=`, `Wants=`, and `BindsTo=` dependencies on other
+    /// containers into compose service dependencies.
+    ///
+    /// Dependencies on other given files are skipped, as compose creates the networks and volumes
+    /// of a service before it is started.
+    fn depends_on(&self, file: &quadlet::File) -> Mapping {
+        let quadlet::Unit {
+            wants,
+            requires,
+            binds_to,
+            ..
+        } = &file.unit;
+
+        let dependencies = binds_to
+            .iter()
+            .map(|unit| (unit, true, true))
+            .chain(requires.iter().map(|unit| (unit, true, false)))
+            .chain(wants.iter().map(|unit| (unit, false, false)));
+
+        let mut depends_on = Mapping::new();
+        for (units, required, restart) in dependencies {
+            for unit in units.split_whitespace() {
+                if let Some(&service) = self.services.get(unit) {
+                    if service == file.name || depends_on.contains_key(service) {
+                        continue;
+                    }
+                    let mut dependency = Mapping::new();
+                    dependency.insert("condition".into(), "service_started".into());
+                    dependency.insert("required".into(), required.into());
+                    dependency.insert("restart".into(), restart.into());
+                    depends_on.insert(service.into(), Value::Mapping(dependency));
+                } else if !self.resource_units.contains(unit) {
+                    eprintln!(
+                        "Warning: dependency `{unit}` of `{}.container` is not one of the given \
+                            files and was ignored",
+                        file.name
+                    );
+                }
+            }
+        }
+
+        depends_on
+    }
+}
+
+/// Insert the options of the `container` which map directly onto compose service fields.
+fn insert_container_options(
+    service: &mut Mapping,
+    name: &str,
+    container: &quadlet::Container,
+) -> color_eyre::Result<()> {
+    insert(service, "container_name", &container.container_name)?;
+    insert(service, "command", &container.exec)?;
+    insert(service, "entrypoint", &container.entrypoint)?;
+    insert(service, "environment", &container.environment)?;
+    insert(service, "env_file", &container.environment_file)?;
+    insert(service, "expose", &container.expose_host_port)?;
+    insert(service, "labels", &container.label)?;
+    insert(service, "annotations", &container.annotation)?;
+    insert(service, "cap_add", &container.add_capability)?;
+    insert(service, "cap_drop", &container.drop_capability)?;
+    insert(service, "extra_hosts", &container.add_host)?;
+    insert(service, "dns_opt", &container.dns_option)?;
+    insert(service, "dns_search", &container.dns_search)?;
+    insert(service, "hostname", &container.host_name)?;
+    insert(service, "group_add", &container.group_add)?;
+    insert(service, "working_dir", &container.working_dir)?;
+    insert(service, "read_only", container.read_only)?;
+    insert(service, "init", container.run_init)?;
+    insert(service, "shm_size", &container.shm_size)?;
+    insert(service, "stop_signal", &container.stop_signal)?;
+    insert(service, "sysctls", &container.sysctl)?;
+    insert(service, "tmpfs", &container.tmpfs)?;
+    insert(service, "userns_mode", &container.user_ns)?;
+    insert(service, "pids_limit", container.pids_limit)?;
+    insert(service, "mem_limit", &container.memory)?;
+    insert(
+        service,
+        "devices",
+        container
+            .add_device
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+    )?;
+    insert(
+        service,
+        "stop_grace_period",
+        container
+            .stop_timeout
+            .map(|stop_timeout| format!("{stop_timeout}s")),
+    )?;
+
+    match &container.dns {
+        Dns::None => eprintln!(
+            "Warning: `DNS=none` of `{name}.container` cannot be exported and was ignored"
+        ),
+        Dns::Custom(dns) => insert(service, "dns", dns)?,
+    }
+
+    match (&container.user, &container.group) {
+        (Some(user), Some(group)) => insert(service, "user", format!("{user}:{group}"))?,
+        (user, None) => insert(service, "user", user)?,
+        (None, Some(_)) => eprintln!(
+            "Warning: `Group=` of `{name}.container` cannot be exported without `User=` and \
+                was ignored"
+        ),
+    }
+
+    match container.pull.as_ref().map(ToString::to_string) {
+        Some(pull) if pull == "newer" => eprintln!(
+            "Warning: `Pull=newer` of `{name}.container` cannot be exported and was ignored"
+        ),
+        pull => insert(service, "pull_policy", pull)?,
+    }
+
+    insert(service, "security_opt", security_opt(container))?;
+    insert(
+        service,
+        "ulimits",
+        ulimits_try_into_mapping(&container.ulimit)?,
+    )?;
+    insert(service, "logging", logging(container)?)?;
+    insert(service, "healthcheck", healthcheck(container)?)?;
+
+    Ok(())
+}
+
+/// Convert the security options of the `container` into compose service `security_opt`.
+fn security_opt(container: &quadlet::Container) -> Vec<String> {
+    let labels = [
+        ("type", &container.security_label_type),
+        ("level", &container.security_label_level),
+        ("filetype", &container.security_label_file_type),
+    ];
+
+    labels
+        .into_iter()
+        .filter_map(|(label, value)| value.as_ref().map(|value| format!("label:{label}:{value}")))
+        .chain(
+            container
+                .security_label_disable
+                .then(|| String::from("label:disable")),
+        )
+        .chain(
+            container
+                .security_label_nested
+                .then(|| String::from("label:nested")),
+        )
+        .chain(
+            container
+                .no_new_privileges
+                .then(|| String::from("no-new-privileges:true")),
+        )
+        .chain(
+            container
+                .app_armor
+                .as_ref()
+                .map(|app_armor| format!("apparmor:{app_armor}")),
+        )
+        .chain(
+            container
+                .seccomp_profile
+                .as_ref()
+                .map(|profile| format!("seccomp:{}", profile.display())),
+        )
+        .collect()
+}
+
+/// Convert `Ulimit=` values, e.g. `nofile=1024:2048`, into compose service `ulimits`.
+fn ulimits_try_into_mapping(ulimits: &[String]) -> color_eyre::Result<Mapping> {
+    let parse = |limit: &str| {
+        limit
+            .parse::<u64>()
+            .map(Value::from)
+            .wrap_err_with(|| format!("invalid ulimit value `{limit}`"))
+    };
+
+    ulimits
+        .iter()
+        .map(|ulimit| {
+            let (name, limits) = ulimit
+                .split_once('=')
+                .ok_or_else(|| eyre!("invalid `Ulimit={ulimit}`, expected `name=limit`"))?;
+            let limits = if let Some((soft, hard)) = limits.split_once(':') {
+                let mut limits = Mapping::new();
+                limits.insert("soft".into(), parse(soft)?);
+                limits.insert("hard".into(), parse(hard)?);
+                Value::Mapping(limits)
+            } else {
+                parse(limits)?
+            };
+            Ok((name.into(), limits))
+        })
+        .collect()
+}
+
+/// Convert `LogDriver=` and `LogOpt=` into compose service `logging`.
+fn logging(container: &quadlet::Container) -> Result<Mapping, serde_yaml::Error> {
+    let options: IndexMap<&str, &str> = container
+        .log_opt
+        .iter()
+        .map(|option| option.split_once('=').unwrap_or((option, "")))
+        .collect();
+
+    let mut logging = Mapping::new();
+    insert(&mut logging, "driver", &container.log_driver)?;
+    insert(&mut logging, "options", options)?;
+    Ok(logging)
+}
+
+/// Convert `Health*=` options into a compose service `healthcheck`.
+fn healthcheck(container: &quadlet::Container) -> Result<Mapping, serde_yaml::Error> {
+    let mut healthcheck = Mapping::new();
+    match container.health_cmd.as_deref() {
+        Some("none") => insert(&mut healthcheck, "disable", true)?,
+        Some(command) => insert(&mut healthcheck, "test", ["CMD-SHELL", command])?,
+        None => {}
+    }
+    insert(&mut healthcheck, "interval", &container.health_interval)?;
+    insert(&mut healthcheck, "timeout", &container.health_timeout)?;
+    insert(&mut healthcheck, "retries", container.health_retries)?;
+    insert(
+        &mut healthcheck,
+        "start_period",
+        &container.health_start_period,
+    )?;
+    Ok(healthcheck)
+}
+
+/// Convert a Quadlet `.build` file into a compose service `build`.
+fn build_try_into_value(build: &Build) -> Result<Mapping, serde_yaml::Error> {
+    // `SetWorkingDirectory=unit` is the directory of the Quadlet file,
+    // which is assumed to be where the compose file will be.
+    let context = match (&build.set_working_directory, &build.file) {
+        (Some(Context::Path(path)), Some(Context::Path(file))) if path == Path::new("file") => {
+            file.parent().map(Path::to_path_buf).map(Context::Path)
+        }
+        (Some(Context::Path(path)), _) if path == Path::new("unit") => None,
+        (context, _) => context.clone(),
+    };
+
+    let mut value = Mapping::new();
+    insert(
+        &mut value,
+        "context",
+        context.unwrap_or_else(|| Context::Path(PathBuf::from("."))),
+    )?;
+    insert(&mut value, "dockerfile", &build.file)?;
+    insert(&mut value, "args", &build.build_arg)?;
+    insert(&mut value, "target", &build.target)?;
+    insert(&mut value, "labels", &build.label)?;
+    Ok(value)
+}
+
+/// Convert a Quadlet `.network` file into a compose network.
+fn network_try_into_value(
+    file_name: &str,
+    network: &quadlet::Network,
+) -> color_eyre::Result<Value> {
+    let driver_opts: IndexMap<&str, &str> = network
+        .options
+        .iter()
+        .map(|option| option.split_once('=').unwrap_or((option, "")))
+        .collect();
+
+    // `Subnet=`, `Gateway=`, and `IPRange=` are grouped by their order.
+    let len = network
+        .subnet
+        .len()
+        .max(network.gateway.len())
+        .max(network.ip_range.len());
+    let mut ipam_config = Vec::with_capacity(len);
+    for index in 0..len {
+        let mut config = Mapping::new();
+        insert(&mut config, "subnet", network.subnet.get(index))?;
+        insert(&mut config, "gateway", network.gateway.get(index))?;
+        match network.ip_range.get(index) {
+            Some(ip_range @ quadlet::IpRange::Cidr(_)) => {
+                insert(&mut config, "ip_range", ip_range)?;
+            }
+            None => {}
+            Some(_) => eprintln!(
+                "Warning: `IPRange=` values in `{file_name}` which are not in CIDR notation \
+                    cannot be exported and were ignored"
+            ),
+        }
+        ipam_config.push(config);
+    }
+
+    let mut ipam = Mapping::new();
+    insert(&mut ipam, "driver", &network.ipam_driver)?;
+    insert(&mut ipam, "config", ipam_config)?;
+
+    let mut value = Mapping::new();
+    insert(&mut value, "driver", &network.driver)?;
+    insert(&mut value, "driver_opts", driver_opts)?;
+    insert(&mut value, "internal", network.internal)?;
+    insert(&mut value, "enable_ipv6", network.ipv6)?;
+    insert(&mut value, "labels", &network.label)?;
+    insert(&mut value, "ipam", ipam)?;
+    Ok(if value.is_empty() {
+        Value::Null
+    } else {
+        Value::Mapping(value)
+    })
+}
+
+/// Convert a Quadlet `.volume` file into a compose volume.
+///
+/// The options are converted into `driver_opts`, like `podman volume create --opt`.
+fn volume_try_into_value(volume: &quadlet::Volume) -> Result<Value, serde_yaml::Error> {
+    let mount_options: Vec<String> = volume
+        .options
+        .iter()
+        .cloned()
+        .chain(volume.user.as_ref().map(|user| format!("uid={user}")))
+        .chain(volume.group.as_ref().map(|group| format!("gid={group}")))
+        .collect();
+
+    let mut driver_opts = Mapping::new();
+    insert(&mut driver_opts, "type", &volume.fs_type)?;
+    insert(&mut driver_opts, "device", &volume.device)?;
+    insert(
+        &mut driver_opts,
+        "o",
+        (!mount_options.is_empty()).then(|| mount_options.join(",")),
+    )?;
+    insert(&mut driver_opts, "copy", volume.copy.then_some("true"))?;
+    insert(&mut driver_opts, "image", &volume.image)?;
+
+    let mut value = Mapping::new();
+    insert(&mut value, "driver", &volume.driver)?;
+    insert(&mut value, "driver_opts", driver_opts)?;
+    insert(&mut value, "labels", &volume.label)?;
+    Ok(if value.is_empty() {
+        Value::Null
+    } else {
+        Value::Mapping(value)
+    })
+}
+
+/// Print a warning listing the options of the `file`'s resource which are not `supported`.
+///
+/// # Errors
+///
+/// Returns an error if the resource could not be serialized.
+fn warn_unsupported(file: &quadlet::File, supported: &[&str]) -> color_eyre::Result<()> {
+    let mut unsupported: Vec<String> = entries(&file.resource)?
+        .into_iter()
+        .map(|entry| entry.key)
+        .filter(|key| !supported.contains(&key.as_str()))
+        .map(|key| format!("`{key}=`"))
+        .collect();
+    unsupported.dedup();
+
+    let quadlet::Globals {
+        containers_conf_module,
+        global_args,
+        service_name: _,
+    } = &file.globals;
+    if !containers_conf_module.is_empty() {
+        unsupported.push(String::from("`ContainersConfModule=`"));
+    }
+    if global_args.is_some() {
+        unsupported.push(String::from("`GlobalArgs=`"));
+    }
+
+    if !unsupported.is_empty() {
+        eprintln!(
+            "Warning: options in `{}.{}` which cannot be exported were ignored: {}",
+            file.name,
+            file.resource.extension(),
+            unsupported.join(", "),
+        );
+    }
+
+    Ok(())
+}
+
+/// Insert `value` into the `mapping` as `key`, unless it is [`None`], empty, or `false`.
+fn insert(
+    mapping: &mut Mapping,
+    key: &str,
+    value: impl Serialize,
+) -> Result<(), serde_yaml::Error> {
+    let value = serde_yaml::to_value(value)?;
+    let skip = match &value {
+        Value::Null | Value::Bool(false) => true,
+        Value::String(string) => string.is_empty(),
+        Value::Sequence(sequence) => sequence.is_empty(),
+        Value::Mapping(mapping) => mapping.is_empty(),
+        _ => false,
+    };
+    if !skip {
+        mapping.insert(key.into(), value);
+    }
+    Ok(())
+}
+
+/// Compose value for an external network, volume, or secret.
+fn external() -> Value {
+    let mut external = Mapping::new();
+    external.insert("external".into(), true.into());
+    Value::Mapping(external)
+}
+
+/// Error for when a Quadlet `option` references a `file` which was not given.
+fn not_found(file: &str, option: &str) -> color_eyre::Report {
+    eyre!("`{option}={file}` references a file which is not one of the given files").suggestion(
+        format!("Include the `{file}` Quadlet file in the files to export."),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(files: &[(&str, &str)]) -> color_eyre::Result<Vec<quadlet::File>> {
+        files
+            .iter()
+            .map(|(name, contents)| {
+                quadlet::File::deserialize_from_quadlet((*name).to_owned(), contents)
+                    .map_err(Into::into)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn services_networks_and_volumes() -> color_eyre::Result<()> {
+        let files = files(&[
+            (
+                "web",
+                "[Unit]\n\
+                Requires=db.service\n\
+                Wants=cache.container app-network.service\n\
+                [Container]\n\
+                Image=web.build\n\
+                PublishPort=8080:80\n\
+                Network=app.network\n\
+                Volume=data.volume:/data:ro\n\
+                Environment=ONE=one\n\
+                StopTimeout=20\n\
+                [Service]\n\
+                Restart=always\n",
+            ),
+            (
+                "db",
+                "[Container]\n\
+                Image=db.image\n\
+                Volume=db-data:/var/lib/db\n\
+                Secret=password\n",
+            ),
+            (
+                "cache",
+                "[Unit]\n\
+                BindsTo=db.service\n\
+                [Container]\n\
+                Image=docker.io/library/redis\n\
+                Network=host\n\
+                Ulimit=nofile=1024:2048\n",
+            ),
+            ("app", "[Network]\nSubnet=10.0.0.0/24\nGateway=10.0.0.1\n"),
+            ("data", "[Volume]\nDriver=local\nUser=1000\n"),
+            ("db", "[Image]\nImage=docker.io/library/postgres\n"),
+            (
+                "web",
+                "[Build]\nImageTag=localhost/web\nSetWorkingDirectory=unit\n",
+            ),
+        ])?;
+
+        let expected = compose_spec::Compose::options().from_yaml_str(
+            "
+            services:
+              web:
+                build:
+                  context: .
+                image: localhost/web
+                environment: [ONE=one]
+                stop_grace_period: 20s
+                ports: ['8080:80']
+                networks: [app]
+                volumes: ['data:/data:ro']
+                depends_on:
+                  db:
+                    condition: service_started
+                  cache:
+                    condition: service_started
+                    required: false
+                restart: always
+              db:
+                image: docker.io/library/postgres
+                volumes: ['db-data:/var/lib/db']
+                secrets: [password]
+              cache:
+                image: docker.io/library/redis
+                network_mode: host
+                ulimits:
+                  nofile:
+                    soft: 1024
+                    hard: 2048
+                depends_on:
+                  db:
+                    condition: service_started
+                    restart: true
+            networks:
+              app:
+                ipam:
+                  config:
+                    - subnet: 10.0.0.0/24
+                      gateway: 10.0.0.1
+            volumes:
+              data:
+                driver: local
+                driver_opts:
+                  o: uid=1000
+              db-data:
+                external: true
+            secrets:
+              password:
+                external: true
+            ",
+        )?;
+
+        assert_eq!(files_try_into_compose(&files)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pod() -> color_eyre::Result<()> {
+        let files = files(&[
+            ("app", "[Pod]\nPublishPort=8080:80\nNetwork=app.network\n"),
+            ("app", "[Network]\n"),
+            ("web", "[Container]\nImage=web\nPod=app.pod\n"),
+            ("sidecar", "[Container]\nImage=sidecar\nPod=app.pod\n"),
+        ])?;
+
+        let expected = compose_spec::Compose::options().from_yaml_str(
+            "
+            services:
+              web:
+                image: web
+                ports: ['8080:80']
+                networks: [app]
+              sidecar:
+                image: sidecar
+                network_mode: service:web
+            networks:
+              app:
+            ",
+        )?;
+
+        assert_eq!(files_try_into_compose(&files)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_reference() -> color_eyre::Result<()> {
+        let files = files(&[("web", "[Container]\nImage=web\nNetwork=missing.network\n")])?;
+        assert!(files_try_into_compose(&files).is_err());
+        Ok(())
+    }
+}
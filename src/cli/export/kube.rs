@@ -0,0 +1,457 @@
+//! Provides the `podlet export kube` subcommand, see [`Kube`].
+//!
+//! The Quadlet files are first converted into compose services, see [`files_try_into_compose()`],
+//! which are then converted into a [`k8s::File`] like `podlet compose --kube`.
+
+use std::{collections::BTreeMap, mem, path::PathBuf, time::Duration};
+
+use clap::{ArgAction, Args, Subcommand};
+use color_eyre::{
+    Help,
+    eyre::{OptionExt, WrapErr, eyre},
+};
+use compose_spec::{
+    Identifier, ListOrMap, Name,
+    service::{DependsOn, Restart},
+};
+use k8s_openapi::api::core::v1::Pod;
+use serde_yaml::Value;
+
+use crate::{
+    cli::{Container, PodmanCommands, k8s, rewrite::read_quadlet_file},
+    quadlet::{self, GenericSections, Globals, Resource, RestartConfig},
+};
+
+use super::compose::files_try_into_compose;
+
+/// Compose service fields which can be converted into a Kubernetes pod container.
+const CONTAINER_FIELDS: &[&str] = &[
+    "cap_add",
+    "cap_drop",
+    "command",
+    "container_name",
+    "cpus",
+    "cpuset",
+    "entrypoint",
+    "environment",
+    "healthcheck",
+    "image",
+    "mem_limit",
+    "mem_reservation",
+    "pids_limit",
+    "ports",
+    "privileged",
+    "pull_policy",
+    "read_only",
+    "security_opt",
+    "stdin_open",
+    "stop_signal",
+    "tmpfs",
+    "tty",
+    "user",
+    "volumes",
+    "working_dir",
+];
+
+/// `security_opt` prefixes which can be converted into Kubernetes SELinux options.
+const SELINUX_OPTIONS: &[&str] = &["label:user:", "label:role:", "label:type:", "label:level:"];
+
+/// [`Args`] for the `podlet export kube` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+#[command(subcommand_negates_reqs = true)]
+pub struct Kube {
+    /// The name of the pod
+    ///
+    /// Defaults to the name of the `.pod` file, or the `.container` file if only one is given.
+    #[arg(short, long)]
+    pub name: Option<String>,
+
+    /// The Quadlet files to convert
+    ///
+    /// The type of each file is determined by its extension, e.g. `.container` or `.volume`.
+    /// `.network` files are accepted but only used to validate references,
+    /// as the containers in a pod share its network.
+    #[arg(required = true, value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+
+    /// Convert a Podman command instead of Quadlet files
+    #[command(subcommand)]
+    pub podman: Option<PodmanRun>,
+}
+
+/// [`Subcommand`] for converting a Podman command with `podlet export kube`.
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum PodmanRun {
+    /// Convert a `podman run` command into Kubernetes YAML
+    #[command(disable_help_flag = true)]
+    Run {
+        /// The \[Container\] section
+        #[command(flatten)]
+        container: Box<Container>,
+
+        /// Configure if and when the service should be restarted.
+        #[arg(long, value_name = "POLICY")]
+        restart: Option<RestartConfig>,
+
+        /// Print help
+        // Changed from default to support `podman run -h`, i.e. `podman run --hostname`.
+        #[arg(
+            short = '?',
+            long,
+            action = ArgAction::Help,
+            help = "Print help (see more with '--help')",
+            long_help = "Print help (see a summary with '-?')"
+        )]
+        help: (),
+    },
+}
+
+impl Kube {
+    /// Read the Quadlet files, or convert the Podman command, and print them as Kubernetes YAML.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file could not be read, or the files could not be converted.
+    pub fn run(&self) -> color_eyre::Result<()> {
+        let files = if let Some(PodmanRun::Run {
+            container, restart, ..
+        }) = &self.podman
+        {
+            let command = PodmanCommands::Run {
+                container: container.clone(),
+                restart: *restart,
+                help: (),
+            };
+            vec![command.into_quadlet(None, GenericSections::default(), Globals::default())]
+        } else {
+            self.files
+                .iter()
+                .map(|path| read_quadlet_file(path))
+                .collect::<color_eyre::Result<_>>()?
+        };
+
+        let file = files_try_into_k8s(self.name.as_deref(), &files)?;
+        let file = file
+            .serialize_to_yaml()
+            .wrap_err("error serializing Kubernetes YAML")?;
+        print!("{file}");
+
+        Ok(())
+    }
+}
+
+/// Convert Quadlet `files` into a Kubernetes [`Pod`] and
+/// [`PersistentVolumeClaim`](k8s_openapi::api::core::v1::PersistentVolumeClaim)s.
+///
+/// Each `.container` file becomes a container in the pod. If not given, the `name` of the pod is
+/// taken from the `.pod` file or the only `.container` file.
+///
+/// A warning is printed for each option which cannot be converted.
+///
+/// # Errors
+///
+/// Returns an error if the name of the pod could not be determined, the files could not be
+/// converted into compose services, or the services could not be converted into Kubernetes
+/// containers.
+pub fn files_try_into_k8s(
+    name: Option<&str>,
+    files: &[quadlet::File],
+) -> color_eyre::Result<k8s::File> {
+    let name = name.map_or_else(|| pod_name(files), Ok)?;
+    let name = Name::new(name)
+        .wrap_err_with(|| format!("`{name}` is not a valid pod name"))
+        .suggestion("Set the name of the pod with `--name`.")?;
+
+    let compose_spec::Compose {
+        services,
+        volumes,
+        secrets,
+        ..
+    } = files_try_into_compose(files)?;
+
+    if !secrets.is_empty() {
+        eprintln!("Warning: secrets cannot be exported to Kubernetes YAML and were ignored");
+    }
+
+    let mut pod_options = PodOptions::default();
+    let services = services
+        .into_iter()
+        .map(|(name, service)| {
+            pod_options
+                .take_from(&name, service)
+                .wrap_err_with(|| format!("error converting container `{name}`"))
+                .map(|service| (name, service))
+        })
+        .collect::<color_eyre::Result<_>>()?;
+
+    let compose = compose_spec::Compose {
+        name: Some(name),
+        services,
+        volumes,
+        ..compose_spec::Compose::default()
+    };
+    let mut file =
+        k8s::File::try_from(compose).wrap_err("error converting into Kubernetes YAML")?;
+    pod_options.add_to_pod(&mut file.pod);
+
+    Ok(file)
+}
+
+/// Returns the name of the only `.pod` file, or the only `.container` file if there are no pods.
+///
+/// # Errors
+///
+/// Returns an error if there is more than one pod, or no pod and more than one container.
+fn pod_name(files: &[quadlet::File]) -> color_eyre::Result<&str> {
+    let names = |kind: fn(&Resource) -> bool| {
+        files
+            .iter()
+            .filter(move |file| kind(&file.resource))
+            .map(|file| file.name.as_str())
+    };
+
+    let mut pods = names(|resource| matches!(resource, Resource::Pod(_)));
+    let mut containers = names(|resource| matches!(resource, Resource::Container(_)));
+
+    match (pods.next(), pods.next()) {
+        (Some(pod), None) => Ok(pod),
+        (Some(_), Some(_)) => Err(eyre!("only one `.pod` file can be exported")),
+        (None, _) => containers
+            .next()
+            .filter(|_| containers.next().is_none())
+            .ok_or_eyre("the name of the pod could not be determined")
+            .suggestion("Set the name of the pod with `--name`."),
+    }
+}
+
+/// Options of the compose services which apply to the whole Kubernetes [`Pod`].
+#[derive(Default)]
+struct PodOptions {
+    labels: BTreeMap<String, String>,
+    annotations: BTreeMap<String, String>,
+    hostname: Option<String>,
+    restart: Option<Restart>,
+    stop_grace_period: Option<Duration>,
+}
+
+impl PodOptions {
+    /// Take the options of the `service` which apply to the whole pod, and remove the options
+    /// which are not supported by Kubernetes containers.
+    ///
+    /// A warning is printed for each removed option, other than the service's networks and
+    /// dependencies as the containers in a pod share its network and are started together.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the labels or annotations are invalid.
+    fn take_from(
+        &mut self,
+        name: &Identifier,
+        mut service: compose_spec::Service,
+    ) -> color_eyre::Result<compose_spec::Service> {
+        self.labels
+            .extend(list_or_map_into_map(mem::take(&mut service.labels))?);
+        self.annotations
+            .extend(list_or_map_into_map(mem::take(&mut service.annotations))?);
+
+        if let Some(hostname) = service.hostname.take() {
+            self.hostname.get_or_insert_with(|| hostname.into());
+        }
+
+        if let Some(restart) = service.restart.take() {
+            match self.restart {
+                Some(pod_restart) if pod_restart != restart => eprintln!(
+                    "Warning: the restart policy of container `{name}` is different from the \
+                        pod's and was ignored"
+                ),
+                _ => self.restart = Some(restart),
+            }
+        }
+
+        self.stop_grace_period = self.stop_grace_period.max(service.stop_grace_period.take());
+
+        service.network_config = None;
+        service.depends_on = DependsOn::default();
+
+        service.security_opt.retain(|security_opt| {
+            let supported = SELINUX_OPTIONS
+                .iter()
+                .any(|prefix| security_opt.starts_with(prefix));
+            if !supported {
+                eprintln!(
+                    "Warning: security option `{security_opt}` of container `{name}` cannot be \
+                        exported to Kubernetes YAML and was ignored"
+                );
+            }
+            supported
+        });
+
+        let Value::Mapping(mut service) = serde_yaml::to_value(service)? else {
+            return Err(eyre!("service is not a mapping"));
+        };
+
+        let unsupported: Vec<String> = service
+            .keys()
+            .filter_map(Value::as_str)
+            .filter(|field| !CONTAINER_FIELDS.contains(field))
+            .map(|field| format!("`{field}`"))
+            .collect();
+        if !unsupported.is_empty() {
+            eprintln!(
+                "Warning: fields of container `{name}` which cannot be exported to Kubernetes \
+                    YAML were ignored: {}",
+                unsupported.join(", ")
+            );
+        }
+        service.retain(|field, _| {
+            field
+                .as_str()
+                .is_some_and(|field| CONTAINER_FIELDS.contains(&field))
+        });
+
+        serde_yaml::from_value(Value::Mapping(service)).map_err(Into::into)
+    }
+
+    /// Add the options to the [`Pod`]'s metadata and spec.
+    fn add_to_pod(self, pod: &mut Pod) {
+        let Self {
+            labels,
+            annotations,
+            hostname,
+            restart,
+            stop_grace_period,
+        } = self;
+
+        if !labels.is_empty() {
+            pod.metadata.labels.get_or_insert_default().extend(labels);
+        }
+        if !annotations.is_empty() {
+            pod.metadata
+                .annotations
+                .get_or_insert_default()
+                .extend(annotations);
+        }
+
+        let spec = pod.spec.get_or_insert_default();
+        spec.hostname = hostname;
+        spec.restart_policy = restart.map(|restart| {
+            match restart {
+                Restart::No => "Never",
+                Restart::Always | Restart::UnlessStopped => "Always",
+                Restart::OnFailure => "OnFailure",
+            }
+            .to_owned()
+        });
+        spec.termination_grace_period_seconds = stop_grace_period
+            .map(|stop_grace_period| stop_grace_period.as_secs().try_into().unwrap_or(i64::MAX));
+    }
+}
+
+/// Convert compose labels or annotations into a Kubernetes metadata map.
+///
+/// # Errors
+///
+/// Returns an error if a key is invalid.
+fn list_or_map_into_map(
+    list_or_map: ListOrMap,
+) -> color_eyre::Result<impl Iterator<Item = (String, String)>> {
+    Ok(list_or_map
+        .into_map()?
+        .into_iter()
+        .map(|(key, value)| (key.into(), value.map(Into::into).unwrap_or_default())))
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::ContainerPort;
+
+    use super::*;
+
+    #[test]
+    fn pod_with_volume() -> color_eyre::Result<()> {
+        let files = [
+            ("app", "[Pod]\nPublishPort=8080:80\n"),
+            (
+                "web",
+                "[Container]\n\
+                Image=docker.io/library/nginx\n\
+                Pod=app.pod\n\
+                Environment=ONE=one\n\
+                Volume=data.volume:/data\n\
+                Label=tier=web\n\
+                SecurityLabelType=container_t\n\
+                DNSOption=ndots:1\n\
+                [Service]\n\
+                Restart=always\n",
+            ),
+            ("sidecar", "[Container]\nImage=sidecar\nPod=app.pod\n"),
+            ("data", "[Volume]\nDriver=local\n"),
+        ]
+        .into_iter()
+        .map(|(name, contents)| quadlet::File::deserialize_from_quadlet(name.into(), contents))
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let file = files_try_into_k8s(None, &files)?;
+        assert_eq!(file.name, "app");
+        assert_eq!(file.persistent_volume_claims.len(), 1);
+
+        let pod = &file.pod;
+        assert_eq!(
+            pod.metadata.labels,
+            Some([(String::from("tier"), String::from("web"))].into())
+        );
+
+        let spec = pod.spec.as_ref().ok_or_eyre("pod has no spec")?;
+        assert_eq!(spec.restart_policy.as_deref(), Some("Always"));
+
+        let [web, sidecar] = spec.containers.as_slice() else {
+            return Err(eyre!("pod does not have two containers"));
+        };
+        assert_eq!(web.name, "web");
+        assert_eq!(sidecar.name, "sidecar");
+        assert_eq!(
+            web.ports,
+            Some(vec![ContainerPort {
+                container_port: 80,
+                host_port: Some(8080),
+                ..ContainerPort::default()
+            }])
+        );
+        assert_eq!(
+            web.env
+                .as_ref()
+                .and_then(|env| env.first())
+                .map(|env| (env.name.as_str(), env.value.as_deref())),
+            Some(("ONE", Some("one")))
+        );
+        assert_eq!(
+            web.security_context
+                .as_ref()
+                .and_then(|security_context| security_context.se_linux_options.as_ref())
+                .and_then(|selinux_options| selinux_options.type_.as_deref()),
+            Some("container_t")
+        );
+        assert!(web.volume_mounts.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pod_name_required() -> color_eyre::Result<()> {
+        let files = [
+            quadlet::File::deserialize_from_quadlet("one".into(), "[Container]\nImage=one\n")?,
+            quadlet::File::deserialize_from_quadlet("two".into(), "[Container]\nImage=two\n")?,
+        ];
+
+        assert!(files_try_into_k8s(None, &files).is_err());
+        assert_eq!(
+            files_try_into_k8s(Some("pod"), &files)?
+                .pod
+                .spec
+                .map(|spec| spec.containers.len()),
+            Some(2)
+        );
+
+        Ok(())
+    }
+}
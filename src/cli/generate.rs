@@ -1,10 +1,17 @@
 //! Provides the `podlet generate` subcommand, see [`Generate`].
 //!
-//! `podlet generate` uses the `podman inspect` commands to get information on the selected
-//! resource. The information is converted into a [`PodmanCommands`] which, in turn, is turned into
-//! a [`crate::quadlet::File`].
+//! `podlet generate` uses the `podman inspect` commands, or the equivalent requests to the Podman
+//! REST API, to get information on the selected resource. The information is converted into a
+//! [`PodmanCommands`] which, in turn, is turned into a [`crate::quadlet::File`].
+
+mod api;
+mod build;
+mod container;
+mod kube;
+mod pod;
 
 use std::{
+    collections::HashSet,
     env,
     marker::PhantomData,
     net::{IpAddr, Ipv4Addr, Ipv6Addr},
@@ -12,22 +19,31 @@ use std::{
     process::Command,
 };
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use color_eyre::{
     Section, SectionExt,
     eyre::{WrapErr, eyre},
 };
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 use ipnet::IpNet;
 use serde::{
     Deserialize, Deserializer,
     de::{self, DeserializeOwned, MapAccess, SeqAccess, Visitor, value::MapAccessDeserializer},
 };
 
-use crate::quadlet::{self, GenericSections, Globals, IpRange, ResourceKind, Service};
+use crate::quadlet::{
+    self, GenericSections, Globals, IpRange, ResourceKind, RestartConfig, Service,
+    container::volume::Source,
+};
+
+use self::{
+    build::BuildInspect,
+    container::{ContainerInspect, ImageConfig},
+    pod::PodInspect,
+};
 
 use super::{
-    Container, Image, Network, Pod, Volume, global_args::GlobalArgs, image, network, volume,
+    Container, File, Image, Network, Pod, Volume, global_args::GlobalArgs, image, network, volume,
 };
 
 /// [`Subcommand`] for `podlet generate`
@@ -36,7 +52,27 @@ pub enum Generate {
     /// Generate a Quadlet file from an existing container
     ///
     /// The command used to create the container is parsed to generate the Quadlet file.
+    ///
+    /// Containers created through the Podman API or a Docker-compatible client have no recorded
+    /// create command. For these, the Quadlet file is generated from the container's settings in
+    /// the output of `podman container inspect`, and warnings are printed for settings which could
+    /// not be reproduced.
     Container {
+        /// Also generate Quadlet files for the networks and named volumes the container uses
+        ///
+        /// The networks and volumes are inspected with `podman network inspect` and
+        /// `podman volume inspect`. The container references the generated `.network` and
+        /// `.volume` files instead of the network and volume names, so that Quadlet adds the
+        /// dependencies between the units. The generated files set `NetworkName=` and
+        /// `VolumeName=` so that the existing network and volumes continue to be used.
+        ///
+        /// Options for the `[Unit]` and `[Install]` sections, like `--install`, only apply to the
+        /// container.
+        ///
+        /// Podman's default networks, `podman` and `podman-default-kube-network`, are skipped.
+        #[arg(long)]
+        with_resources: bool,
+
         /// Name or ID of the container
         ///
         /// Passed to `podman container inspect`.
@@ -47,8 +83,11 @@ pub enum Generate {
     ///
     /// Creates a `.pod` Quadlet file and a `.container` Quadlet file for each container in the pod.
     ///
-    /// Only supports pods created with `podman pod create`.
     /// The command used to create the pod is parsed to generate the Quadlet file.
+    ///
+    /// Pods created with `podman kube play` or through the Podman API have no usable create
+    /// command. For these, the Quadlet file is generated from the pod's settings in the output of
+    /// `podman pod inspect`, and warnings are printed for settings which could not be reproduced.
     Pod {
         /// Ignore the `podman pod create --infra-conmon-pidfile` option if it is set.
         ///
@@ -102,32 +141,105 @@ pub enum Generate {
         /// Passed to `podman image inspect`.
         image: String,
     },
+
+    /// Generate a `.build` Quadlet file from an image in local storage
+    ///
+    /// The image's tags, labels, and annotations are used, excluding labels inherited from the
+    /// base image. Build arguments are recovered from the image's history.
+    ///
+    /// The build context is taken from the `org.opencontainers.image.source` annotation or label
+    /// if it is a path or a Git repository URL, i.e. with the `git` scheme or ending in `.git`.
+    /// The Containerfile and target stage cannot be recovered, so `File=` and `Target=` may need
+    /// to be set in the generated file.
+    Build {
+        /// Name of the image
+        ///
+        /// Passed to `podman image inspect`.
+        image: String,
+    },
+
+    /// Generate a `.kube` Quadlet file from existing pods or containers
+    ///
+    /// Runs `podman kube generate` on the pods or containers. The generated Kubernetes YAML is
+    /// written next to the `.kube` file as `NAME-kube.yaml`. The name is taken from the first pod
+    /// or container, unless set with `--name`.
+    ///
+    /// Networks are carried over to the `.kube` file, as well as `io.containers.autoupdate`
+    /// annotations. Published ports are included in the YAML.
+    ///
+    /// The `--file` option must be a directory if used.
+    Kube {
+        /// Names or IDs of the pods or containers
+        ///
+        /// Passed to `podman kube generate`.
+        #[arg(required = true, value_name = "POD|CONTAINER")]
+        resources: Vec<String>,
+    },
+
+    /// Generate Quadlet files from all running containers and pods, networks, and volumes
+    ///
+    /// Creates a `.container` file for each running container not in a pod, a `.pod` file and
+    /// `.container` files for each running pod, a `.network` file for each network, and a
+    /// `.volume` file for each named volume. Containers and pods reference the generated
+    /// `.network` and `.volume` files instead of the network and volume names. The generated files
+    /// set `NetworkName=` and `VolumeName=` so that the existing networks and volumes continue to
+    /// be used.
+    ///
+    /// Podman's default networks, `podman` and `podman-default-kube-network`, are skipped.
+    All {
+        /// Only include resources with the label
+        ///
+        /// Passed to the `podman ls` commands as `--filter label=LABEL`.
+        ///
+        /// Can be specified multiple times.
+        #[arg(long, value_name = "KEY[=VALUE]")]
+        label: Vec<String>,
+
+        /// Only include resources with a name matching the filter
+        ///
+        /// Passed to the `podman ls` commands as `--filter name=NAME_FILTER`.
+        #[arg(long)]
+        name_filter: Option<String>,
+    },
 }
 
 impl Generate {
-    /// Inspect the given resource by running a Podman command, deserializing the output,
-    /// and transforming it into one or more [`quadlet::File`]s.
+    /// Inspect the given resource by running a Podman command, or with the Podman API depending on
+    /// the `connection`, deserializing the output, and transforming it into one or more [`File`]s.
     ///
     /// # Errors
     ///
-    /// Returns an error if there is a problem running the Podman command
-    /// or its output could not be deserialized.
-    pub fn try_into_quadlet_files(
+    /// Returns an error if there is a problem running the Podman command or requesting from the
+    /// API, or the output could not be deserialized.
+    pub fn try_into_files(
         self,
+        connection: Connection,
         name: Option<String>,
         sections: GenericSections,
-    ) -> color_eyre::Result<Vec<quadlet::File>> {
-        match self {
-            Self::Container { container } => Ok(vec![
-                ContainerParser::from_container(&container)?
-                    .into_quadlet_file(None, name, sections),
-            ]),
+    ) -> color_eyre::Result<Vec<File>> {
+        let backend = Backend::from(connection);
+        let files = match self {
+            Self::Container {
+                with_resources,
+                container,
+            } => {
+                let container = ContainerParser::from_container(&backend, &container)?
+                    .into_quadlet_file(None, name, sections.clone());
+                if with_resources {
+                    let mut files = used_resources(&backend, &container)?;
+                    files.push(container);
+                    reference_generated(&mut files);
+                    Ok(files)
+                } else {
+                    Ok(vec![container])
+                }
+            }
             Self::Pod {
                 ignore_infra_conmon_pidfile,
                 ignore_pod_id_file,
                 pod,
             } => {
-                let pod = PodParser::from_pod(&pod)?;
+                let pod = PodParser::from_pod(&backend, &pod)?;
 
                 if pod.infra_conmon_pidfile.is_some() && !ignore_infra_conmon_pidfile {
                     Err(eyre!(
@@ -150,16 +262,279 @@ impl Generate {
                 }
             }
             Self::Network { network } => Ok(vec![
-                NetworkInspect::from_network(&network)?.into_quadlet_file(name, sections),
+                NetworkInspect::from_network(&backend, &network)?.into_quadlet_file(name, sections),
             ]),
             Self::Volume { volume } => Ok(vec![
-                VolumeInspect::from_volume(&volume)?.into_quadlet_file(name, sections),
+                VolumeInspect::from_volume(&backend, &volume)?.into_quadlet_file(name, sections),
             ]),
             Self::Image { image } => Ok(vec![
-                ImageInspect::from_image(&image)?.into_quadlet_file(name, sections),
+                ImageInspect::from_image(&backend, &image)?.into_quadlet_file(name, sections),
             ]),
+            Self::All { label, name_filter } => {
+                if name.is_some() {
+                    return Err(eyre!("cannot set `--name` with `podlet generate all`"))
+                        .note("`podlet generate all` generates multiple Quadlet files");
+                }
+                let filters: Vec<String> = label
+                    .iter()
+                    .map(|label| format!("label={label}"))
+                    .chain(name_filter.map(|name| format!("name={name}")))
+                    .collect();
+                generate_all(&backend, &filters, &sections)
+            }
+            Self::Build { image } => Ok(vec![
+                BuildInspect::from_image(&backend, &image)?
+                    .into_quadlet_file(&backend, &image, name, sections),
+            ]),
+            Self::Kube { resources } => {
+                return kube::generate(&backend, &resources, name, sections);
+            }
+        }?;
+
+        Ok(files.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Networks created by Podman which do not need a Quadlet file.
+const DEFAULT_NETWORKS: &[&str] = &["podman", "podman-default-kube-network"];
+
+/// Generate Quadlet files from all running containers and pods, networks, and volumes matching
+/// the `filters`.
+///
+/// # Errors
+///
+/// Returns an error if a resource could not be listed or inspected, or a creation command could
+/// not be parsed.
+fn generate_all(
+    backend: &Backend,
+    filters: &[String],
+    sections: &GenericSections,
+) -> color_eyre::Result<Vec<quadlet::File>> {
+    let mut files = Vec::new();
+
+    for NetworkListing { name } in backend.list(ResourceKind::Network, filters)? {
+        if !DEFAULT_NETWORKS.contains(&name.as_str()) {
+            files.push(
+                NetworkInspect::from_network(backend, &name)?
+                    .into_quadlet_file(None, sections.clone()),
+            );
+        }
+    }
+
+    for VolumeListing { name, anonymous } in backend.list(ResourceKind::Volume, filters)? {
+        if !anonymous {
+            files.push(
+                VolumeInspect::from_volume(backend, &name)?
+                    .into_quadlet_file(None, sections.clone()),
+            );
         }
     }
+
+    let mut pod_filters = filters.to_vec();
+    pod_filters.push(String::from("status=running"));
+    for PodListing { name } in backend.list(ResourceKind::Pod, &pod_filters)? {
+        let mut pod = PodParser::from_pod(backend, &name)?;
+        if pod.infra_conmon_pidfile.take().is_some() {
+            eprintln!(
+                "Warning: the `--infra-conmon-pidfile` option of pod `{name}` was ignored \
+                    as it is set by Quadlet"
+            );
+        }
+        if pod.pod_id_file.take().is_some() {
+            eprintln!(
+                "Warning: the `--pod-id-file` option of pod `{name}` was ignored \
+                    as it is set by Quadlet"
+            );
+        }
+        files.extend(pod.into_quadlet_files(Some(name), sections.clone()));
+    }
+
+    // Running containers are listed by default.
+    for ContainerListing {
+        names,
+        pod,
+        is_infra,
+    } in backend.list(ResourceKind::Container, filters)?
+    {
+        // Containers in pods are generated with their pod.
+        let Some(name) = names
+            .into_iter()
+            .next()
+            .filter(|_| pod.is_empty() && !is_infra)
+        else {
+            continue;
+        };
+        files.push(
+            ContainerParser::from_container(backend, &name)?.into_quadlet_file(
+                None,
+                Some(name),
+                sections.clone(),
+            ),
+        );
+    }
+
+    reference_generated(&mut files);
+
+    Ok(files)
+}
+
+/// Inspect the networks and named volumes used by the container or pod `file` and generate
+/// Quadlet files for them.
+///
+/// Network modes, like `host`, and Podman's [default networks](DEFAULT_NETWORKS) are skipped.
+///
+/// The generated files do not get the `[Unit]`, `[Quadlet]`, or `[Install]` sections given to the
+/// container or pod.
+///
+/// # Errors
+///
+/// Returns an error if a network or volume could not be inspected.
+fn used_resources(
+    backend: &Backend,
+    file: &quadlet::File,
+) -> color_eyre::Result<Vec<quadlet::File>> {
+    let (network, volume) = match &file.resource {
+        quadlet::Resource::Container(container) => (&container.network, &container.volume),
+        quadlet::Resource::Pod(pod) => (&pod.network, &pod.volume),
+        _ => return Ok(Vec::new()),
+    };
+
+    let networks = network_names(network);
+    let volumes: IndexSet<&str> = volume
+        .iter()
+        .filter_map(|volume| match &volume.source {
+            Some(Source::NamedVolume(name)) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut files = Vec::with_capacity(networks.len() + volumes.len());
+    for network in networks {
+        files.push(
+            NetworkInspect::from_network(backend, network)?
+                .into_quadlet_file(None, GenericSections::default()),
+        );
+    }
+    for volume in volumes {
+        files.push(
+            VolumeInspect::from_volume(backend, volume)?
+                .into_quadlet_file(None, GenericSections::default()),
+        );
+    }
+    Ok(files)
+}
+
+/// Values of `Network=` which are a network mode rather than a network.
+const NETWORK_MODES: &[&str] = &[
+    "bridge",
+    "container",
+    "host",
+    "none",
+    "ns",
+    "pasta",
+    "private",
+    "slirp4netns",
+];
+
+/// Names of the networks in the `Network=` values, without their options.
+///
+/// Network modes, like `host`, and Podman's [default networks](DEFAULT_NETWORKS) are skipped.
+fn network_names(network: &[String]) -> IndexSet<&str> {
+    network
+        .iter()
+        .map(|network| {
+            network
+                .split_once(':')
+                .map_or(network.as_str(), |(name, _)| name)
+        })
+        .filter(|name| !NETWORK_MODES.contains(name) && !DEFAULT_NETWORKS.contains(name))
+        .collect()
+}
+
+/// Reference the `.network` and `.volume` files in `files` from the `Network=` and `Volume=`
+/// options of the container and pod files.
+///
+/// Networks and volumes are matched by the name of their Quadlet file.
+fn reference_generated(files: &mut [quadlet::File]) {
+    let mut networks = HashSet::new();
+    let mut volumes = HashSet::new();
+    for file in &*files {
+        match file.resource {
+            quadlet::Resource::Network(_) => {
+                networks.insert(file.name.clone());
+            }
+            quadlet::Resource::Volume(_) => {
+                volumes.insert(file.name.clone());
+            }
+            _ => {}
+        }
+    }
+
+    for file in files {
+        let (network, volume) = match &mut file.resource {
+            quadlet::Resource::Container(container) => {
+                (&mut container.network, &mut container.volume)
+            }
+            quadlet::Resource::Pod(pod) => (&mut pod.network, &mut pod.volume),
+            _ => continue,
+        };
+
+        for network in network {
+            let (name, options) = network
+                .split_once(':')
+                .map_or((network.as_str(), None), |(name, options)| {
+                    (name, Some(options))
+                });
+            if networks.contains(name) {
+                *network = options.map_or_else(
+                    || format!("{name}.network"),
+                    |options| format!("{name}.network:{options}"),
+                );
+            }
+        }
+
+        for volume in volume {
+            if let Some(Source::NamedVolume(source)) = &mut volume.source {
+                if volumes.contains(source) {
+                    source.push_str(".volume");
+                }
+            }
+        }
+    }
+}
+
+/// Container in the output of `podman container ls --format json`.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct ContainerListing {
+    names: Vec<String>,
+    /// ID of the container's pod, empty if not in a pod.
+    #[serde(default)]
+    pod: String,
+    #[serde(default)]
+    is_infra: bool,
+}
+
+/// Pod in the output of `podman pod ls --format json`.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct PodListing {
+    name: String,
+}
+
+/// Network in the output of `podman network ls --format json`.
+#[derive(Deserialize, Debug)]
+struct NetworkListing {
+    name: String,
+}
+
+/// Volume in the output of `podman volume ls --format json`.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct VolumeListing {
+    name: String,
+    #[serde(default)]
+    anonymous: bool,
 }
 
 /// [`Parser`] for container creation CLI options.
@@ -174,9 +549,9 @@ struct ContainerParser {
     #[command(flatten)]
     container: Container,
 
-    /// The \[Service\] section
-    #[command(flatten)]
-    service: Service,
+    /// The `--restart` policy, converts to "Restart=" in the \[Service\] section.
+    #[arg(long)]
+    restart: Option<RestartConfig>,
 }
 
 impl ContainerParser {
@@ -186,19 +561,58 @@ impl ContainerParser {
     ///
     /// Returns an error if there is an error getting the create command,
     /// or if it cannot be successfully parsed into container creation CLI options.
-    fn from_container(container: &str) -> color_eyre::Result<Self> {
-        let create_command = ContainerInspect::from_container(container)
-            .wrap_err_with(|| {
-                format!("error getting command used to create container `{container}`")
-            })?
-            .config
-            .create_command;
+    fn from_container(backend: &Backend, container: &str) -> color_eyre::Result<Self> {
+        let inspect = ContainerInspect::from_container(backend, container).wrap_err_with(|| {
+            format!("error getting command used to create container `{container}`")
+        })?;
+
+        let create_command = inspect.create_command();
+        if create_command.is_empty() || is_kube_play(create_command) {
+            return Self::from_inspect(backend, container, &inspect);
+        }
 
-        Self::try_parse_from(filter_container_create_command(&create_command)).wrap_err_with(|| {
+        Self::try_parse_from(filter_container_create_command(create_command)).wrap_err_with(|| {
             format!("error parsing Podman container command from `{create_command:?}`")
         })
     }
 
+    /// Rebuild the container creation CLI options from the output of `podman container inspect`.
+    ///
+    /// Used for containers without a recorded create command, e.g. those created through the
+    /// Podman API or a Docker-compatible client, and containers created with `podman kube play`.
+    /// Warnings are printed for settings which could not be reproduced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rebuilt options could not be parsed.
+    fn from_inspect(
+        backend: &Backend,
+        container: &str,
+        inspect: &ContainerInspect,
+    ) -> color_eyre::Result<Self> {
+        eprintln!(
+            "Warning: container `{container}` was not created with `podman run` or \
+                `podman create`, generating from the output of `podman container inspect`"
+        );
+
+        let image = ImageConfig::from_image(backend, inspect.image_id()).unwrap_or_else(|error| {
+            eprintln!(
+                "Warning: error inspecting the image of container `{container}`, \
+                    settings from the image are included: {error:#}"
+            );
+            ImageConfig::default()
+        });
+
+        let (args, unsupported) = inspect.create_args(&image);
+        for setting in unsupported {
+            eprintln!("Warning: the {setting} of container `{container}` could not be reproduced");
+        }
+
+        Self::try_parse_from(&args).wrap_err_with(|| {
+            format!("error parsing Podman container options rebuilt from inspection: {args:?}")
+        })
+    }
+
     /// Convert the parsed container command into a [`quadlet::File`].
     fn into_quadlet_file(
         self,
@@ -213,7 +627,7 @@ impl ContainerParser {
         let Self {
             global_args,
             mut container,
-            service,
+            restart,
         } = self;
 
         if pod.is_some() {
@@ -233,12 +647,20 @@ impl ContainerParser {
             resource: container.into(),
             globals: global_args.into(),
             quadlet,
-            service,
+            service: restart.map(Into::into).unwrap_or_default(),
             install,
+            options: Vec::new(),
         }
     }
 }
 
+/// Returns `true` if `command` is a `podman kube play` command.
+///
+/// Containers and pods created from Kubernetes YAML record it as their create command.
+fn is_kube_play(command: &[String]) -> bool {
+    command.windows(2).any(|args| args == ["kube", "play"])
+}
+
 /// Remove the command parts of `command`, leaving just the container creation options.
 fn filter_container_create_command(command: &[String]) -> impl Iterator<Item = &String> {
     let mut iter = command.iter();
@@ -259,33 +681,6 @@ fn filter_container_create_command(command: &[String]) -> impl Iterator<Item = &
     })
 }
 
-/// Selected output of `podman container inspect`.
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "PascalCase")]
-struct ContainerInspect {
-    config: ContainerConfig,
-}
-
-/// Part of `Config` object from the output of `podman container inspect`
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "PascalCase")]
-struct ContainerConfig {
-    create_command: Vec<String>,
-}
-
-impl ContainerInspect {
-    /// Runs `podman container inspect` on the container and deserializes the output into [`Self`].
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if there is problem running `podman container inspect`,
-    /// it doesn't complete successfully,
-    /// or if the output cannot be properly deserialized.
-    fn from_container(container: &str) -> color_eyre::Result<Self> {
-        podman_inspect(ResourceKind::Container, container)
-    }
-}
-
 /// [`Parser`] for pod creation CLI options.
 #[derive(Parser, Debug)]
 #[command(no_binary_name = true, disable_help_flag = true)]
@@ -332,30 +727,55 @@ impl PodParser {
     /// the creation command cannot be successfully parsed into pod CLI options,
     /// there is an error getting one of the pod's container's creation command,
     /// or a container creation command could not be parsed.
-    fn from_pod(pod: &str) -> color_eyre::Result<Self> {
-        let PodInspect {
-            create_command,
-            containers,
-        } = PodInspect::from_pod(pod).wrap_err_with(|| format!("error inspecting pod `{pod}`"))?;
-
-        // skip the `podman pod` prefix
-        let iter = create_command.iter().skip(2);
-
-        let mut pod = Self::try_parse_from(iter).wrap_err_with(|| {
-            format!("error parsing `podman pod create` command from `{create_command:?}`")
-        })?;
+    fn from_pod(backend: &Backend, pod: &str) -> color_eyre::Result<Self> {
+        let inspect = PodInspect::from_pod(backend, pod)
+            .wrap_err_with(|| format!("error inspecting pod `{pod}`"))?;
+
+        let create_command = inspect.create_command();
+        let mut parser = if matches!(
+            create_command.get(1..3),
+            Some([command, subcommand]) if command == "pod" && subcommand == "create"
+        ) {
+            // skip the `podman pod` prefix
+            Self::try_parse_from(create_command.iter().skip(2)).wrap_err_with(|| {
+                format!("error parsing `podman pod create` command from `{create_command:?}`")
+            })?
+        } else {
+            Self::from_inspect(pod, &inspect)?
+        };
 
-        let containers = containers
-            .into_iter()
-            .filter_map(|PodContainer { name }| {
-                // skip infra containers
-                (!name.ends_with("-infra")).then(|| ContainerParser::from_container(&name))
-            })
+        parser.containers = inspect
+            .container_names()
+            .map(|container| ContainerParser::from_container(backend, container))
             .collect::<Result<_, _>>()
             .wrap_err("error inspecting one of the pod's containers")?;
 
-        pod.containers = containers;
-        Ok(pod)
+        Ok(parser)
+    }
+
+    /// Rebuild the pod creation CLI options from the output of `podman pod inspect`.
+    ///
+    /// Used for pods not created with `podman pod create`, e.g. those created with
+    /// `podman kube play` or through the Podman API. Warnings are printed for settings which could
+    /// not be reproduced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rebuilt options could not be parsed.
+    fn from_inspect(pod: &str, inspect: &PodInspect) -> color_eyre::Result<Self> {
+        eprintln!(
+            "Warning: pod `{pod}` was not created with `podman pod create`, \
+                generating from the output of `podman pod inspect`"
+        );
+
+        let (args, unsupported) = inspect.create_args();
+        for setting in unsupported {
+            eprintln!("Warning: the {setting} of pod `{pod}` could not be reproduced");
+        }
+
+        Self::try_parse_from(&args).wrap_err_with(|| {
+            format!("error parsing Podman pod options rebuilt from inspection: {args:?}")
+        })
     }
 
     /// Convert the parsed pod and containers into [`quadlet::File`]s.
@@ -394,6 +814,7 @@ impl PodParser {
             quadlet,
             service: Service::default(),
             install,
+            options: Vec::new(),
         };
 
         files.push(pod);
@@ -401,37 +822,6 @@ impl PodParser {
     }
 }
 
-/// Selected output of `podman pod inspect`.
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "PascalCase")]
-struct PodInspect {
-    /// Full command and arguments that created the pod.
-    create_command: Vec<String>,
-    /// All containers in the pod.
-    containers: Vec<PodContainer>,
-}
-
-/// Container in output of `podman pod inspect`.
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "PascalCase")]
-struct PodContainer {
-    /// The name of the container.
-    name: String,
-}
-
-impl PodInspect {
-    /// Runs `podman pod inspect` on the pod and deserializes the output into [`Self`].
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if there is problem running `podman pod inspect`,
-    /// it doesn't complete successfully,
-    /// or if the output cannot be properly deserialized.
-    fn from_pod(pod: &str) -> color_eyre::Result<Self> {
-        podman_inspect(ResourceKind::Pod, pod)
-    }
-}
-
 /// Output of `podman network inspect`.
 #[derive(Deserialize, Debug)]
 struct NetworkInspect {
@@ -553,8 +943,8 @@ impl NetworkInspect {
     /// Returns an error if there is problem running `podman network inspect`,
     /// it doesn't complete successfully,
     /// or if the output cannot be properly deserialized.
-    fn from_network(network: &str) -> color_eyre::Result<Self> {
-        podman_inspect(ResourceKind::Network, network)
+    fn from_network(backend: &Backend, network: &str) -> color_eyre::Result<Self> {
+        backend.inspect(ResourceKind::Network, network)
     }
 
     /// Convert the inspected network into a [`quadlet::File`].
@@ -568,14 +958,21 @@ impl NetworkInspect {
         }: GenericSections,
     ) -> quadlet::File {
         let network = Network::from(self);
+        let network_name = network.name().to_owned();
         quadlet::File {
-            name: name.unwrap_or_else(|| network.name().to_owned()),
+            name: name.unwrap_or_else(|| network_name.clone()),
             unit,
-            resource: network.into(),
+            // Keep using the existing network instead of Quadlet's default `systemd-%N`.
+            resource: quadlet::Network {
+                network_name: Some(network_name),
+                ..network.into()
+            }
+            .into(),
             globals: Globals::default(),
             quadlet,
             service: Service::default(),
             install,
+            options: Vec::new(),
         }
     }
 }
@@ -680,8 +1077,8 @@ impl VolumeInspect {
     /// Returns an error if there is problem running `podman volume inspect`,
     /// it doesn't complete successfully,
     /// or if the output cannot be properly deserialized.
-    fn from_volume(volume: &str) -> color_eyre::Result<Self> {
-        podman_inspect(ResourceKind::Volume, volume)
+    fn from_volume(backend: &Backend, volume: &str) -> color_eyre::Result<Self> {
+        backend.inspect(ResourceKind::Volume, volume)
     }
 
     /// Convert the inspected volume into a [`quadlet::File`].
@@ -695,14 +1092,21 @@ impl VolumeInspect {
         }: GenericSections,
     ) -> quadlet::File {
         let volume = Volume::from(self);
+        let volume_name = volume.name().to_owned();
         quadlet::File {
-            name: name.unwrap_or_else(|| volume.name().to_owned()),
+            name: name.unwrap_or_else(|| volume_name.clone()),
             unit,
-            resource: volume.into(),
+            // Keep using the existing volume instead of Quadlet's default `systemd-%N`.
+            resource: quadlet::Volume {
+                volume_name: Some(volume_name),
+                ..volume.into()
+            }
+            .into(),
             globals: Globals::default(),
             quadlet,
             service: Service::default(),
             install,
+            options: Vec::new(),
         }
     }
 }
@@ -757,8 +1161,8 @@ impl ImageInspect {
     /// Returns an error if there is problem running `podman image inspect`,
     /// it doesn't complete successfully,
     /// or if the output cannot be properly deserialized.
-    fn from_image(image: &str) -> color_eyre::Result<Self> {
-        podman_inspect(ResourceKind::Image, image)
+    fn from_image(backend: &Backend, image: &str) -> color_eyre::Result<Self> {
+        backend.inspect(ResourceKind::Image, image)
     }
 
     /// Convert the inspected image into a [`quadlet::File`].
@@ -780,6 +1184,7 @@ impl ImageInspect {
             quadlet,
             service: Service::default(),
             install,
+            options: Vec::new(),
         }
     }
 }
@@ -806,53 +1211,202 @@ impl From<ImageInspect> for Image {
     }
 }
 
-/// Runs `podman {resource_kind} inspect` on the resource and deserializes the output.
+/// Options for how `podlet generate` gets information from Podman.
+#[derive(Args, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Connection {
+    /// Send requests to the Podman REST API instead of running `podman`
+    ///
+    /// The API socket is at `$XDG_RUNTIME_DIR/podman/podman.sock`, or `/run/podman/podman.sock`
+    /// when run as root, unless set with `--url`. This allows generating Quadlet files where the
+    /// socket is available but `podman` is not, e.g. in a container with the socket mounted.
+    ///
+    /// The API service can be started with `systemctl --user start podman.socket`.
+    #[arg(long, global = true)]
+    api: bool,
+
+    /// URL of the Podman API socket, implies `--api`
+    ///
+    /// Only Unix sockets are supported, e.g. `unix:///run/podman/podman.sock`.
+    #[arg(long, global = true, value_name = "URL", value_parser = parse_unix_url)]
+    url: Option<PathBuf>,
+}
+
+/// Parse the path of a Unix socket from a `unix://` URL.
+fn parse_unix_url(url: &str) -> Result<PathBuf, &'static str> {
+    url.strip_prefix("unix://")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .ok_or("URL must start with `unix://` followed by the path of the socket")
+}
+
+/// How `podlet generate` gets information from Podman.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Backend {
+    /// Run `podman` commands.
+    Cli,
+
+    /// Send requests to the Podman REST API.
+    Api(api::Client),
+}
+
+impl From<Connection> for Backend {
+    fn from(Connection { api, url }: Connection) -> Self {
+        match url {
+            Some(socket) => Self::Api(api::Client::new(socket)),
+            None if api => Self::Api(api::Client::new(api::Client::default_socket())),
+            None => Self::Cli,
+        }
+    }
+}
+
+impl Backend {
+    /// Inspect the resource and deserialize the output.
+    ///
+    /// Runs `podman {resource_kind} inspect`, or requests the equivalent from the API, which
+    /// returns the same JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is problem running `podman {resource_kind} inspect`
+    /// or requesting from the API, it doesn't complete successfully,
+    /// or if the output cannot be properly deserialized.
+    fn inspect<T: DeserializeOwned>(
+        &self,
+        resource_kind: ResourceKind,
+        resource: &str,
+    ) -> color_eyre::Result<T> {
+        let output = match self {
+            Self::Cli => podman([resource_kind.as_str(), "inspect", resource])?,
+            Self::Api(client) => {
+                let endpoint = format!("{resource_kind}s");
+                client.get([endpoint.as_str(), resource, "json"], [])?
+            }
+        };
+
+        serde_json::Deserializer::from_str(&output)
+            .deserialize_any(PodmanInspectVisitor {
+                resource_kind,
+                resource,
+                value: PhantomData,
+            })
+            .wrap_err_with(|| {
+                format!(
+                    "error deserializing from `podman {resource_kind} inspect {resource}` output"
+                )
+            })
+            .with_section(|| output.trim().to_owned().header("Podman Output:"))
+    }
+
+    /// List the resources matching the `filters` and deserialize the output.
+    ///
+    /// Runs `podman {resource_kind} ls --format json`, or requests the equivalent from the API.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is problem running `podman {resource_kind} ls`
+    /// or requesting from the API, it doesn't complete successfully,
+    /// or if the output cannot be properly deserialized.
+    fn list<T: DeserializeOwned>(
+        &self,
+        resource_kind: ResourceKind,
+        filters: &[String],
+    ) -> color_eyre::Result<Vec<T>> {
+        let output = match self {
+            Self::Cli => {
+                let mut args = vec![resource_kind.as_str(), "ls", "--format", "json"];
+                for filter in filters {
+                    args.extend(["--filter", filter]);
+                }
+                podman(args)?
+            }
+            Self::Api(client) => {
+                // The API takes the filters as a JSON object of filter names and their values.
+                let mut api_filters: IndexMap<&str, Vec<&str>> = IndexMap::new();
+                for filter in filters {
+                    let (filter, value) = filter.split_once('=').unwrap_or((filter, ""));
+                    api_filters.entry(filter).or_default().push(value);
+                }
+                let api_filters = serde_json::to_string(&api_filters)
+                    .wrap_err("error serializing API filters")?;
+
+                let endpoint = format!("{resource_kind}s");
+                client.get(
+                    [endpoint.as_str(), "json"],
+                    (!filters.is_empty()).then_some(("filters", api_filters.as_str())),
+                )?
+            }
+        };
+
+        // Older versions of Podman output nothing instead of an empty array.
+        if output.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        serde_json::from_str(&output)
+            .wrap_err_with(|| {
+                format!("error deserializing from `podman {resource_kind} ls` output")
+            })
+            .with_section(|| output.trim().to_owned().header("Podman Output:"))
+    }
+
+    /// Generate Kubernetes YAML for the pods or containers in `resources`.
+    ///
+    /// Runs `podman kube generate`, or requests the equivalent from the API.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is problem running `podman kube generate`
+    /// or requesting from the API, or it doesn't complete successfully.
+    fn kube_generate(&self, resources: &[String]) -> color_eyre::Result<String> {
+        match self {
+            Self::Cli => podman(
+                ["kube", "generate"]
+                    .into_iter()
+                    .chain(resources.iter().map(String::as_str)),
+            ),
+            Self::Api(client) => client.get(
+                ["generate", "kube"],
+                resources
+                    .iter()
+                    .map(|resource| ("names", resource.as_str())),
+            ),
+        }
+    }
+}
+
+/// Runs `podman` with the `args` and returns its stdout.
 ///
 /// # Errors
 ///
-/// Returns an error if there is problem running `podman {resource_kind} inspect`,
-/// it doesn't complete successfully,
-/// or if the output cannot be properly deserialized.
-fn podman_inspect<T: DeserializeOwned>(
-    resource_kind: ResourceKind,
-    resource: &str,
-) -> color_eyre::Result<T> {
+/// Returns an error if there is problem running `podman` or it doesn't complete successfully.
+fn podman<'a>(args: impl IntoIterator<Item = &'a str>) -> color_eyre::Result<String> {
+    let args: Vec<&str> = args.into_iter().collect();
+    let command = args.join(" ");
+
     let output = Command::new("podman")
-        .args([resource_kind.as_str(), "inspect", resource])
+        .args(&args)
         .output()
-        .wrap_err_with(|| format!("error running `podman {resource_kind} inspect {resource}`"))
+        .wrap_err_with(|| format!("error running `podman {command}`"))
         .note("ensure Podman is installed and available on $PATH")
+        .suggestion("use `--api` if only the Podman API socket is available")
         .with_section(|| env::var("PATH").unwrap_or_default().header("PATH:"))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return if let Some(code) = output.status.code() {
             Err(eyre!(
-                "`podman {resource_kind} inspect {resource}` \
-                    exited unsuccessfully with status code: {code}"
+                "`podman {command}` exited unsuccessfully with status code: {code}"
             ))
         } else {
-            Err(eyre!(
-                "`podman {resource_kind} inspect {resource}` \
-                    was terminated by a signal"
-            ))
+            Err(eyre!("`podman {command}` was terminated by a signal"))
         }
         .section(stdout.trim().to_owned().header("Podman Stdout:"))
         .section(stderr.trim().to_owned().header("Podman Stderr:"));
     }
 
-    serde_json::Deserializer::from_str(&stdout)
-        .deserialize_any(PodmanInspectVisitor {
-            resource_kind,
-            resource,
-            value: PhantomData,
-        })
-        .wrap_err_with(|| {
-            format!("error deserializing from `podman {resource_kind} inspect {resource}` output")
-        })
-        .with_section(|| stdout.trim().to_owned().header("Podman Stdout:"))
+    Ok(stdout)
 }
 
 /// A [`Visitor`] for deserializing the output of `podman inspect`.
@@ -910,4 +1464,347 @@ mod tests {
     fn verify_pod_parser_cli() {
         PodParser::command().debug_assert();
     }
+
+    #[test]
+    fn reference_generated_files() -> color_eyre::Result<()> {
+        let mut files = [
+            quadlet::File::new("net", quadlet::Network::default()),
+            quadlet::File::new("data", quadlet::Volume::default()),
+            quadlet::File::new(
+                "web",
+                quadlet::Container {
+                    image: String::from("image"),
+                    network: vec![
+                        String::from("net:ip=10.0.0.2"),
+                        String::from("other"),
+                        String::from("host"),
+                    ],
+                    volume: vec!["data:/data".parse()?, "other:/other".parse()?],
+                    ..quadlet::Container::default()
+                },
+            ),
+        ];
+        reference_generated(&mut files);
+
+        let [_, _, web] = &files;
+        let quadlet::Resource::Container(container) = &web.resource else {
+            return Err(eyre!("resource is not a container"));
+        };
+        assert_eq!(
+            container.network,
+            ["net.network:ip=10.0.0.2", "other", "host"]
+        );
+        assert_eq!(
+            container
+                .volume
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["data.volume:/data", "other:/other"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn network_names_skip_modes() {
+        let network = [
+            "app:ip=10.0.0.2",
+            "host",
+            "container:db",
+            "pasta:-t,8080",
+            "ns:/run/netns/test",
+            "podman",
+            "podman-default-kube-network",
+            "app",
+            "other",
+        ]
+        .map(String::from);
+        assert_eq!(
+            network_names(&network).into_iter().collect::<Vec<_>>(),
+            ["app", "other"]
+        );
+    }
+
+    #[test]
+    fn keep_inspected_names() -> color_eyre::Result<()> {
+        let network: NetworkInspect = serde_json::from_str(
+            r#"{
+                "name": "app",
+                "driver": "bridge",
+                "network_interface": "podman1",
+                "ipv6_enabled": false,
+                "internal": false,
+                "dns_enabled": true,
+                "ipam_options": {"driver": "host-local"}
+            }"#,
+        )?;
+        let network = network.into_quadlet_file(None, GenericSections::default());
+        assert_eq!(network.name, "app");
+        let quadlet::Resource::Network(network) = network.resource else {
+            return Err(eyre!("resource is not a network"));
+        };
+        assert_eq!(network.network_name.as_deref(), Some("app"));
+
+        let volume: VolumeInspect = serde_json::from_str(
+            r#"{"Name": "data", "Driver": "local", "Labels": {}, "Options": {}}"#,
+        )?;
+        let volume = volume.into_quadlet_file(Some(String::from("db")), GenericSections::default());
+        assert_eq!(volume.name, "db");
+        let quadlet::Resource::Volume(volume) = volume.resource else {
+            return Err(eyre!("resource is not a volume"));
+        };
+        assert_eq!(volume.volume_name.as_deref(), Some("data"));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn with_resources() -> color_eyre::Result<()> {
+        let (backend, socket, server) = fake_api(
+            "with-resources",
+            vec![
+                (
+                    "GET /v4.0.0/libpod/networks/app/json HTTP/1.1",
+                    r#"{
+                        "name": "app",
+                        "driver": "bridge",
+                        "network_interface": "podman1",
+                        "ipv6_enabled": false,
+                        "internal": false,
+                        "dns_enabled": true,
+                        "ipam_options": {"driver": "host-local"}
+                    }"#,
+                ),
+                (
+                    "GET /v4.0.0/libpod/volumes/data/json HTTP/1.1",
+                    r#"{"Name": "data", "Driver": "local", "Labels": {}, "Options": {}}"#,
+                ),
+            ],
+        )?;
+
+        let container = quadlet::File {
+            name: String::from("web"),
+            unit: quadlet::Unit::default(),
+            resource: quadlet::Container {
+                image: String::from("image"),
+                network: vec![String::from("app:ip=10.0.0.2"), String::from("podman")],
+                volume: vec!["data:/data".parse()?, "/srv:/srv".parse()?],
+                ..quadlet::Container::default()
+            }
+            .into(),
+            globals: Globals::default(),
+            quadlet: quadlet::Quadlet::default(),
+            service: Service::default(),
+            install: quadlet::Install {
+                wanted_by: vec![String::from("default.target")],
+                ..quadlet::Install::default()
+            },
+            options: Vec::new(),
+        };
+
+        let mut files = used_resources(&backend, &container)?;
+        files.push(container);
+        reference_generated(&mut files);
+
+        server.join().expect("server thread panicked")?;
+        std::fs::remove_file(socket)?;
+
+        let [network, volume, container] = files.as_slice() else {
+            return Err(eyre!("expected three files, got {}", files.len()));
+        };
+
+        assert_eq!(network.name, "app");
+        assert!(network.install.is_empty());
+        let quadlet::Resource::Network(network) = &network.resource else {
+            return Err(eyre!("resource is not a network"));
+        };
+        assert_eq!(network.network_name.as_deref(), Some("app"));
+
+        assert_eq!(volume.name, "data");
+        assert!(volume.install.is_empty());
+        let quadlet::Resource::Volume(volume) = &volume.resource else {
+            return Err(eyre!("resource is not a volume"));
+        };
+        assert_eq!(volume.volume_name.as_deref(), Some("data"));
+
+        assert_eq!(container.install.wanted_by, ["default.target"]);
+        let quadlet::Resource::Container(container) = &container.resource else {
+            return Err(eyre!("resource is not a container"));
+        };
+        assert_eq!(container.network, ["app.network:ip=10.0.0.2", "podman"]);
+        assert_eq!(
+            container
+                .volume
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["data.volume:/data", "/srv:/srv"]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn generate_all_resources() -> color_eyre::Result<()> {
+        let (backend, socket, server) = fake_api(
+            "generate-all",
+            vec![
+                (
+                    "GET /v4.0.0/libpod/networks/json HTTP/1.1",
+                    r#"[{"name": "podman"}, {"name": "app"}]"#,
+                ),
+                (
+                    "GET /v4.0.0/libpod/networks/app/json HTTP/1.1",
+                    r#"{
+                        "name": "app",
+                        "driver": "bridge",
+                        "network_interface": "podman1",
+                        "ipv6_enabled": false,
+                        "internal": false,
+                        "dns_enabled": true,
+                        "ipam_options": {"driver": "host-local"}
+                    }"#,
+                ),
+                (
+                    "GET /v4.0.0/libpod/volumes/json HTTP/1.1",
+                    r#"[{"Name": "data", "Anonymous": false}, {"Name": "0123abcd", "Anonymous": true}]"#,
+                ),
+                (
+                    "GET /v4.0.0/libpod/volumes/data/json HTTP/1.1",
+                    r#"{"Name": "data", "Driver": "local", "Labels": {}, "Options": {}}"#,
+                ),
+                (
+                    "GET /v4.0.0/libpod/pods/json\
+                        ?filters=%7B%22status%22%3A%5B%22running%22%5D%7D HTTP/1.1",
+                    "[]",
+                ),
+                (
+                    "GET /v4.0.0/libpod/containers/json HTTP/1.1",
+                    r#"[
+                        {"Names": ["web"], "Pod": "", "IsInfra": false},
+                        {"Names": ["member"], "Pod": "4567cdef", "IsInfra": false},
+                        {"Names": ["4567cdef-infra"], "Pod": "4567cdef", "IsInfra": true}
+                    ]"#,
+                ),
+                (
+                    "GET /v4.0.0/libpod/containers/web/json HTTP/1.1",
+                    r#"{"Name": "web", "Config": {"CreateCommand": [
+                        "podman", "run", "--name", "web", "--network", "app", "--network", "podman",
+                        "-v", "data:/data", "image"
+                    ]}}"#,
+                ),
+            ],
+        )?;
+
+        let files = generate_all(&backend, &[], &GenericSections::default())?;
+
+        server.join().expect("server thread panicked")?;
+        std::fs::remove_file(socket)?;
+
+        // The default network, the anonymous volume, and the pod's containers are skipped.
+        let names: Vec<_> = files
+            .iter()
+            .map(|file| format!("{}.{}", file.name, file.resource.extension()))
+            .collect();
+        assert_eq!(names, ["app.network", "data.volume", "web.container"]);
+
+        let Some(quadlet::Resource::Container(container)) = files.last().map(|file| &file.resource)
+        else {
+            return Err(eyre!("resource is not a container"));
+        };
+        assert_eq!(container.network, ["app.network", "podman"]);
+        assert_eq!(
+            container
+                .volume
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["data.volume:/data"]
+        );
+
+        Ok(())
+    }
+
+    /// Start a fake Podman API service on a socket named after the `test`, answering each of the
+    /// expected requests in `responses`, given as the request line and response body, in order.
+    #[cfg(unix)]
+    fn fake_api(
+        test: &str,
+        responses: Vec<(&'static str, &'static str)>,
+    ) -> std::io::Result<(
+        Backend,
+        PathBuf,
+        std::thread::JoinHandle<std::io::Result<()>>,
+    )> {
+        use std::{
+            io::{BufRead, BufReader, Write},
+            os::unix::net::UnixListener,
+            thread,
+        };
+
+        let socket =
+            env::temp_dir().join(format!("podlet-test-{test}-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket);
+        let listener = UnixListener::bind(&socket)?;
+
+        let server = thread::spawn(move || {
+            for (request, body) in responses {
+                let (mut stream, _) = listener.accept()?;
+                let mut request_line = String::new();
+                BufReader::new(&stream).read_line(&mut request_line)?;
+                assert_eq!(request_line.trim_end(), request);
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{body}\r\n0\r\n\r\n",
+                    body.len(),
+                )?;
+            }
+            Ok(())
+        });
+
+        Ok((
+            Backend::Api(api::Client::new(socket.clone())),
+            socket,
+            server,
+        ))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn api_backend() -> color_eyre::Result<()> {
+        let (backend, socket, server) = fake_api(
+            "api-backend",
+            vec![
+                (
+                    "GET /v4.0.0/libpod/volumes/json\
+                        ?filters=%7B%22label%22%3A%5B%22app%3Dweb%22%5D%7D HTTP/1.1",
+                    r#"[{"Name": "data", "Anonymous": false}]"#,
+                ),
+                (
+                    "GET /v4.0.0/libpod/volumes/data/json HTTP/1.1",
+                    r#"{"Name": "data", "Driver": "local", "Labels": {"app": "web"}, "Options": {}}"#,
+                ),
+            ],
+        )?;
+
+        let volumes: Vec<VolumeListing> =
+            backend.list(ResourceKind::Volume, &[String::from("label=app=web")])?;
+        let names: Vec<&str> = volumes.iter().map(|volume| volume.name.as_str()).collect();
+        assert_eq!(names, ["data"]);
+
+        let volume = VolumeInspect::from_volume(&backend, "data")?;
+        assert_eq!(volume.driver, "local");
+        assert_eq!(
+            volume.labels,
+            IndexMap::from([(String::from("app"), String::from("web"))])
+        );
+
+        server.join().expect("server thread panicked")?;
+        std::fs::remove_file(socket)?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,286 @@
+//! Provides [`Client`], a minimal client for the Podman REST API listening on a Unix socket, used
+//! by `podlet generate --api` instead of running `podman`.
+
+use std::{env, path::PathBuf};
+
+use color_eyre::{
+    Section, SectionExt,
+    eyre::{WrapErr, eyre},
+};
+use url::{Position, Url};
+
+/// Version of the libpod API used for requests, supported by Podman v4.0.0 and newer.
+const API_VERSION: &str = "v4.0.0";
+
+/// Client for the libpod REST API of a Podman service listening on a Unix socket.
+///
+/// The service is started with `podman system service` or by the `podman.socket` systemd unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Client {
+    /// Path of the API socket.
+    socket: PathBuf,
+}
+
+impl Client {
+    /// Create a client for the API listening on `socket`.
+    pub const fn new(socket: PathBuf) -> Self {
+        Self { socket }
+    }
+
+    /// The default location of the Podman API socket.
+    ///
+    /// `/run/podman/podman.sock` when run as root, `$XDG_RUNTIME_DIR/podman/podman.sock`
+    /// otherwise.
+    pub fn default_socket() -> PathBuf {
+        #[cfg(unix)]
+        if rustix::process::getuid().is_root() {
+            return PathBuf::from("/run/podman/podman.sock");
+        }
+
+        let mut path: PathBuf =
+            env::var_os("XDG_RUNTIME_DIR").map_or_else(default_runtime_dir, Into::into);
+        path.push("podman/podman.sock");
+        path
+    }
+
+    /// Send a `GET` request to the libpod API `endpoint`, given as path segments, e.g.
+    /// `["containers", name, "json"]`, with the `query` parameters and return the response body.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket could not be connected to, the response is invalid, or the
+    /// request was not successful.
+    pub fn get<'a>(
+        &self,
+        endpoint: impl IntoIterator<Item = &'a str>,
+        query: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> color_eyre::Result<String> {
+        let target = request_target(endpoint, query);
+
+        let response = self
+            .send(&target)
+            .wrap_err_with(|| format!("error sending Podman API request `GET {target}`"))?;
+        let Response { status, body } = Response::parse(&response)
+            .ok_or_else(|| eyre!("invalid response to Podman API request `GET {target}`"))
+            .with_section(|| {
+                String::from_utf8_lossy(&response)
+                    .trim()
+                    .to_owned()
+                    .header("Response:")
+            })?;
+
+        if !(200..300).contains(&status) {
+            return Err(eyre!(
+                "Podman API request `GET {target}` failed with status code: {status}"
+            ))
+            .section(body.trim().to_owned().header("Podman API Response:"));
+        }
+
+        Ok(body)
+    }
+
+    /// Send a `GET` request for the `target` over the socket and return the raw response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket could not be connected to, written to, or read from.
+    #[cfg(unix)]
+    fn send(&self, target: &str) -> color_eyre::Result<Vec<u8>> {
+        use std::{
+            io::{Read, Write},
+            os::unix::net::UnixStream,
+        };
+
+        let mut stream = UnixStream::connect(&self.socket)
+            .wrap_err_with(|| {
+                format!(
+                    "error connecting to the Podman API socket `{}`",
+                    self.socket.display()
+                )
+            })
+            .suggestion(
+                "Start the Podman API service, e.g. with `systemctl --user start podman.socket`, \
+                    or set the socket's location with `--url`.",
+            )?;
+
+        // The connection is closed after the response, so the whole stream is the response.
+        stream
+            .write_all(
+                format!("GET {target} HTTP/1.1\r\nHost: d\r\nConnection: close\r\n\r\n").as_bytes(),
+            )
+            .wrap_err("error writing to the Podman API socket")?;
+
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .wrap_err("error reading from the Podman API socket")?;
+
+        Ok(response)
+    }
+
+    /// Connecting to a Unix socket is not supported on this platform.
+    ///
+    /// # Errors
+    ///
+    /// Always returns an error.
+    #[cfg(not(unix))]
+    fn send(&self, _target: &str) -> color_eyre::Result<Vec<u8>> {
+        Err(eyre!(
+            "cannot connect to the Podman API socket `{}`, \
+                Unix sockets are only supported on Unix",
+            self.socket.display()
+        ))
+    }
+}
+
+/// Runtime directory of the current user, used when `XDG_RUNTIME_DIR` is not set.
+fn default_runtime_dir() -> PathBuf {
+    #[cfg(unix)]
+    {
+        format!("/run/user/{}", rustix::process::getuid().as_raw()).into()
+    }
+
+    #[cfg(not(unix))]
+    {
+        env::temp_dir()
+    }
+}
+
+/// Create the request target, i.e. the path and query, for the libpod API `endpoint` with the
+/// `query` parameters.
+///
+/// The path segments and query parameters are percent-encoded.
+fn request_target<'a>(
+    endpoint: impl IntoIterator<Item = &'a str>,
+    query: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> String {
+    let mut url = Url::parse("http://d").expect("URL is valid");
+    url.path_segments_mut()
+        .expect("URL has a host")
+        .clear()
+        .extend([API_VERSION, "libpod"])
+        .extend(endpoint);
+
+    let mut query = query.into_iter().peekable();
+    if query.peek().is_some() {
+        url.query_pairs_mut().extend_pairs(query);
+    }
+
+    url[Position::BeforePath..].to_owned()
+}
+
+/// HTTP response from the Podman API.
+#[derive(Debug, PartialEq, Eq)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    /// Parse a raw HTTP/1.1 response, decoding the body if it uses chunked transfer encoding.
+    ///
+    /// Returns [`None`] if the response is invalid.
+    fn parse(response: &[u8]) -> Option<Self> {
+        let header_end = response
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")?;
+        let (head, body) = response.split_at_checked(header_end)?;
+        let body = body.get(4..)?;
+
+        let head = std::str::from_utf8(head).ok()?;
+        let mut lines = head.split("\r\n");
+
+        // e.g. "HTTP/1.1 200 OK"
+        let status = lines.next()?.split(' ').nth(1)?.parse().ok()?;
+
+        let chunked = lines.any(|header| {
+            header.split_once(':').is_some_and(|(name, value)| {
+                name.trim().eq_ignore_ascii_case("transfer-encoding")
+                    && value.trim().eq_ignore_ascii_case("chunked")
+            })
+        });
+        let body = if chunked {
+            decode_chunked(body)?
+        } else {
+            body.to_vec()
+        };
+
+        Some(Self {
+            status,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+}
+
+/// Decode a `body` sent with chunked transfer encoding.
+///
+/// Returns [`None`] if the body is not properly chunked.
+fn decode_chunked(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(body.len());
+    loop {
+        // Format is "{size in hex}[;extensions]\r\n{chunk}\r\n", ending with a zero size chunk.
+        let line_end = body.windows(2).position(|window| window == b"\r\n")?;
+        let (size, rest) = body.split_at_checked(line_end)?;
+        let size = std::str::from_utf8(size).ok()?;
+        let size = size.split_once(';').map_or(size, |(size, _)| size).trim();
+        let size = usize::from_str_radix(size, 16).ok()?;
+        if size == 0 {
+            return Some(decoded);
+        }
+
+        let (chunk, rest) = rest.get(2..)?.split_at_checked(size)?;
+        decoded.extend_from_slice(chunk);
+        body = rest.strip_prefix(b"\r\n")?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_target_encoding() {
+        assert_eq!(
+            request_target(
+                ["images", "quay.io/podman/hello:latest", "json"],
+                [("filters", r#"{"label":["app=web"]}"#)],
+            ),
+            "/v4.0.0/libpod/images/quay.io%2Fpodman%2Fhello:latest/json\
+                ?filters=%7B%22label%22%3A%5B%22app%3Dweb%22%5D%7D"
+        );
+        assert_eq!(
+            request_target(["containers", "web", "json"], []),
+            "/v4.0.0/libpod/containers/web/json"
+        );
+    }
+
+    #[test]
+    fn parse_chunked_response() {
+        let response = b"HTTP/1.1 200 OK\r\n\
+            Content-Type: application/json\r\n\
+            Transfer-Encoding: chunked\r\n\
+            \r\n\
+            7\r\n{\"Name\"\r\n\
+            9;ext=1\r\n: \"web\"}\n\r\n\
+            0\r\n\
+            \r\n";
+        assert_eq!(
+            Response::parse(response),
+            Some(Response {
+                status: 200,
+                body: String::from("{\"Name\": \"web\"}\n"),
+            })
+        );
+
+        let response = b"HTTP/1.1 404 Not Found\r\nContent-Length: 2\r\n\r\n{}";
+        assert_eq!(
+            Response::parse(response),
+            Some(Response {
+                status: 404,
+                body: String::from("{}"),
+            })
+        );
+
+        assert_eq!(Response::parse(b"HTTP/1.1 200 OK\r\n"), None);
+    }
+}
@@ -0,0 +1,318 @@
+//! Provides [`BuildInspect`] for `podlet generate build`, which recreates the `.build` Quadlet file
+//! of an existing image from its metadata and history.
+
+use std::path::Path;
+
+use compose_spec::service::build::Context;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{
+    cli::image_to_name,
+    quadlet::{self, GenericSections, Globals, ResourceKind, Service},
+};
+
+use super::{Backend, container::null_default};
+
+/// OCI annotation with the name of the base image, added by Buildah.
+const BASE_NAME_ANNOTATION: &str = "org.opencontainers.image.base.name";
+
+/// OCI annotation or label with the URL or path of the image's source.
+const SOURCE_KEY: &str = "org.opencontainers.image.source";
+
+/// Selected output of `podman image inspect`, used to recreate a `.build` Quadlet file.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct BuildInspect {
+    /// --tag
+    #[serde(deserialize_with = "null_default")]
+    repo_tags: Vec<String>,
+
+    /// --label, including the base image's labels.
+    #[serde(deserialize_with = "null_default")]
+    labels: IndexMap<String, String>,
+
+    /// --annotation, including those added by Buildah.
+    #[serde(deserialize_with = "null_default")]
+    annotations: IndexMap<String, String>,
+
+    /// History of the image's layers, used to recover build arguments.
+    #[serde(deserialize_with = "null_default")]
+    history: Vec<History>,
+}
+
+/// Entry in the history of an image.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct History {
+    /// Containerfile instruction which created the layer.
+    ///
+    /// Buildah prefixes `RUN` instructions with the build arguments used, e.g.
+    /// `|2 ONE=1 TWO=2 /bin/sh -c make`.
+    created_by: String,
+}
+
+/// Labels of the base image from `podman image inspect`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct BaseImageInspect {
+    #[serde(deserialize_with = "null_default")]
+    labels: IndexMap<String, String>,
+}
+
+impl BuildInspect {
+    /// Runs `podman image inspect` on the image and deserializes the output into [`Self`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is problem running `podman image inspect`,
+    /// it doesn't complete successfully,
+    /// or if the output cannot be properly deserialized.
+    pub fn from_image(backend: &Backend, image: &str) -> color_eyre::Result<Self> {
+        backend.inspect(ResourceKind::Image, image)
+    }
+
+    /// Convert the inspected image into a [`quadlet::File`].
+    ///
+    /// The base image, named in the image's annotations, is inspected to leave out the labels the
+    /// image inherited from it. Warnings are printed for build settings which could not be
+    /// recovered.
+    pub fn into_quadlet_file(
+        self,
+        backend: &Backend,
+        image: &str,
+        name: Option<String>,
+        GenericSections {
+            unit,
+            quadlet,
+            install,
+        }: GenericSections,
+    ) -> quadlet::File {
+        let base_labels = self.annotations.get(BASE_NAME_ANNOTATION).map_or_else(
+            || {
+                eprintln!(
+                    "Warning: the base image of `{image}` is unknown, \
+                        labels inherited from it are included"
+                );
+                IndexMap::new()
+            },
+            |base| {
+                backend.inspect(ResourceKind::Image, base).map_or_else(
+                    |error| {
+                        eprintln!(
+                            "Warning: error inspecting the base image of `{image}`, \
+                                labels inherited from it are included: {error:#}"
+                        );
+                        IndexMap::new()
+                    },
+                    |BaseImageInspect { labels }| labels,
+                )
+            },
+        );
+
+        let source = self.source().cloned();
+        let build = self.into_build(&base_labels);
+
+        if build.set_working_directory.is_none() {
+            if let Some(source) = source {
+                eprintln!(
+                    "Warning: the source of `{image}`, `{source}`, is not a usable build context, \
+                        set `SetWorkingDirectory=` in the generated file"
+                );
+            } else {
+                eprintln!(
+                    "Warning: the build context of `{image}` could not be recovered, \
+                        set `SetWorkingDirectory=` in the generated file"
+                );
+            }
+        }
+        eprintln!(
+            "Warning: the Containerfile and target stage used to build `{image}` cannot be \
+                recovered, set `File=` and `Target=` if needed"
+        );
+
+        let name = name.unwrap_or_else(|| {
+            build
+                .image_tag
+                .first()
+                .map_or(image, |tag| image_to_name(tag))
+                .to_owned()
+        });
+
+        quadlet::File {
+            name,
+            unit,
+            resource: build.into(),
+            globals: Globals::default(),
+            quadlet,
+            service: Service::default(),
+            install,
+            options: Vec::new(),
+        }
+    }
+
+    /// The URL or path of the image's source, from its annotations or labels.
+    fn source(&self) -> Option<&String> {
+        self.annotations
+            .get(SOURCE_KEY)
+            .or_else(|| self.labels.get(SOURCE_KEY))
+    }
+
+    /// Recreate the [`quadlet::Build`] from the image's metadata.
+    ///
+    /// Labels which are the same as the `base_labels` and annotations added by Buildah are left
+    /// out.
+    fn into_build(self, base_labels: &IndexMap<String, String>) -> quadlet::Build {
+        let Self {
+            repo_tags,
+            labels,
+            annotations,
+            history,
+        } = self;
+
+        let set_working_directory = annotations
+            .get(SOURCE_KEY)
+            .or_else(|| labels.get(SOURCE_KEY))
+            .and_then(|source| build_context(source));
+
+        let label = labels
+            .into_iter()
+            .filter(|(label, value)| {
+                !label.starts_with("io.buildah.") && base_labels.get(label) != Some(value)
+            })
+            .map(|(label, value)| format!("{label}={value}"))
+            .collect();
+
+        let annotation = annotations
+            .into_iter()
+            .filter(|(annotation, _)| {
+                !annotation.starts_with("org.opencontainers.image.base.")
+                    && !annotation.starts_with("io.buildah.")
+            })
+            .map(|(annotation, value)| format!("{annotation}={value}"))
+            .collect();
+
+        quadlet::Build {
+            annotation,
+            build_arg: build_args(&history),
+            image_tag: repo_tags,
+            label,
+            set_working_directory,
+            ..quadlet::Build::default()
+        }
+    }
+}
+
+/// Convert the image's `source` into a build context for `SetWorkingDirectory=`.
+///
+/// Only paths and Git repositories, URLs with the `git` scheme or ending in `.git`, are usable.
+/// Other URLs, like `https://github.com/example/app`, are usually the source's web page, which
+/// `podman build` cannot use.
+fn build_context(source: &str) -> Option<Context> {
+    match Context::parse(source) {
+        Context::Url(url)
+            if url.scheme() != "git"
+                && !Path::new(url.path())
+                    .extension()
+                    .is_some_and(|extension| extension.eq_ignore_ascii_case("git")) =>
+        {
+            None
+        }
+        context => Some(context),
+    }
+}
+
+/// Recover the build arguments, in the form `ARG=VALUE`, used for the image's `RUN` instructions
+/// from its `history`.
+fn build_args(history: &[History]) -> Vec<String> {
+    let mut build_args: IndexMap<&str, &str> = IndexMap::new();
+    for History { created_by } in history {
+        // Format is "|{count} ARG=VALUE... {command}".
+        let Some((count, args)) = created_by
+            .strip_prefix('|')
+            .and_then(|created_by| created_by.split_once(' '))
+        else {
+            continue;
+        };
+        let Ok(count) = count.parse() else {
+            continue;
+        };
+        for arg in args.split(' ').take(count) {
+            if let Some((arg, value)) = arg.split_once('=') {
+                build_args.insert(arg, value);
+            }
+        }
+    }
+
+    build_args
+        .into_iter()
+        .map(|(arg, value)| format!("{arg}={value}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_build() -> serde_json::Result<()> {
+        let image: BuildInspect = serde_json::from_str(
+            r#"{
+                "RepoTags": ["localhost/app:latest"],
+                "Labels": {
+                    "io.buildah.version": "1.39.0",
+                    "maintainer": "base",
+                    "app": "web"
+                },
+                "Annotations": {
+                    "org.opencontainers.image.base.name": "docker.io/library/alpine:latest",
+                    "org.opencontainers.image.source": "https://github.com/example/app.git",
+                    "custom": "value"
+                },
+                "History": [
+                    {"created_by": "/bin/sh -c #(nop) ADD file:0123 in / "},
+                    {"created_by": "/bin/sh -c #(nop) ARG VERSION=1.0"},
+                    {"created_by": "|2 VERSION=2.0 MODE=release /bin/sh -c make"},
+                    {"created_by": "|1 MODE=debug /bin/sh -c make test"}
+                ]
+            }"#,
+        )?;
+
+        let base_labels = IndexMap::from([(String::from("maintainer"), String::from("base"))]);
+        let build = image.into_build(&base_labels);
+
+        assert_eq!(build.image_tag, ["localhost/app:latest"]);
+        assert_eq!(build.label, ["app=web"]);
+        assert_eq!(
+            build.annotation,
+            [
+                "org.opencontainers.image.source=https://github.com/example/app.git",
+                "custom=value"
+            ]
+        );
+        assert_eq!(build.build_arg, ["VERSION=2.0", "MODE=debug"]);
+        assert!(matches!(
+            build.set_working_directory,
+            Some(Context::Url(url)) if url.as_str() == "https://github.com/example/app.git"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn usable_build_context() {
+        assert!(build_context("https://github.com/example/app").is_none());
+        assert!(matches!(
+            build_context("git://example.com/app"),
+            Some(Context::Url(_))
+        ));
+        assert!(matches!(
+            build_context("https://example.com/app.git"),
+            Some(Context::Url(_))
+        ));
+        assert!(matches!(
+            build_context("/srv/app"),
+            Some(Context::Path(path)) if path == Path::new("/srv/app")
+        ));
+    }
+}
@@ -0,0 +1,1404 @@
+//! Provides [`ContainerInspect`], the output of `podman container inspect`, and the fallback for
+//! containers without a recorded create command, see [`ContainerInspect::create_args()`].
+
+use std::time::Duration;
+
+use compose_spec::duration;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{cli::container::security_opt::SecurityOpt, quadlet::ResourceKind};
+
+use super::Backend;
+
+/// Selected output of `podman container inspect`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ContainerInspect {
+    /// ID of the container.
+    id: String,
+
+    /// Name of the container.
+    name: String,
+
+    /// ID of the container's pod, empty if not in a pod.
+    pod: String,
+
+    /// ID of the container's image.
+    image: String,
+
+    /// Name of the image the container was created from.
+    image_name: String,
+
+    config: ContainerConfig,
+
+    host_config: HostConfig,
+
+    network_settings: NetworkSettings,
+
+    mounts: Vec<Mount>,
+}
+
+impl ContainerInspect {
+    /// Runs `podman container inspect` on the container and deserializes the output into [`Self`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is problem running `podman container inspect`,
+    /// it doesn't complete successfully,
+    /// or if the output cannot be properly deserialized.
+    pub fn from_container(backend: &Backend, container: &str) -> color_eyre::Result<Self> {
+        backend.inspect(ResourceKind::Container, container)
+    }
+
+    /// Full command and arguments that created the container.
+    ///
+    /// Empty for containers created through the Podman API or a Docker-compatible client.
+    pub fn create_command(&self) -> &[String] {
+        &self.config.create_command
+    }
+
+    /// Name of the container.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// ID of the container's image.
+    pub fn image_id(&self) -> &str {
+        &self.image
+    }
+
+    /// The container's networks, as values for `--network`.
+    pub fn networks(&self) -> Vec<String> {
+        networks(
+            &self.host_config.network_mode,
+            &self.network_settings,
+            [&self.name, self.short_id()],
+        )
+    }
+
+    /// The first 12 characters of the container's ID.
+    fn short_id(&self) -> &str {
+        self.id.get(..12).unwrap_or(&self.id)
+    }
+
+    /// Rebuild the `podman run` arguments for the container from the inspected settings.
+    ///
+    /// Environment variables, labels, the entrypoint, and the command which are the same as the
+    /// `image`'s are left out, as are settings which are Podman's defaults.
+    ///
+    /// Returns the arguments (without `podman run`) and descriptions of the settings which could
+    /// not be reproduced.
+    pub fn create_args(&self, image: &ImageConfig) -> (Vec<String>, Vec<String>) {
+        let short_id = self.short_id();
+        let Self {
+            id: _,
+            name,
+            pod,
+            image: image_id,
+            image_name,
+            config,
+            host_config,
+            network_settings,
+            mounts,
+        } = self;
+
+        let mut args = Args::default();
+        let mut unsupported = Vec::new();
+        // Pod members share the pod's namespaces, see `HostConfig::push_args()`.
+        let in_pod = !pod.is_empty();
+
+        args.push("--name", name);
+
+        for env in &config.env {
+            let key = env.split_once('=').map_or(env.as_str(), |(key, _)| key);
+            // Podman sets these if the image does not.
+            let prefix = format!("{key}=");
+            let podman_default = matches!(key, "container" | "HOSTNAME" | "HOME")
+                && !image
+                    .env
+                    .iter()
+                    .any(|image_env| image_env.starts_with(&prefix));
+            if !podman_default && !image.env.contains(env) {
+                args.push("--env", env);
+            }
+        }
+
+        for (label, value) in &config.labels {
+            if image.labels.get(label) != Some(value) {
+                args.push("--label", format!("{label}={value}"));
+            }
+        }
+
+        // Podman's default host name is the container's short ID.
+        if !in_pod && !config.hostname.is_empty() && config.hostname != short_id {
+            args.push("--hostname", &config.hostname);
+        }
+
+        if !config.user.is_empty() && config.user != image.user {
+            args.push("--user", &config.user);
+        }
+
+        if !config.working_dir.is_empty()
+            && config.working_dir != image.working_dir
+            && !(config.working_dir == "/" && image.working_dir.is_empty())
+        {
+            args.push("--workdir", &config.working_dir);
+        }
+
+        let entrypoint = config.entrypoint.clone().unwrap_or_default().into_args();
+        if entrypoint != image.entrypoint {
+            args.push("--entrypoint", json(&entrypoint));
+        }
+
+        if let Some(stop_timeout) = config.stop_timeout.filter(|timeout| *timeout != 10) {
+            args.push("--stop-timeout", stop_timeout.to_string());
+        }
+
+        if let Some(healthcheck) = &config.healthcheck {
+            healthcheck.push_args(&mut args);
+        }
+
+        mounts_args(mounts, host_config, &mut args, &mut unsupported);
+        host_config.push_args(in_pod, &mut args, &mut unsupported);
+        if !in_pod {
+            for network in networks(
+                &host_config.network_mode,
+                network_settings,
+                [name, short_id],
+            ) {
+                args.push("--network", network);
+            }
+        }
+
+        args.0.push(if image_name.is_empty() {
+            image_id.clone()
+        } else {
+            image_name.clone()
+        });
+        if config.cmd != image.cmd {
+            args.0.extend(config.cmd.iter().cloned());
+        }
+
+        (args.0, unsupported)
+    }
+}
+
+/// Arguments for `podman run`.
+#[derive(Debug, Default)]
+pub(super) struct Args(pub Vec<String>);
+
+impl Args {
+    /// Add `flag` with its `value`.
+    pub fn push(&mut self, flag: &str, value: impl Into<String>) {
+        self.0.push(flag.to_owned());
+        self.0.push(value.into());
+    }
+
+    /// Add `flag` if it is `set`.
+    pub fn push_flag(&mut self, flag: &str, set: bool) {
+        if set {
+            self.0.push(flag.to_owned());
+        }
+    }
+}
+
+/// Serialize `args` as a JSON array.
+fn json(args: &[String]) -> String {
+    serde_json::to_string(args).expect("a list of strings always serializes")
+}
+
+/// Part of `Config` object from the output of `podman container inspect`
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct ContainerConfig {
+    #[serde(deserialize_with = "null_default")]
+    create_command: Vec<String>,
+
+    /// --hostname
+    hostname: String,
+
+    /// --env
+    #[serde(deserialize_with = "null_default")]
+    env: Vec<String>,
+
+    /// --label
+    #[serde(deserialize_with = "null_default")]
+    labels: IndexMap<String, String>,
+
+    /// --user
+    user: String,
+
+    /// --workdir
+    working_dir: String,
+
+    /// --entrypoint
+    ///
+    /// Podman v4 outputs the entrypoint as a single string.
+    entrypoint: Option<Entrypoint>,
+
+    /// The command after the image.
+    #[serde(deserialize_with = "null_default")]
+    cmd: Vec<String>,
+
+    /// --stop-timeout
+    stop_timeout: Option<u64>,
+
+    /// --health-*
+    healthcheck: Option<Healthcheck>,
+}
+
+/// Entrypoint of a container or image.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+enum Entrypoint {
+    Args(Vec<String>),
+    Joined(String),
+}
+
+impl Default for Entrypoint {
+    fn default() -> Self {
+        Self::Args(Vec::new())
+    }
+}
+
+impl Entrypoint {
+    /// The entrypoint as a list of arguments.
+    fn into_args(self) -> Vec<String> {
+        match self {
+            Self::Args(args) => args,
+            Self::Joined(entrypoint) => entrypoint.split_whitespace().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Health check settings from `podman container inspect`.
+///
+/// Durations are in nanoseconds.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct Healthcheck {
+    /// `["CMD-SHELL", command]`, `["CMD", args...]`, or `["NONE"]`
+    #[serde(deserialize_with = "null_default")]
+    test: Vec<String>,
+    interval: u64,
+    timeout: u64,
+    start_period: u64,
+    retries: u64,
+}
+
+impl Healthcheck {
+    /// Add the `--health-*` arguments, leaving out Podman's defaults.
+    fn push_args(&self, args: &mut Args) {
+        let Self {
+            test,
+            interval,
+            timeout,
+            start_period,
+            retries,
+        } = self;
+
+        match test.split_first() {
+            Some((kind, command)) if kind == "CMD-SHELL" => {
+                args.push("--health-cmd", command.join(" "));
+            }
+            Some((kind, command)) if kind == "CMD" => args.push("--health-cmd", json(command)),
+            Some((kind, _)) if kind == "NONE" => args.push("--health-cmd", "none"),
+            _ => return,
+        }
+
+        for (flag, nanos, default) in [
+            ("--health-interval", *interval, 30),
+            ("--health-timeout", *timeout, 30),
+            ("--health-start-period", *start_period, 0),
+        ] {
+            let duration = Duration::from_nanos(nanos);
+            if nanos != 0 && duration != Duration::from_secs(default) {
+                args.push(flag, duration::to_string(duration));
+            }
+        }
+
+        if *retries != 0 && *retries != 3 {
+            args.push("--health-retries", retries.to_string());
+        }
+    }
+}
+
+/// Part of `HostConfig` object from the output of `podman container inspect`
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct HostConfig {
+    /// --network mode
+    network_mode: String,
+
+    /// --publish, keyed by container port and protocol, e.g. "80/tcp"
+    #[serde(deserialize_with = "null_default")]
+    port_bindings: IndexMap<String, Option<Vec<PortBinding>>>,
+
+    /// --restart
+    restart_policy: RestartPolicy,
+
+    /// --cap-add
+    #[serde(deserialize_with = "null_default")]
+    cap_add: Vec<String>,
+
+    /// --cap-drop
+    #[serde(deserialize_with = "null_default")]
+    cap_drop: Vec<String>,
+
+    /// --device
+    #[serde(deserialize_with = "null_default")]
+    devices: Vec<Device>,
+
+    /// --tmpfs, keyed by container path
+    #[serde(deserialize_with = "null_default")]
+    tmpfs: IndexMap<String, String>,
+
+    /// --memory, in bytes
+    memory: i64,
+
+    /// --cpus, in billionths of a CPU
+    nano_cpus: i64,
+
+    /// --pids-limit
+    pids_limit: i64,
+
+    /// --privileged
+    privileged: bool,
+
+    /// --read-only
+    readonly_rootfs: bool,
+
+    /// --init
+    init: bool,
+
+    /// --security-opt
+    #[serde(deserialize_with = "null_default")]
+    security_opt: Vec<String>,
+
+    /// --dns
+    #[serde(deserialize_with = "null_default")]
+    dns: Vec<String>,
+
+    /// --dns-option
+    #[serde(deserialize_with = "null_default")]
+    dns_options: Vec<String>,
+
+    /// --dns-search
+    #[serde(deserialize_with = "null_default")]
+    dns_search: Vec<String>,
+
+    /// --add-host
+    #[serde(deserialize_with = "null_default")]
+    extra_hosts: Vec<String>,
+
+    /// --ulimit
+    #[serde(deserialize_with = "null_default")]
+    ulimits: Vec<Ulimit>,
+
+    /// --sysctl
+    #[serde(deserialize_with = "null_default")]
+    sysctls: IndexMap<String, String>,
+
+    /// --log-driver, --log-opt
+    #[serde(deserialize_with = "null_default")]
+    log_config: LogConfig,
+
+    /// --userns
+    userns_mode: String,
+}
+
+impl HostConfig {
+    /// Add the arguments for the host config, except for networks and mounts.
+    ///
+    /// Containers `in_pod` share the pod's network, UTS, and user namespaces, so the published
+    /// ports, DNS settings, hosts, user namespace mode, and network sysctls, which belong to the
+    /// pod, are skipped.
+    fn push_args(&self, in_pod: bool, args: &mut Args, unsupported: &mut Vec<String>) {
+        let Self {
+            network_mode: _,
+            port_bindings: _,
+            restart_policy,
+            cap_add,
+            cap_drop,
+            devices,
+            tmpfs: _,
+            memory,
+            nano_cpus,
+            pids_limit,
+            privileged,
+            readonly_rootfs,
+            init,
+            security_opt,
+            dns: _,
+            dns_options: _,
+            dns_search: _,
+            extra_hosts: _,
+            ulimits,
+            sysctls,
+            log_config,
+            userns_mode: _,
+        } = self;
+
+        if !in_pod {
+            self.push_namespace_args(args);
+        }
+
+        restart_policy.push_args(args, unsupported);
+
+        for capability in cap_add {
+            args.push("--cap-add", capability);
+        }
+        for capability in cap_drop {
+            args.push("--cap-drop", capability);
+        }
+
+        for Device {
+            path_on_host,
+            path_in_container,
+            cgroup_permissions,
+        } in devices
+        {
+            args.push(
+                "--device",
+                format!("{path_on_host}:{path_in_container}:{cgroup_permissions}"),
+            );
+        }
+
+        if *memory > 0 {
+            args.push("--memory", memory.to_string());
+        }
+        if *nano_cpus > 0 {
+            #[expect(
+                clippy::cast_precision_loss,
+                reason = "CPU counts are small enough to be precise"
+            )]
+            let cpus = *nano_cpus as f64 / 1e9;
+            args.push("--cpus", cpus.to_string());
+        }
+        // 2048 is the default from containers.conf.
+        if *pids_limit > 0 && *pids_limit != 2048 {
+            args.push("--pids-limit", pids_limit.to_string());
+        }
+
+        args.push_flag("--privileged", *privileged);
+        args.push_flag("--read-only", *readonly_rootfs);
+        args.push_flag("--init", *init);
+
+        for option in security_opt {
+            if option.parse::<SecurityOpt>().is_ok() {
+                args.push("--security-opt", option);
+            } else {
+                unsupported.push(format!("security option `{option}`"));
+            }
+        }
+
+        for ulimit in ulimits {
+            ulimit.push_args(args);
+        }
+
+        for (sysctl, value) in sysctls {
+            // Default from containers.conf.
+            let default = sysctl == "net.ipv4.ping_group_range" && value == "0 0";
+            let pod_owned = in_pod && sysctl.starts_with("net.");
+            if !(default || pod_owned) {
+                args.push("--sysctl", format!("{sysctl}={value}"));
+            }
+        }
+
+        log_config.push_args(args);
+    }
+
+    /// Add the `--publish`, `--dns*`, `--add-host`, and `--userns` arguments.
+    fn push_namespace_args(&self, args: &mut Args) {
+        let Self {
+            port_bindings,
+            dns,
+            dns_options,
+            dns_search,
+            extra_hosts,
+            userns_mode,
+            ..
+        } = self;
+
+        for port in publish_ports(port_bindings) {
+            args.push("--publish", port);
+        }
+
+        for (flag, values) in [
+            ("--dns", dns),
+            ("--dns-option", dns_options),
+            ("--dns-search", dns_search),
+            ("--add-host", extra_hosts),
+        ] {
+            for value in values {
+                args.push(flag, value);
+            }
+        }
+
+        if !matches!(userns_mode.as_str(), "" | "host") {
+            args.push("--userns", userns_mode);
+        }
+    }
+}
+
+/// Format the `port_bindings`, keyed by container port and protocol, e.g. "80/tcp", as values for
+/// `--publish`.
+pub(super) fn publish_ports(
+    port_bindings: &IndexMap<String, Option<Vec<PortBinding>>>,
+) -> Vec<String> {
+    let mut ports = Vec::new();
+    for (container_port, bindings) in port_bindings {
+        let container_port = container_port
+            .strip_suffix("/tcp")
+            .unwrap_or(container_port);
+        for PortBinding { host_ip, host_port } in bindings.iter().flatten() {
+            ports.push(match (host_ip.as_str(), host_port.as_str()) {
+                (_, "") => container_port.to_owned(),
+                ("", host_port) => format!("{host_port}:{container_port}"),
+                (host_ip, host_port) if host_ip.contains(':') => {
+                    format!("[{host_ip}]:{host_port}:{container_port}")
+                }
+                (host_ip, host_port) => format!("{host_ip}:{host_port}:{container_port}"),
+            });
+        }
+    }
+    ports
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub(super) struct PortBinding {
+    host_ip: String,
+    host_port: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct RestartPolicy {
+    name: String,
+    maximum_retry_count: u64,
+}
+
+impl RestartPolicy {
+    /// Add the `--restart` argument, the maximum retry count is not supported.
+    fn push_args(&self, args: &mut Args, unsupported: &mut Vec<String>) {
+        let Self {
+            name,
+            maximum_retry_count,
+        } = self;
+
+        if !matches!(name.as_str(), "" | "no") {
+            args.push("--restart", name);
+        }
+        if *maximum_retry_count > 0 {
+            unsupported.push(format!(
+                "restart policy maximum retry count ({maximum_retry_count})"
+            ));
+        }
+    }
+}
+
+/// Resource limit of the container's process.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct Ulimit {
+    /// e.g. `RLIMIT_NOFILE`
+    name: String,
+    /// -1 if unlimited
+    soft: i64,
+    /// -1 if unlimited
+    hard: i64,
+}
+
+impl Ulimit {
+    /// Add the `--ulimit` argument, leaving out Podman's defaults.
+    fn push_args(&self, args: &mut Args) {
+        let Self { name, soft, hard } = self;
+
+        // Podman always sets these, by default to the same soft and hard limit.
+        let default = match name.as_str() {
+            "RLIMIT_NOFILE" => Some(1_048_576),
+            "RLIMIT_NPROC" => Some(4_194_304),
+            _ => None,
+        };
+        if default.is_some_and(|default| *soft == default && *hard == default) {
+            return;
+        }
+
+        let name = name.strip_prefix("RLIMIT_").unwrap_or(name).to_lowercase();
+        let [soft, hard] = [*soft, *hard].map(|limit| limit.max(-1).to_string());
+        args.push("--ulimit", format!("{name}={soft}:{hard}"));
+    }
+}
+
+/// Logging settings of the container.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct LogConfig {
+    /// --log-driver
+    #[serde(rename = "Type")]
+    driver: String,
+
+    /// --log-opt
+    #[serde(deserialize_with = "null_default")]
+    config: IndexMap<String, String>,
+
+    /// --log-opt path=PATH
+    path: String,
+
+    /// --log-opt tag=TAG
+    tag: String,
+
+    /// --log-opt max-size=SIZE
+    size: String,
+}
+
+impl LogConfig {
+    /// Add the `--log-driver` and `--log-opt` arguments, leaving out Podman's defaults.
+    fn push_args(&self, args: &mut Args) {
+        let Self {
+            driver,
+            config,
+            path,
+            tag,
+            size,
+        } = self;
+
+        if !matches!(driver.as_str(), "" | "journald") {
+            args.push("--log-driver", driver);
+        }
+
+        for (option, value) in config {
+            args.push("--log-opt", format!("{option}={value}"));
+        }
+        // The default path is in the container's storage directory.
+        if !path.is_empty() && !path.ends_with("/userdata/ctr.log") {
+            args.push("--log-opt", format!("path={path}"));
+        }
+        if !tag.is_empty() {
+            args.push("--log-opt", format!("tag={tag}"));
+        }
+        // The size is unlimited by default.
+        if !matches!(size.as_str(), "" | "0B") && !size.starts_with('-') {
+            args.push("--log-opt", format!("max-size={size}"));
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct Device {
+    path_on_host: String,
+    path_in_container: String,
+    cgroup_permissions: String,
+}
+
+/// Part of `NetworkSettings` object from the output of `podman container inspect`
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct NetworkSettings {
+    /// Networks the container is connected to, keyed by name.
+    #[serde(deserialize_with = "null_default")]
+    networks: IndexMap<String, Endpoint>,
+}
+
+/// The container's settings for one of its networks.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct Endpoint {
+    /// --network NAME:alias=ALIAS
+    #[serde(deserialize_with = "null_default")]
+    aliases: Vec<String>,
+
+    /// --network NAME:ip=IPV4,ip6=IPV6
+    ///
+    /// Static IP addresses, keyed by `IPv4Address` and `IPv6Address`.
+    #[serde(rename = "IPAMConfig", deserialize_with = "null_default")]
+    ipam_config: IndexMap<String, String>,
+}
+
+impl Endpoint {
+    /// Options for `--network NAME:OPTIONS`, skipping the `container_aliases` Podman adds to every
+    /// container.
+    fn options(&self, container_aliases: [&str; 2]) -> Vec<String> {
+        let Self {
+            aliases,
+            ipam_config,
+        } = self;
+
+        let aliases = aliases
+            .iter()
+            .filter(|alias| !container_aliases.contains(&alias.as_str()))
+            .map(|alias| format!("alias={alias}"));
+        let ips = [("IPv4Address", "ip"), ("IPv6Address", "ip6")]
+            .into_iter()
+            .filter_map(|(key, option)| {
+                ipam_config
+                    .get(key)
+                    .filter(|ip| !ip.is_empty())
+                    .map(|ip| format!("{option}={ip}"))
+            });
+        aliases.chain(ips).collect()
+    }
+}
+
+/// Values for `--network` from the `network_mode`.
+///
+/// In bridge mode, the container's networks, with their aliases and static IPs, are returned,
+/// unless it is only connected to the default `podman` network without any options.
+fn networks(
+    network_mode: &str,
+    network_settings: &NetworkSettings,
+    container_aliases: [&str; 2],
+) -> Vec<String> {
+    match network_mode {
+        "" => Vec::new(),
+        "bridge" => {
+            let networks: Vec<String> = network_settings
+                .networks
+                .iter()
+                .map(|(name, endpoint)| {
+                    let options = endpoint.options(container_aliases);
+                    if options.is_empty() {
+                        name.clone()
+                    } else {
+                        format!("{name}:{}", options.join(","))
+                    }
+                })
+                .collect();
+            if networks == ["podman"] {
+                Vec::new()
+            } else {
+                networks
+            }
+        }
+        mode => vec![mode.to_owned()],
+    }
+}
+
+/// Mount in the output of `podman container inspect`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct Mount {
+    /// "bind", "volume", "tmpfs", "image", etc.
+    #[serde(rename = "Type")]
+    kind: String,
+
+    /// Name of the volume.
+    name: String,
+
+    /// Host path of the bind mount or volume.
+    source: String,
+
+    destination: String,
+
+    /// SELinux relabeling, "z" or "Z".
+    mode: String,
+
+    #[serde(rename = "RW")]
+    rw: bool,
+
+    propagation: String,
+}
+
+/// Add the `--volume` and `--tmpfs` arguments for the `mounts`.
+fn mounts_args(
+    mounts: &[Mount],
+    host_config: &HostConfig,
+    args: &mut Args,
+    unsupported: &mut Vec<String>,
+) {
+    for Mount {
+        kind,
+        name,
+        source,
+        destination,
+        mode,
+        rw,
+        propagation,
+    } in mounts
+    {
+        let source = match kind.as_str() {
+            "bind" => source,
+            // Anonymous volumes are named with a 64 character hex ID.
+            "volume" if name.len() == 64 && name.chars().all(|char| char.is_ascii_hexdigit()) => {
+                args.push("--volume", destination);
+                continue;
+            }
+            "volume" => name,
+            "tmpfs" => {
+                if !host_config.tmpfs.contains_key(destination) {
+                    args.push("--tmpfs", destination);
+                }
+                continue;
+            }
+            kind => {
+                unsupported.push(format!("{kind} mount at `{destination}`"));
+                continue;
+            }
+        };
+
+        let options: Vec<&str> = [
+            (!rw).then_some("ro"),
+            (!mode.is_empty()).then_some(mode.as_str()),
+            (!matches!(propagation.as_str(), "" | "rprivate")).then_some(propagation.as_str()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        if options.is_empty() {
+            args.push("--volume", format!("{source}:{destination}"));
+        } else {
+            args.push(
+                "--volume",
+                format!("{source}:{destination}:{}", options.join(",")),
+            );
+        }
+    }
+
+    for (destination, options) in &host_config.tmpfs {
+        if options.is_empty() {
+            args.push("--tmpfs", destination);
+        } else {
+            args.push("--tmpfs", format!("{destination}:{options}"));
+        }
+    }
+}
+
+/// Selected output of `podman image inspect`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct ImageInspect {
+    config: ImageConfig,
+}
+
+/// `Config` object from the output of `podman image inspect`, the default settings of containers
+/// created from the image.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ImageConfig {
+    #[serde(deserialize_with = "null_default")]
+    env: Vec<String>,
+
+    #[serde(deserialize_with = "null_default")]
+    labels: IndexMap<String, String>,
+
+    user: String,
+
+    working_dir: String,
+
+    #[serde(deserialize_with = "null_default")]
+    entrypoint: Vec<String>,
+
+    #[serde(deserialize_with = "null_default")]
+    cmd: Vec<String>,
+}
+
+impl ImageConfig {
+    /// Runs `podman image inspect` on the image and deserializes the `Config` object from the
+    /// output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is problem running `podman image inspect`,
+    /// it doesn't complete successfully,
+    /// or if the output cannot be properly deserialized.
+    pub fn from_image(backend: &Backend, image: &str) -> color_eyre::Result<Self> {
+        backend
+            .inspect(ResourceKind::Image, image)
+            .map(|ImageInspect { config }| config)
+    }
+}
+
+/// Deserialize `null` as the default value.
+pub(super) fn null_default<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Deserialize<'de> + Default,
+    D: serde::Deserializer<'de>,
+{
+    Option::deserialize(deserializer).map(Option::unwrap_or_default)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{super::ContainerParser, *};
+
+    #[test]
+    fn create_args() -> color_eyre::Result<()> {
+        let container: ContainerInspect = serde_json::from_str(
+            r#"{
+                "Id": "0123456789abcdef",
+                "Name": "web",
+                "Image": "0123",
+                "ImageName": "docker.io/library/nginx:latest",
+                "Config": {
+                    "Hostname": "www",
+                    "Env": ["PATH=/usr/bin", "container=podman", "HOME=/root", "ONE=one"],
+                    "Labels": {"image": "label", "app": "web"},
+                    "WorkingDir": "/",
+                    "Entrypoint": "/docker-entrypoint.sh",
+                    "Cmd": ["nginx", "-g", "daemon off;"],
+                    "StopTimeout": 10,
+                    "Healthcheck": {
+                        "Test": ["CMD-SHELL", "curl -f localhost"],
+                        "Interval": 60000000000,
+                        "Timeout": 30000000000,
+                        "Retries": 3
+                    }
+                },
+                "HostConfig": {
+                    "NetworkMode": "bridge",
+                    "PortBindings": {
+                        "80/tcp": [{"HostIp": "", "HostPort": "8080"}],
+                        "53/udp": [{"HostIp": "127.0.0.1", "HostPort": "53"}]
+                    },
+                    "RestartPolicy": {"Name": "on-failure", "MaximumRetryCount": 3},
+                    "CapAdd": ["CAP_NET_ADMIN"],
+                    "CapDrop": null,
+                    "Memory": 1073741824,
+                    "PidsLimit": 2048
+                },
+                "NetworkSettings": {"Networks": {"app": {
+                    "Aliases": ["web", "0123456789ab", "www"],
+                    "IPAMConfig": {"IPv4Address": "10.89.0.5"}
+                }}},
+                "Mounts": [
+                    {"Type": "volume", "Name": "data", "Destination": "/data", "RW": true},
+                    {"Type": "bind", "Source": "/srv", "Destination": "/srv", "Mode": "Z",
+                        "RW": false, "Propagation": "rprivate"},
+                    {"Type": "image", "Destination": "/image", "RW": true}
+                ]
+            }"#,
+        )?;
+        let image: ImageConfig = serde_json::from_str(
+            r#"{
+                "Env": ["PATH=/usr/bin", "HOMEPAGE=https://example.com"],
+                "Labels": {"image": "label"},
+                "Entrypoint": ["/docker-entrypoint.sh"],
+                "Cmd": ["nginx", "-g", "daemon off;"]
+            }"#,
+        )?;
+
+        let (args, unsupported) = container.create_args(&image);
+        assert_eq!(
+            args,
+            [
+                "--name",
+                "web",
+                "--env",
+                "ONE=one",
+                "--label",
+                "app=web",
+                "--hostname",
+                "www",
+                "--health-cmd",
+                "curl -f localhost",
+                "--health-interval",
+                "1m",
+                "--volume",
+                "data:/data",
+                "--volume",
+                "/srv:/srv:ro,Z",
+                "--publish",
+                "8080:80",
+                "--publish",
+                "127.0.0.1:53:53/udp",
+                "--restart",
+                "on-failure",
+                "--cap-add",
+                "CAP_NET_ADMIN",
+                "--memory",
+                "1073741824",
+                "--network",
+                "app:alias=www,ip=10.89.0.5",
+                "docker.io/library/nginx:latest",
+            ]
+        );
+        assert_eq!(
+            unsupported,
+            [
+                "image mount at `/image`",
+                "restart policy maximum retry count (3)"
+            ]
+        );
+
+        ContainerParser::try_parse_from(&args)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn host_config_args() -> color_eyre::Result<()> {
+        let host_config: HostConfig = serde_json::from_str(
+            r#"{
+                "SecurityOpt": ["label=disable", "unknown=option"],
+                "Dns": ["10.0.0.1"],
+                "DnsSearch": ["example.com"],
+                "ExtraHosts": ["db:10.0.0.2"],
+                "Ulimits": [
+                    {"Name": "RLIMIT_NOFILE", "Soft": 1048576, "Hard": 1048576},
+                    {"Name": "RLIMIT_CORE", "Soft": -1, "Hard": -1}
+                ],
+                "Sysctls": {
+                    "net.ipv4.ping_group_range": "0 0",
+                    "net.ipv4.ip_forward": "1"
+                },
+                "LogConfig": {
+                    "Type": "k8s-file",
+                    "Config": null,
+                    "Path": "/var/lib/containers/storage/overlay-containers/0123/userdata/ctr.log",
+                    "Tag": "web",
+                    "Size": "0B"
+                },
+                "UsernsMode": "keep-id"
+            }"#,
+        )?;
+
+        let mut args = Args(vec![String::from("--name"), String::from("web")]);
+        let mut unsupported = Vec::new();
+        host_config.push_args(false, &mut args, &mut unsupported);
+        args.0.push(String::from("image"));
+        assert_eq!(
+            args.0,
+            [
+                "--name",
+                "web",
+                "--dns",
+                "10.0.0.1",
+                "--dns-search",
+                "example.com",
+                "--add-host",
+                "db:10.0.0.2",
+                "--userns",
+                "keep-id",
+                "--security-opt",
+                "label=disable",
+                "--ulimit",
+                "core=-1:-1",
+                "--sysctl",
+                "net.ipv4.ip_forward=1",
+                "--log-driver",
+                "k8s-file",
+                "--log-opt",
+                "tag=web",
+                "image",
+            ]
+        );
+        assert_eq!(unsupported, ["security option `unknown=option`"]);
+
+        ContainerParser::try_parse_from(&args.0)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn pod_member_create_args() -> color_eyre::Result<()> {
+        let container: ContainerInspect = serde_json::from_str(
+            r#"{
+                "Id": "0123456789abcdef",
+                "Name": "app-web",
+                "Pod": "fedcba9876543210",
+                "Image": "0123",
+                "ImageName": "docker.io/library/nginx:latest",
+                "Config": {"Hostname": "app", "Env": ["ONE=one"]},
+                "HostConfig": {
+                    "NetworkMode": "container:a1b2c3d4e5f6",
+                    "PortBindings": {"80/tcp": [{"HostIp": "", "HostPort": "8080"}]},
+                    "Dns": ["10.0.0.1"],
+                    "ExtraHosts": ["db:10.0.0.2"],
+                    "Sysctls": {"net.ipv4.ip_forward": "1", "kernel.msgmax": "65536"},
+                    "UsernsMode": "container:a1b2c3d4e5f6",
+                    "Init": true
+                },
+                "NetworkSettings": {"Networks": {"app": {"Aliases": ["www"]}}}
+            }"#,
+        )?;
+
+        let (args, unsupported) = container.create_args(&ImageConfig::default());
+        assert_eq!(
+            args,
+            [
+                "--name",
+                "app-web",
+                "--env",
+                "ONE=one",
+                "--init",
+                "--sysctl",
+                "kernel.msgmax=65536",
+                "docker.io/library/nginx:latest",
+            ]
+        );
+        assert!(unsupported.is_empty());
+
+        ContainerParser::try_parse_from(&args)?;
+
+        Ok(())
+    }
+
+    /// Deserialize the `container` and `image` inspect output and rebuild the container's
+    /// arguments, checking that they parse.
+    fn inspect_args(
+        container: &str,
+        image: &str,
+    ) -> color_eyre::Result<(Vec<String>, Vec<String>)> {
+        let container: ContainerInspect = serde_json::from_str(container)?;
+        let image: ImageConfig = serde_json::from_str(image)?;
+        let (args, unsupported) = container.create_args(&image);
+        ContainerParser::try_parse_from(&args)?;
+        Ok((args, unsupported))
+    }
+
+    #[test]
+    fn image_settings_skipped() -> color_eyre::Result<()> {
+        let (args, unsupported) = inspect_args(
+            r#"{
+                "Name": "web",
+                "ImageName": "image",
+                "Config": {
+                    "Env": ["PATH=/usr/bin", "HOSTNAME=web", "container=podman", "HOME=/root",
+                        "ONE=one"],
+                    "Labels": {"image": "label", "version": "2"},
+                    "Entrypoint": ["/entrypoint.sh"],
+                    "Cmd": ["serve"]
+                }
+            }"#,
+            r#"{
+                "Env": ["PATH=/usr/bin"],
+                "Labels": {"image": "label", "version": "1"},
+                "Entrypoint": ["/entrypoint.sh"],
+                "Cmd": ["serve"]
+            }"#,
+        )?;
+        assert_eq!(
+            args,
+            [
+                "--name",
+                "web",
+                "--env",
+                "ONE=one",
+                "--label",
+                "version=2",
+                "image"
+            ]
+        );
+        assert!(unsupported.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn image_settings_overridden() -> color_eyre::Result<()> {
+        // `HOME=` is kept as the image sets it to something else.
+        let (args, unsupported) = inspect_args(
+            r#"{
+                "Name": "web",
+                "ImageName": "image",
+                "Config": {
+                    "Env": ["HOME=/root", "container=podman"],
+                    "Entrypoint": ["/bin/sh", "-c"],
+                    "Cmd": ["echo hello"]
+                }
+            }"#,
+            r#"{"Env": ["HOME=/app"], "Entrypoint": ["/entrypoint.sh"], "Cmd": ["serve"]}"#,
+        )?;
+        assert_eq!(
+            args,
+            [
+                "--name",
+                "web",
+                "--env",
+                "HOME=/root",
+                "--entrypoint",
+                r#"["/bin/sh","-c"]"#,
+                "image",
+                "echo hello",
+            ]
+        );
+        assert!(unsupported.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn joined_entrypoint() -> color_eyre::Result<()> {
+        // Podman v4 outputs the entrypoint as a single string.
+        let image = r#"{"Entrypoint": ["/bin/sh", "-c"]}"#;
+        let (args, unsupported) = inspect_args(
+            r#"{"Name": "web", "ImageName": "image", "Config": {"Entrypoint": "/bin/sh -c"}}"#,
+            image,
+        )?;
+        assert_eq!(args, ["--name", "web", "image"]);
+        assert!(unsupported.is_empty());
+
+        let (args, unsupported) = inspect_args(
+            r#"{
+                "Name": "web",
+                "ImageName": "image",
+                "Config": {"Entrypoint": "/entrypoint.sh --verbose"}
+            }"#,
+            image,
+        )?;
+        assert_eq!(
+            args,
+            [
+                "--name",
+                "web",
+                "--entrypoint",
+                r#"["/entrypoint.sh","--verbose"]"#,
+                "image",
+            ]
+        );
+        assert!(unsupported.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn healthcheck_defaults() -> color_eyre::Result<()> {
+        let (args, unsupported) = inspect_args(
+            r#"{
+                "Name": "web",
+                "ImageName": "image",
+                "Config": {"Healthcheck": {
+                    "Test": ["CMD", "curl", "-f", "localhost"],
+                    "Interval": 30000000000,
+                    "Timeout": 30000000000,
+                    "StartPeriod": 0,
+                    "Retries": 3
+                }}
+            }"#,
+            "{}",
+        )?;
+        assert_eq!(
+            args,
+            [
+                "--name",
+                "web",
+                "--health-cmd",
+                r#"["curl","-f","localhost"]"#,
+                "image",
+            ]
+        );
+        assert!(unsupported.is_empty());
+
+        let (args, unsupported) = inspect_args(
+            r#"{
+                "Name": "web",
+                "ImageName": "image",
+                "Config": {"Healthcheck": {"Test": ["NONE"], "StartPeriod": 5000000000}}
+            }"#,
+            "{}",
+        )?;
+        assert_eq!(
+            args,
+            [
+                "--name",
+                "web",
+                "--health-cmd",
+                "none",
+                "--health-start-period",
+                "5s",
+                "image",
+            ]
+        );
+        assert!(unsupported.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn resource_defaults() -> color_eyre::Result<()> {
+        let (args, unsupported) = inspect_args(
+            r#"{
+                "Name": "web",
+                "ImageName": "image",
+                "HostConfig": {
+                    "NanoCpus": 1500000000,
+                    "PidsLimit": 2048,
+                    "Sysctls": {"net.ipv4.ping_group_range": "0 0", "kernel.msgmax": "65536"}
+                }
+            }"#,
+            "{}",
+        )?;
+        assert_eq!(
+            args,
+            [
+                "--name",
+                "web",
+                "--cpus",
+                "1.5",
+                "--sysctl",
+                "kernel.msgmax=65536",
+                "image",
+            ]
+        );
+        assert!(unsupported.is_empty());
+
+        let (args, unsupported) = inspect_args(
+            r#"{
+                "Name": "web",
+                "ImageName": "image",
+                "HostConfig": {
+                    "PidsLimit": 100,
+                    "Sysctls": {"net.ipv4.ping_group_range": "0 2000"}
+                }
+            }"#,
+            "{}",
+        )?;
+        assert_eq!(
+            args,
+            [
+                "--name",
+                "web",
+                "--pids-limit",
+                "100",
+                "--sysctl",
+                "net.ipv4.ping_group_range=0 2000",
+                "image",
+            ]
+        );
+        assert!(unsupported.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_security_options() -> color_eyre::Result<()> {
+        let (args, unsupported) = inspect_args(
+            r#"{
+                "Name": "web",
+                "ImageName": "image",
+                "HostConfig": {
+                    "SecurityOpt": ["no-new-privileges", "label=unknown", "seccomp=unconfined",
+                        "unknown=option"]
+                }
+            }"#,
+            "{}",
+        )?;
+        assert_eq!(
+            args,
+            [
+                "--name",
+                "web",
+                "--security-opt",
+                "no-new-privileges",
+                "--security-opt",
+                "seccomp=unconfined",
+                "image",
+            ]
+        );
+        assert_eq!(
+            unsupported,
+            [
+                "security option `label=unknown`",
+                "security option `unknown=option`",
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+//! Provides [`generate()`] for `podlet generate kube`, which wraps existing pods and containers in
+//! a `.kube` Quadlet file.
+
+use std::path::PathBuf;
+
+use color_eyre::eyre::WrapErr;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{
+    cli::File,
+    quadlet::{self, GenericSections, Globals, Service, kube::AutoUpdate},
+};
+
+use super::{Backend, container::ContainerInspect, pod::PodInspect};
+
+/// Run `podman kube generate` on the pods or containers in `resources` and create a `.kube` Quadlet
+/// file which plays the generated Kubernetes YAML.
+///
+/// The YAML file is named after the `.kube` file, `{name}-kube.yaml`. If not given, the `name` is
+/// taken from the first resource.
+///
+/// The networks of the resources are carried over to `Network=`, as they are not included in the
+/// YAML. Published ports are not, as they are included in the YAML as `hostPort`s. `AutoUpdate=`
+/// is set from the `io.containers.autoupdate` annotations in the YAML.
+///
+/// # Errors
+///
+/// Returns an error if a resource could not be inspected, `podman kube generate` fails, or its
+/// output could not be deserialized.
+pub fn generate(
+    backend: &Backend,
+    resources: &[String],
+    name: Option<String>,
+    GenericSections {
+        unit,
+        quadlet,
+        install,
+    }: GenericSections,
+) -> color_eyre::Result<Vec<File>> {
+    let mut resource_names = Vec::with_capacity(resources.len());
+    let mut network = Vec::new();
+    for resource in resources {
+        let (name, networks) = if let Ok(pod) = PodInspect::from_pod(backend, resource) {
+            (pod.name().to_owned(), pod.networks())
+        } else {
+            let container = ContainerInspect::from_container(backend, resource)
+                .wrap_err_with(|| format!("error inspecting `{resource}`"))?;
+            (container.name().to_owned(), container.networks())
+        };
+        resource_names.push(name);
+        for value in networks {
+            if !network.contains(&value) {
+                network.push(value);
+            }
+        }
+    }
+
+    let yaml = backend.kube_generate(resources)?;
+
+    let mut annotations =
+        annotations(&yaml).wrap_err("error deserializing from `podman kube generate` output")?;
+    let auto_update = AutoUpdate::extract_from_annotations(&mut annotations);
+
+    let name = name.unwrap_or_else(|| resource_names.swap_remove(0));
+    let yaml_name = format!("{name}-kube.yaml");
+
+    let mut kube = quadlet::Kube::new(PathBuf::from(&yaml_name).into());
+    kube.auto_update = auto_update;
+    kube.network = network;
+
+    let kube = quadlet::File {
+        name,
+        unit,
+        resource: kube.into(),
+        globals: Globals::default(),
+        quadlet,
+        service: Service::default(),
+        install,
+        options: Vec::new(),
+    };
+
+    Ok(vec![
+        kube.into(),
+        File::Content {
+            name: yaml_name,
+            contents: yaml,
+            executable: false,
+        },
+    ])
+}
+
+/// Kubernetes object in the output of `podman kube generate`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct Object {
+    metadata: Metadata,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct Metadata {
+    annotations: Option<IndexMap<String, String>>,
+}
+
+/// Returns the annotations, in the form `KEY=VALUE`, of all objects in the Kubernetes `yaml`.
+fn annotations(yaml: &str) -> serde_yaml::Result<Vec<String>> {
+    let mut annotations = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(yaml) {
+        let Some(Object { metadata }) = Option::deserialize(document)? else {
+            continue;
+        };
+        annotations.extend(
+            metadata
+                .annotations
+                .into_iter()
+                .flatten()
+                .map(|(key, value)| format!("{key}={value}")),
+        );
+    }
+    Ok(annotations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_update_annotations() -> serde_yaml::Result<()> {
+        let yaml = "\
+# Save the output of this file and use kubectl create -f to import it into Kubernetes.
+apiVersion: v1
+kind: Pod
+metadata:
+  annotations:
+    io.containers.autoupdate/web: registry
+    io.kubernetes.cri-o.SandboxID/web: '0123'
+  name: web-pod
+spec:
+  containers:
+  - image: docker.io/library/nginx:latest
+    name: web
+---
+apiVersion: v1
+kind: PersistentVolumeClaim
+metadata:
+  name: data
+";
+        let mut annotations = annotations(yaml)?;
+        assert_eq!(
+            AutoUpdate::extract_from_annotations(&mut annotations),
+            [AutoUpdate::Container {
+                container: String::from("web"),
+                auto_update: quadlet::AutoUpdate::Registry,
+            }]
+        );
+        assert_eq!(annotations, ["io.kubernetes.cri-o.SandboxID/web=0123"]);
+
+        Ok(())
+    }
+}
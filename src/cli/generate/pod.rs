@@ -0,0 +1,492 @@
+//! Provides [`PodInspect`], the output of `podman pod inspect`, and the fallback for pods not
+//! created with `podman pod create`, see [`PodInspect::create_args()`].
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::quadlet::ResourceKind;
+
+use super::{
+    Backend,
+    container::{Args, PortBinding, null_default, publish_ports},
+};
+
+/// Selected output of `podman pod inspect`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct PodInspect {
+    /// Name of the pod.
+    name: String,
+
+    /// Full command and arguments that created the pod.
+    #[serde(deserialize_with = "null_default")]
+    create_command: Vec<String>,
+
+    /// ID of the pod's infra container.
+    #[serde(alias = "InfraContainerID")]
+    infra_container_id: String,
+
+    /// All containers in the pod.
+    #[serde(deserialize_with = "null_default")]
+    containers: Vec<PodContainer>,
+
+    /// --label
+    #[serde(deserialize_with = "null_default")]
+    labels: IndexMap<String, String>,
+
+    /// --hostname
+    hostname: String,
+
+    /// --share
+    #[serde(deserialize_with = "null_default")]
+    shared_namespaces: Vec<String>,
+
+    /// --exit-policy
+    exit_policy: String,
+
+    /// --restart
+    restart_policy: String,
+
+    infra_config: InfraConfig,
+}
+
+/// Container in output of `podman pod inspect`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct PodContainer {
+    /// The ID of the container.
+    id: String,
+
+    /// The name of the container.
+    name: String,
+}
+
+impl PodInspect {
+    /// Runs `podman pod inspect` on the pod and deserializes the output into [`Self`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is problem running `podman pod inspect`,
+    /// it doesn't complete successfully,
+    /// or if the output cannot be properly deserialized.
+    pub fn from_pod(backend: &Backend, pod: &str) -> color_eyre::Result<Self> {
+        backend.inspect(ResourceKind::Pod, pod)
+    }
+
+    /// Full command and arguments that created the pod.
+    ///
+    /// May be empty, or a different command like `podman kube play`, for pods not created with
+    /// `podman pod create`.
+    pub fn create_command(&self) -> &[String] {
+        &self.create_command
+    }
+
+    /// Name of the pod.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The pod's networks, as values for `--network`.
+    pub fn networks(&self) -> Vec<String> {
+        self.infra_config.networks()
+    }
+
+    /// Names of the containers in the pod, excluding the infra container.
+    pub fn container_names(&self) -> impl Iterator<Item = &str> {
+        self.containers
+            .iter()
+            .filter(|container| container.id != self.infra_container_id)
+            .map(|container| container.name.as_str())
+    }
+
+    /// Rebuild the `podman pod create` arguments for the pod from the inspected settings.
+    ///
+    /// Settings which are Podman's defaults are left out.
+    ///
+    /// Returns the arguments (starting with `create`) and descriptions of the settings which could
+    /// not be reproduced.
+    pub fn create_args(&self) -> (Vec<String>, Vec<String>) {
+        let Self {
+            name,
+            create_command: _,
+            infra_container_id: _,
+            containers: _,
+            labels,
+            hostname,
+            shared_namespaces,
+            exit_policy,
+            restart_policy,
+            infra_config,
+        } = self;
+
+        let mut args = Args(vec![String::from("create")]);
+        let mut unsupported = Vec::new();
+
+        args.push("--name", name);
+
+        for (label, value) in labels {
+            args.push("--label", format!("{label}={value}"));
+        }
+
+        if !hostname.is_empty() && hostname != name {
+            args.push("--hostname", hostname);
+        }
+
+        let mut shared_namespaces = shared_namespaces.clone();
+        shared_namespaces.sort();
+        if !shared_namespaces.is_empty()
+            && shared_namespaces != ["ipc", "net", "uts"]
+            && shared_namespaces != ["cgroup", "ipc", "net", "uts"]
+        {
+            args.push("--share", shared_namespaces.join(","));
+        }
+
+        if !exit_policy.is_empty() && exit_policy != "continue" {
+            args.push("--exit-policy", exit_policy);
+        }
+
+        if !matches!(restart_policy.as_str(), "" | "no") {
+            args.push("--restart", restart_policy);
+        }
+
+        infra_config.push_args(&mut args, &mut unsupported);
+
+        (args.0, unsupported)
+    }
+}
+
+/// `InfraConfig` object from the output of `podman pod inspect`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct InfraConfig {
+    /// --publish
+    #[serde(deserialize_with = "null_default")]
+    port_bindings: IndexMap<String, Option<Vec<PortBinding>>>,
+
+    /// --network host
+    host_network: bool,
+
+    /// --network
+    #[serde(deserialize_with = "null_default")]
+    networks: Vec<String>,
+
+    /// Options for the networks, keyed by network name.
+    #[serde(deserialize_with = "null_default")]
+    network_options: IndexMap<String, serde_json::Value>,
+
+    /// --ip, --ip6
+    #[serde(rename = "StaticIP")]
+    static_ip: String,
+
+    /// --mac-address
+    #[serde(rename = "StaticMAC")]
+    static_mac: String,
+
+    /// --dns
+    #[serde(rename = "DNSServer", deserialize_with = "null_default")]
+    dns_server: Vec<String>,
+
+    /// --dns-search
+    #[serde(rename = "DNSSearch", deserialize_with = "null_default")]
+    dns_search: Vec<String>,
+
+    /// --dns-option
+    #[serde(rename = "DNSOption", deserialize_with = "null_default")]
+    dns_option: Vec<String>,
+
+    /// --add-host
+    #[serde(deserialize_with = "null_default")]
+    host_add: Vec<String>,
+
+    /// --no-hosts
+    no_manage_hosts: bool,
+
+    /// --dns none
+    no_manage_resolv_conf: bool,
+
+    /// --pid
+    #[serde(rename = "pid_ns")]
+    pid_ns: String,
+
+    /// --userns
+    #[serde(rename = "userns")]
+    userns: String,
+}
+
+impl InfraConfig {
+    /// Values for `--network`, excluding the default `podman` network.
+    fn networks(&self) -> Vec<String> {
+        if self.host_network {
+            vec![String::from("host")]
+        } else if self.networks == ["podman"] {
+            Vec::new()
+        } else {
+            self.networks.clone()
+        }
+    }
+
+    /// Add the arguments for the infra container's config.
+    fn push_args(&self, args: &mut Args, unsupported: &mut Vec<String>) {
+        let Self {
+            port_bindings,
+            host_network: _,
+            networks: _,
+            network_options,
+            static_ip,
+            static_mac,
+            dns_server,
+            dns_search,
+            dns_option,
+            host_add,
+            no_manage_hosts,
+            no_manage_resolv_conf,
+            pid_ns,
+            userns,
+        } = self;
+
+        for port in publish_ports(port_bindings) {
+            args.push("--publish", port);
+        }
+        for network in self.networks() {
+            args.push("--network", network);
+        }
+        for network in network_options.keys() {
+            unsupported.push(format!("network options for `{network}`"));
+        }
+
+        if static_ip.contains(':') {
+            args.push("--ip6", static_ip);
+        } else if !static_ip.is_empty() {
+            args.push("--ip", static_ip);
+        }
+        if !static_mac.is_empty() {
+            args.push("--mac-address", static_mac);
+        }
+
+        for dns in dns_server {
+            args.push("--dns", dns);
+        }
+        if *no_manage_resolv_conf {
+            args.push("--dns", "none");
+        }
+        for search in dns_search {
+            args.push("--dns-search", search);
+        }
+        for option in dns_option {
+            args.push("--dns-option", option);
+        }
+
+        for host in host_add {
+            args.push("--add-host", host);
+        }
+        args.push_flag("--no-hosts", *no_manage_hosts);
+
+        if !pid_ns.is_empty() && pid_ns != "private" {
+            args.push("--pid", pid_ns);
+        }
+        if !userns.is_empty() && userns != "host" {
+            args.push("--userns", userns);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{super::PodParser, *};
+
+    #[test]
+    fn create_args() -> color_eyre::Result<()> {
+        let pod: PodInspect = serde_json::from_str(
+            r#"{
+                "Name": "web",
+                "CreateCommand": ["podman", "kube", "play", "web.yaml"],
+                "InfraContainerId": "0123",
+                "Containers": [
+                    {"Id": "0123", "Name": "0123-infra"},
+                    {"Id": "4567", "Name": "web-app"}
+                ],
+                "Labels": {"app": "web"},
+                "SharedNamespaces": ["uts", "net", "ipc"],
+                "ExitPolicy": "stop",
+                "InfraConfig": {
+                    "PortBindings": {"80/tcp": [{"HostIp": "", "HostPort": "8080"}]},
+                    "HostNetwork": false,
+                    "Networks": ["web"],
+                    "DNSServer": null,
+                    "HostAdd": ["db:10.0.0.2"],
+                    "pid_ns": "private",
+                    "userns": "keep-id"
+                }
+            }"#,
+        )?;
+
+        assert_eq!(pod.container_names().collect::<Vec<_>>(), ["web-app"]);
+
+        let (args, unsupported) = pod.create_args();
+        assert_eq!(
+            args,
+            [
+                "create",
+                "--name",
+                "web",
+                "--label",
+                "app=web",
+                "--exit-policy",
+                "stop",
+                "--publish",
+                "8080:80",
+                "--network",
+                "web",
+                "--add-host",
+                "db:10.0.0.2",
+                "--userns",
+                "keep-id",
+            ]
+        );
+        assert!(unsupported.is_empty());
+
+        PodParser::try_parse_from(&args)?;
+
+        Ok(())
+    }
+
+    /// Deserialize the pod inspect output and rebuild the pod's arguments, checking that they
+    /// parse.
+    fn inspect_args(pod: &str) -> color_eyre::Result<(Vec<String>, Vec<String>)> {
+        let pod: PodInspect = serde_json::from_str(pod)?;
+        let (args, unsupported) = pod.create_args();
+        PodParser::try_parse_from(&args)?;
+        Ok((args, unsupported))
+    }
+
+    #[test]
+    fn shared_namespaces() -> color_eyre::Result<()> {
+        for default in [
+            r#"["uts", "net", "ipc"]"#,
+            r#"["net", "cgroup", "ipc", "uts"]"#,
+        ] {
+            let (args, unsupported) = inspect_args(&format!(
+                r#"{{"Name": "web", "SharedNamespaces": {default}}}"#
+            ))?;
+            assert_eq!(args, ["create", "--name", "web"]);
+            assert!(unsupported.is_empty());
+        }
+
+        let (args, unsupported) =
+            inspect_args(r#"{"Name": "web", "SharedNamespaces": ["uts", "pid", "net", "ipc"]}"#)?;
+        assert_eq!(
+            args,
+            ["create", "--name", "web", "--share", "ipc,net,pid,uts"]
+        );
+        assert!(unsupported.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn port_bindings() -> color_eyre::Result<()> {
+        let (args, unsupported) = inspect_args(
+            r#"{
+                "Name": "web",
+                "InfraConfig": {"PortBindings": {
+                    "80/tcp": [
+                        {"HostIp": "", "HostPort": "8080"},
+                        {"HostIp": "::1", "HostPort": "8081"}
+                    ],
+                    "53/udp": [{"HostIp": "127.0.0.1", "HostPort": "53"}],
+                    "9000/tcp": [{"HostIp": "", "HostPort": ""}],
+                    "9001/tcp": null
+                }}
+            }"#,
+        )?;
+        assert_eq!(
+            args,
+            [
+                "create",
+                "--name",
+                "web",
+                "--publish",
+                "8080:80",
+                "--publish",
+                "[::1]:8081:80",
+                "--publish",
+                "127.0.0.1:53:53/udp",
+                "--publish",
+                "9000",
+            ]
+        );
+        assert!(unsupported.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn networks() -> color_eyre::Result<()> {
+        let (args, unsupported) = inspect_args(
+            r#"{"Name": "web", "InfraConfig": {"HostNetwork": true, "Networks": ["podman"]}}"#,
+        )?;
+        assert_eq!(args, ["create", "--name", "web", "--network", "host"]);
+        assert!(unsupported.is_empty());
+
+        // The default network is only dropped if it is the pod's only network.
+        let (args, unsupported) =
+            inspect_args(r#"{"Name": "web", "InfraConfig": {"Networks": ["podman"]}}"#)?;
+        assert_eq!(args, ["create", "--name", "web"]);
+        assert!(unsupported.is_empty());
+
+        let (args, unsupported) = inspect_args(
+            r#"{
+                "Name": "web",
+                "InfraConfig": {
+                    "Networks": ["podman", "app"],
+                    "NetworkOptions": {"app": ["isolate"]}
+                }
+            }"#,
+        )?;
+        assert_eq!(
+            args,
+            [
+                "create",
+                "--name",
+                "web",
+                "--network",
+                "podman",
+                "--network",
+                "app"
+            ]
+        );
+        assert_eq!(unsupported, ["network options for `app`"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn infra_container() -> color_eyre::Result<()> {
+        // Podman outputs `InfraContainerID` rather than the PascalCase `InfraContainerId`.
+        let pod: PodInspect = serde_json::from_str(
+            r#"{
+                "Name": "web",
+                "InfraContainerID": "0123",
+                "Containers": [
+                    {"Id": "0123", "Name": "0123-infra"},
+                    {"Id": "4567", "Name": "web-app"},
+                    {"Id": "89ab", "Name": "web-db"}
+                ]
+            }"#,
+        )?;
+        assert_eq!(
+            pod.container_names().collect::<Vec<_>>(),
+            ["web-app", "web-db"]
+        );
+
+        // Pods created without an infra container.
+        let pod: PodInspect = serde_json::from_str(
+            r#"{"Name": "web", "Containers": [{"Id": "4567", "Name": "web-app"}]}"#,
+        )?;
+        assert_eq!(pod.container_names().collect::<Vec<_>>(), ["web-app"]);
+
+        Ok(())
+    }
+}
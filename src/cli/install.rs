@@ -40,6 +40,34 @@ pub struct Install {
     /// Can be specified multiple times.
     #[arg(long, requires = "install")]
     upheld_by: Vec<String>,
+
+    /// Install the unit under additional names.
+    ///
+    /// Requires the --install option.
+    ///
+    /// Converts to "Alias=ALIAS".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, requires = "install")]
+    alias: Vec<String>,
+
+    /// Additional units to install or remove when this unit is installed or removed.
+    ///
+    /// Requires the --install option.
+    ///
+    /// Converts to "Also=ALSO".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, requires = "install")]
+    also: Vec<String>,
+
+    /// Instance to enable if the unit is a template and no instance is given.
+    ///
+    /// Requires the --install option.
+    ///
+    /// Converts to "DefaultInstance=DEFAULT_INSTANCE".
+    #[arg(long, requires = "install")]
+    default_instance: Option<String>,
 }
 
 impl From<Install> for crate::quadlet::Install {
@@ -49,6 +77,9 @@ impl From<Install> for crate::quadlet::Install {
             wanted_by,
             required_by,
             upheld_by,
+            alias,
+            also,
+            default_instance,
         }: Install,
     ) -> Self {
         Self {
@@ -63,6 +94,9 @@ impl From<Install> for crate::quadlet::Install {
             },
             required_by,
             upheld_by,
+            alias,
+            also,
+            default_instance,
         }
     }
 }
@@ -129,7 +129,8 @@ impl Service {
             platform,
             ports,
             privileged,
-            profiles,
+            // Services are filtered by their profiles in `crate::cli::compose::profile::apply()`.
+            profiles: _,
             pull_policy,
             read_only,
             restart,
@@ -202,7 +203,6 @@ impl Service {
                 oom_score_adj,
                 pid,
                 platform,
-                profiles,
                 restart,
                 runtime,
                 scale,
@@ -752,7 +752,6 @@ struct Unsupported {
     oom_score_adj: Option<OomScoreAdj>,
     pid: Option<String>,
     platform: Option<Platform>,
-    profiles: IndexSet<Identifier>,
     restart: Option<Restart>,
     runtime: Option<String>,
     scale: Option<u64>,
@@ -822,7 +821,6 @@ impl Unsupported {
             oom_score_adj,
             pid,
             platform,
-            profiles,
             restart,
             runtime,
             scale,
@@ -880,7 +878,6 @@ impl Unsupported {
             ("oom_kill_disable", !oom_kill_disable),
             ("oom_score_adj", oom_score_adj.is_none()),
             ("platform", platform.is_none()),
-            ("profiles", profiles.is_empty()),
             ("runtime", runtime.is_none()),
             ("scale", scale.is_none()),
             ("secrets", secrets.is_empty()),
@@ -0,0 +1,902 @@
+use std::{
+    collections::HashSet,
+    ffi::OsStr,
+    fmt::{self, Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+use color_eyre::eyre::{WrapErr, eyre};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    quadlet::{
+        self, Downgrade, DowngradeError, Globals, Install, PodmanVersion, Quadlet, Resource,
+        ResourceKind, Service, Unit,
+    },
+    serde::quadlet::{Error, Section, option_names},
+};
+
+use super::rewrite::{EXTENSION_SUGGESTION, name_and_kind};
+
+/// Quadlet options of a resource which cannot be used together, and the reason why.
+const CONFLICTS: &[(ResourceKind, &str, &str, &str)] = &[
+    (
+        ResourceKind::Container,
+        "Image",
+        "Rootfs",
+        "a container is created from either an image or a root filesystem",
+    ),
+    (
+        ResourceKind::Container,
+        "Pod",
+        "PublishPort",
+        "ports of containers in a pod must be published by the pod",
+    ),
+    (
+        ResourceKind::Container,
+        "Pod",
+        "Network",
+        "containers in a pod use the pod's network",
+    ),
+    (
+        ResourceKind::Container,
+        "UserNS",
+        "UIDMap",
+        "the user namespace is set by the mapping",
+    ),
+    (
+        ResourceKind::Container,
+        "UserNS",
+        "GIDMap",
+        "the user namespace is set by the mapping",
+    ),
+    (
+        ResourceKind::Container,
+        "UserNS",
+        "SubUIDMap",
+        "the user namespace is set by the mapping",
+    ),
+    (
+        ResourceKind::Container,
+        "UserNS",
+        "SubGIDMap",
+        "the user namespace is set by the mapping",
+    ),
+    (
+        ResourceKind::Pod,
+        "UserNS",
+        "UIDMap",
+        "the user namespace is set by the mapping",
+    ),
+    (
+        ResourceKind::Pod,
+        "UserNS",
+        "GIDMap",
+        "the user namespace is set by the mapping",
+    ),
+    (
+        ResourceKind::Pod,
+        "UserNS",
+        "SubUIDMap",
+        "the user namespace is set by the mapping",
+    ),
+    (
+        ResourceKind::Pod,
+        "UserNS",
+        "SubGIDMap",
+        "the user namespace is set by the mapping",
+    ),
+];
+
+/// Quadlet options which reference other Quadlet files and the extension of the referenced files.
+const REFERENCES: &[(&str, &str)] = &[
+    ("Network", "network"),
+    ("Volume", "volume"),
+    ("Mount", "volume"),
+    ("Pod", "pod"),
+];
+
+/// Quadlet files to check for problems.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct Lint {
+    /// The Quadlet files to check
+    ///
+    /// The type of each file is determined by its extension, e.g. `.container` or `.network`.
+    ///
+    /// References to `.network`, `.volume`, and `.pod` files must be to one of the given files or
+    /// to a file in the same directory as the referencing file.
+    #[arg(required = true, value_name = "FILE")]
+    files: Vec<PathBuf>,
+
+    /// Print the diagnostics as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+impl Lint {
+    /// Check the Quadlet files for problems and print a [`Diagnostic`] for each problem found.
+    ///
+    /// Options not supported by Podman `version` are reported as problems.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the diagnostics are errors, or if the diagnostics could not be
+    /// serialized.
+    pub fn run(&self, version: PodmanVersion) -> color_eyre::Result<()> {
+        let file_names: HashSet<&OsStr> = self
+            .files
+            .iter()
+            .filter_map(|path| path.file_name())
+            .collect();
+
+        let diagnostics: Vec<Diagnostic> = self
+            .files
+            .iter()
+            .flat_map(|path| lint_file(path, version, &file_names))
+            .collect();
+
+        if self.json {
+            let diagnostics = serde_json::to_string_pretty(&diagnostics)
+                .wrap_err("error serializing diagnostics")?;
+            println!("{diagnostics}");
+        } else {
+            for diagnostic in &diagnostics {
+                println!("{diagnostic}");
+            }
+        }
+
+        let errors = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .count();
+        if errors == 0 {
+            Ok(())
+        } else {
+            Err(eyre!("found {errors} error(s) in the Quadlet files"))
+        }
+    }
+}
+
+/// A problem found in a Quadlet file.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Path to the Quadlet file.
+    pub file: PathBuf,
+    /// Line of the file the problem is on, if the problem is specific to a line.
+    pub line: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+    /// How the problem may be fixed.
+    pub suggestion: Option<String>,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Self {
+            file,
+            line,
+            severity,
+            message,
+            suggestion,
+        } = self;
+
+        write!(f, "{}", file.display())?;
+        if let Some(line) = line {
+            write!(f, ":{line}")?;
+        }
+        write!(f, ": {severity}: {message}")?;
+        if let Some(suggestion) = suggestion {
+            write!(f, "\n  suggestion: {suggestion}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Severity of a [`Diagnostic`].
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Quadlet will fail to generate a working unit from the file.
+    Error,
+    /// The file may not work as intended.
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        })
+    }
+}
+
+/// Check the Quadlet file at `path` for problems.
+///
+/// References to other Quadlet files are checked against `file_names`.
+fn lint_file(path: &Path, version: PodmanVersion, file_names: &HashSet<&OsStr>) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        path,
+        diagnostics: Vec::new(),
+    };
+
+    if let Some((name, kind)) = name_and_kind(path) {
+        match fs::read_to_string(path) {
+            Ok(contents) => linter.lint(name, kind, &contents, version, file_names),
+            Err(error) => linter.push(
+                Severity::Error,
+                None,
+                format!("error reading file: {error}"),
+                None,
+            ),
+        }
+    } else {
+        linter.push(
+            Severity::Error,
+            None,
+            "not a Quadlet file",
+            Some(EXTENSION_SUGGESTION.to_owned()),
+        );
+    }
+
+    linter.diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+    linter.diagnostics
+}
+
+/// Collects the [`Diagnostic`]s of a Quadlet file.
+struct Linter<'a> {
+    path: &'a Path,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter<'_> {
+    /// Add a [`Diagnostic`] for the file.
+    fn push(
+        &mut self,
+        severity: Severity,
+        line: Option<usize>,
+        message: impl Into<String>,
+        suggestion: Option<String>,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            file: self.path.to_owned(),
+            line,
+            severity,
+            message: message.into(),
+            suggestion,
+        });
+    }
+
+    /// Add a [`Diagnostic`] for an `error` returned while parsing or deserializing the file.
+    ///
+    /// `options` are the names of the options of the section the error occurred in.
+    fn push_error(&mut self, error: Error, options: &[&str]) {
+        match error {
+            Error::Syntax { line, message } => {
+                self.push(Severity::Error, Some(line), message, None);
+            }
+            Error::Section {
+                line,
+                section,
+                message,
+            } => self.push(
+                Severity::Error,
+                Some(line),
+                format!("invalid [{section}] section: {message}"),
+                None,
+            ),
+            Error::UnknownSection { line, section } => self.push(
+                Severity::Error,
+                Some(line),
+                format!("unknown section [{section}]"),
+                Some(String::from(
+                    "Prefix the section name with `X-` if it is intentionally not used by \
+                        Quadlet or systemd.",
+                )),
+            ),
+            Error::UnknownOption { line, section, key } => {
+                let suggestion = similar_option(&key, options)
+                    .map(|option| format!("Did you mean `{option}=`?"));
+                self.push(
+                    Severity::Error,
+                    Some(line),
+                    format!("unknown option `{key}=` in the [{section}] section"),
+                    suggestion,
+                );
+            }
+            Error::Value { line, key, message } => self.push(
+                Severity::Error,
+                Some(line),
+                format!("invalid value for `{key}=`: {message}"),
+                None,
+            ),
+            error @ (Error::Custom(_) | Error::InvalidType | Error::MissingResource) => {
+                self.push(Severity::Error, None, error.to_string(), None);
+            }
+        }
+    }
+
+    /// Check the `contents` of the file for problems, adding [`Diagnostic`]s for each problem
+    /// found.
+    ///
+    /// `name` and `kind` are the file name and resource kind from the file's path.
+    fn lint(
+        &mut self,
+        name: &str,
+        kind: ResourceKind,
+        contents: &str,
+        version: PodmanVersion,
+        file_names: &HashSet<&OsStr>,
+    ) {
+        let sections = match crate::serde::quadlet::from_str(contents) {
+            Ok(sections) => sections,
+            Err(error) => {
+                self.push_error(error, &[]);
+                return;
+            }
+        };
+
+        let mut unit = Unit::default();
+        let mut quadlet = Quadlet::default();
+        let mut service = Service::default();
+        let mut install = Install::default();
+
+        for section in &sections {
+            match section.name.as_str() {
+                // systemd sections support many more options than Podlet knows about, so only the
+                // values of known options are checked.
+                "Unit" => {
+                    if let Some((value, _)) = self.deserialize_systemd_section(section) {
+                        unit = value;
+                    }
+                }
+                "Service" => {
+                    if let Some((value, _)) = self.deserialize_systemd_section(section) {
+                        service = value;
+                    }
+                }
+                "Install" => {
+                    if let Some((value, _)) = self.deserialize_systemd_section(section) {
+                        install = value;
+                    }
+                }
+                "Quadlet" => {
+                    if let Some((value, _)) =
+                        self.deserialize(section, option_names::<Quadlet>(), Section::deserialize)
+                    {
+                        quadlet = value;
+                    }
+                }
+                section_name
+                    if section_name.starts_with("X-")
+                        || ResourceKind::from_section_name(section_name).is_some() => {}
+                section_name => self.push_error(
+                    Error::UnknownSection {
+                        line: section.line,
+                        section: section_name.to_owned(),
+                    },
+                    &[],
+                ),
+            }
+        }
+
+        let Some((section, resource, globals)) = self.resource_section(&sections, name, kind)
+        else {
+            return;
+        };
+
+        let resource_kind = ResourceKind::from(&resource);
+        self.tmpfs(&section);
+        self.conflicts(resource_kind, &section);
+        self.references(&section, file_names);
+
+        if version < PodmanVersion::LATEST {
+            let file = quadlet::File {
+                name: name.to_owned(),
+                unit,
+                resource,
+                globals,
+                quadlet,
+                service,
+                install,
+                options: Vec::new(),
+            };
+            self.unsupported(&file, version, &sections);
+        }
+    }
+
+    /// Find and deserialize the resource section of the file, e.g. `[Container]`.
+    ///
+    /// `name` and `kind` are the file name and resource kind from the file's path.
+    fn resource_section(
+        &mut self,
+        sections: &[Section],
+        name: &str,
+        kind: ResourceKind,
+    ) -> Option<(Section, Resource, Globals)> {
+        let mut resource_sections = sections.iter().filter_map(|section| {
+            ResourceKind::from_section_name(&section.name).map(|kind| (section, kind))
+        });
+
+        let Some((section, section_kind)) = resource_sections.next() else {
+            self.push(
+                Severity::Error,
+                None,
+                format!("missing the [{}] section", kind.section_name()),
+                Some(format!(
+                    "Add a [{}] section to the file.",
+                    kind.section_name()
+                )),
+            );
+            return None;
+        };
+
+        for (extra, _) in resource_sections {
+            self.push(
+                Severity::Error,
+                Some(extra.line),
+                format!(
+                    "only one resource section is allowed, found [{}] after [{}]",
+                    extra.name, section.name
+                ),
+                Some(format!(
+                    "Move the [{}] section to its own file.",
+                    extra.name
+                )),
+            );
+        }
+
+        if section_kind != kind {
+            self.push(
+                Severity::Error,
+                Some(section.line),
+                format!(
+                    "[{}] section found in a `.{kind}` file, expected [{}]",
+                    section.name,
+                    kind.section_name()
+                ),
+                Some(format!("Rename the file to `{name}.{section_kind}`.")),
+            );
+        }
+
+        let mut options = section_kind.option_names();
+        options.extend_from_slice(option_names::<Globals>());
+        self.deserialize(section, &options, |section| {
+            Resource::deserialize_section(section_kind, section)
+        })
+        .map(|((resource, globals), section)| (section, resource, globals))
+    }
+
+    /// Deserialize a systemd section, e.g. `[Unit]`, ignoring unknown options.
+    fn deserialize_systemd_section<T: DeserializeOwned>(
+        &mut self,
+        section: &Section,
+    ) -> Option<(T, Section)> {
+        self.deserialize(section, option_names::<T>(), |section| {
+            section.deserialize_partial().map(|(value, _unknown)| value)
+        })
+    }
+
+    /// Deserialize the `section` using `deserialize`.
+    ///
+    /// Options which are unknown or have an invalid value are reported and removed from the
+    /// section until deserialization succeeds. The deserialized value is returned with the
+    /// remaining options.
+    fn deserialize<T>(
+        &mut self,
+        section: &Section,
+        options: &[&str],
+        deserialize: impl Fn(&Section) -> Result<T, Error>,
+    ) -> Option<(T, Section)> {
+        let mut section = section.clone();
+        loop {
+            let error = match deserialize(&section) {
+                Ok(value) => return Some((value, section)),
+                Err(error) => error,
+            };
+
+            let key = match &error {
+                Error::UnknownOption { key, .. } | Error::Value { key, .. } => Some(key.clone()),
+                _ => None,
+            };
+            self.push_error(error, options);
+
+            let len = section.entries.len();
+            section
+                .entries
+                .retain(|entry| key.as_ref().is_none_or(|key| entry.key != *key));
+            if section.entries.len() == len {
+                return None;
+            }
+        }
+    }
+
+    /// Check that the values of `Tmpfs=` start with an absolute container path.
+    fn tmpfs(&mut self, section: &Section) {
+        for entry in &section.entries {
+            if entry.key == "Tmpfs" && !entry.value.is_empty() && !entry.value.starts_with('/') {
+                self.push(
+                    Severity::Error,
+                    Some(entry.line),
+                    format!(
+                        "invalid value for `Tmpfs=`: `{}` must start with an absolute container \
+                            path",
+                        entry.value
+                    ),
+                    Some(String::from("Use the form `CONTAINER-DIR[:OPTIONS]`.")),
+                );
+            }
+        }
+    }
+
+    /// Check for [`CONFLICTS`] between the options of the resource `section`.
+    fn conflicts(&mut self, kind: ResourceKind, section: &Section) {
+        for (_, first, second, reason) in CONFLICTS
+            .iter()
+            .filter(|(conflict_kind, ..)| *conflict_kind == kind)
+        {
+            if let (Some(_), Some(line)) =
+                (option_line(section, first), option_line(section, second))
+            {
+                self.push(
+                    Severity::Error,
+                    Some(line),
+                    format!("`{second}=` conflicts with `{first}=`: {reason}"),
+                    Some(format!("Remove either `{first}=` or `{second}=`.")),
+                );
+            }
+        }
+
+        let required: &[&str] = match kind {
+            ResourceKind::Container => &["Image", "Rootfs"],
+            ResourceKind::Kube => &["Yaml"],
+            ResourceKind::Build => &["ImageTag"],
+            ResourceKind::Image => &["Image"],
+            ResourceKind::Artifact => &["Artifact"],
+            ResourceKind::Pod | ResourceKind::Network | ResourceKind::Volume => &[],
+        };
+        if !required.is_empty()
+            && required
+                .iter()
+                .all(|option| option_line(section, option).is_none())
+        {
+            let options = required
+                .iter()
+                .map(|option| format!("`{option}=`"))
+                .collect::<Vec<_>>()
+                .join(" or ");
+            self.push(
+                Severity::Error,
+                Some(section.line),
+                format!("the [{}] section requires {options}", section.name),
+                None,
+            );
+        }
+    }
+
+    /// Check that the options in [`REFERENCES`] only reference Quadlet files which are linted or
+    /// in the same directory as the file.
+    fn references(&mut self, section: &Section, file_names: &HashSet<&OsStr>) {
+        for entry in &section.entries {
+            let Some((_, extension)) = REFERENCES.iter().find(|(key, _)| entry.key == *key) else {
+                continue;
+            };
+
+            for value in entry.value.split_whitespace() {
+                let reference = if entry.key == "Mount" {
+                    value.split(',').find_map(|option| {
+                        option
+                            .strip_prefix("source=")
+                            .or_else(|| option.strip_prefix("src="))
+                    })
+                } else {
+                    value.split(':').next()
+                };
+                let Some(reference) = reference.filter(|reference| {
+                    Path::new(reference)
+                        .extension()
+                        .is_some_and(|reference_extension| reference_extension == *extension)
+                }) else {
+                    continue;
+                };
+
+                if !file_names.contains(OsStr::new(reference))
+                    && !self.path.with_file_name(reference).exists()
+                {
+                    self.push(
+                        Severity::Warning,
+                        Some(entry.line),
+                        format!(
+                            "`{}=` references `{reference}` which does not exist",
+                            entry.key
+                        ),
+                        Some(format!(
+                            "Create `{reference}` or lint it together with this file."
+                        )),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Check for options which are not supported by Podman `version`, reporting each one.
+    ///
+    /// `sections` are used to find the line of each option.
+    fn unsupported(&mut self, file: &quadlet::File, version: PodmanVersion, sections: &[Section]) {
+        let line = |key: &str| {
+            sections
+                .iter()
+                .flat_map(|section| &section.entries)
+                .find(|entry| entry.key == key)
+                .map(|entry| entry.line)
+        };
+        let resource_line = sections
+            .iter()
+            .find(|section| ResourceKind::from_section_name(&section.name).is_some())
+            .map(|section| section.line);
+
+        let Ok(mut file_sections) = file
+            .serialize_to_quadlet(&HashSet::new())
+            .and_then(|file| crate::serde::quadlet::from_str(&file))
+        else {
+            return;
+        };
+        let mut unsupported = Vec::new();
+        let mut remaining = file.clone();
+        let downgraded = loop {
+            let mut downgraded = remaining.clone();
+            let error = match downgraded.downgrade(version) {
+                Ok(()) => break downgraded,
+                Err(error) => error,
+            };
+
+            let (line, suggestion, quadlet_option) = match &error {
+                DowngradeError::Option {
+                    quadlet_option,
+                    supported_version,
+                    ..
+                }
+                | DowngradeError::Multiple {
+                    quadlet_option,
+                    supported_version,
+                } => (
+                    line(quadlet_option),
+                    format!(
+                        "Remove `{quadlet_option}=` or use Podman v{supported_version} or later."
+                    ),
+                    Some(*quadlet_option),
+                ),
+                DowngradeError::Kind {
+                    supported_version, ..
+                } => (
+                    resource_line,
+                    format!("Use Podman v{supported_version} or later."),
+                    None,
+                ),
+            };
+            self.push(Severity::Error, line, error.to_string(), Some(suggestion));
+
+            // Remove the unsupported option so the rest of the options are checked as well.
+            let Some(quadlet_option) = quadlet_option else {
+                return;
+            };
+            let mut removed = false;
+            for section in &mut file_sections {
+                let len = section.entries.len();
+                section.entries.retain(|entry| entry.key != quadlet_option);
+                removed |= section.entries.len() != len;
+            }
+            if !removed {
+                return;
+            }
+            unsupported.push(quadlet_option);
+            match quadlet::File::deserialize_from_sections(file.name.clone(), &file_sections) {
+                Ok(file) => remaining = file,
+                Err(_) => return,
+            }
+        };
+
+        let (Ok(options), Ok(downgraded)) = (file.option_values(), downgraded.option_values())
+        else {
+            return;
+        };
+        for (option, values) in options {
+            let (_, key) = &option;
+            if key == "PodmanArgs" || key == "GlobalArgs" || unsupported.contains(&key.as_str()) {
+                continue;
+            }
+            if downgraded
+                .get(&option)
+                .is_none_or(|downgraded| *downgraded != values)
+            {
+                self.push(
+                    Severity::Error,
+                    line(key),
+                    format!("`{key}=` is not supported by Podman v{version}"),
+                    Some(format!(
+                        "Move the option to `PodmanArgs=` with \
+                            `podlet --podman-version {version} downgrade {}`.",
+                        self.path.display()
+                    )),
+                );
+            }
+        }
+    }
+}
+
+/// Returns the line of the last entry for the `key` option in the `section`, if it is set.
+fn option_line(section: &Section, key: &str) -> Option<usize> {
+    section
+        .entries
+        .iter()
+        .rfind(|entry| entry.key == key)
+        .filter(|entry| !entry.value.is_empty())
+        .map(|entry| entry.line)
+}
+
+/// Returns the option in `options` most similar to `key`, if any are similar enough.
+fn similar_option<'a>(key: &str, options: &[&'a str]) -> Option<&'a str> {
+    options
+        .iter()
+        .map(|option| {
+            let distance = if option.eq_ignore_ascii_case(key) {
+                0
+            } else {
+                edit_distance(key, option)
+            };
+            (distance, *option)
+        })
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, option)| option)
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = i;
+        let mut left = i + 1;
+        let mut next_row = Vec::with_capacity(row.len());
+        next_row.push(left);
+        for (b, above) in b.iter().zip(row.iter().skip(1)) {
+            let distance = (diagonal + usize::from(a != *b))
+                .min(above + 1)
+                .min(left + 1);
+            next_row.push(distance);
+            diagonal = *above;
+            left = distance;
+        }
+        row = next_row;
+    }
+
+    row.last().copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lint the `contents` of a `test.container` file.
+    fn lint(contents: &str, version: PodmanVersion) -> Vec<Diagnostic> {
+        let mut linter = Linter {
+            path: Path::new("test.container"),
+            diagnostics: Vec::new(),
+        };
+        linter.lint(
+            "test",
+            ResourceKind::Container,
+            contents,
+            version,
+            &HashSet::new(),
+        );
+        linter.diagnostics
+    }
+
+    #[test]
+    fn unknown_and_invalid_options() {
+        let diagnostics = lint(
+            "[Container]\n\
+            Imag=image\n\
+            Image=image\n\
+            StopTimeout=abc\n\
+            Tmpfs=tmp\n",
+            PodmanVersion::LATEST,
+        );
+        let lines: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.suggestion.as_deref()))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (Some(4), None),
+                (Some(2), Some("Did you mean `Image=`?")),
+                (Some(5), Some("Use the form `CONTAINER-DIR[:OPTIONS]`.")),
+            ]
+        );
+    }
+
+    #[test]
+    fn conflicts_and_references() {
+        let diagnostics = lint(
+            "[Container]\n\
+            Image=image\n\
+            Pod=missing.pod\n\
+            PublishPort=8080:80\n",
+            PodmanVersion::LATEST,
+        );
+        let severities: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.severity))
+            .collect();
+        assert_eq!(
+            severities,
+            [(Some(4), Severity::Error), (Some(3), Severity::Warning)]
+        );
+    }
+
+    #[test]
+    fn unsupported_options() {
+        let contents = "[Container]\nImage=image\nStopTimeout=20\n";
+        assert!(lint(contents, PodmanVersion::LATEST).is_empty());
+
+        let diagnostics = lint(contents, PodmanVersion::V4_8);
+        let messages: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            [(Some(3), "`StopTimeout=` is not supported by Podman v4.8")]
+        );
+
+        let contents = "[Container]\nImage=image\nHealthLogDestination=/tmp\n\
+            HealthMaxLogCount=10\nStopTimeout=20\n";
+        let diagnostics = lint(contents, PodmanVersion::V4_8);
+        let messages: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (
+                    Some(3),
+                    "Quadlet option `HealthLogDestination=/tmp` was not supported until Podman v5.3"
+                ),
+                (
+                    Some(4),
+                    "Quadlet option `HealthMaxLogCount=10` was not supported until Podman v5.3"
+                ),
+                (Some(5), "`StopTimeout=` is not supported by Podman v4.8"),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_resource() {
+        let diagnostics = lint("[Unit]\nDescription=test\n", PodmanVersion::LATEST);
+        let messages: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect();
+        assert_eq!(messages, ["missing the [Container] section"]);
+    }
+
+    #[test]
+    fn similar_options() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(
+            similar_option("stoptimeout", &["StopTimeout", "Timezone"]),
+            Some("StopTimeout")
+        );
+        assert_eq!(similar_option("Something", &["Image"]), None);
+    }
+}
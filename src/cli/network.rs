@@ -175,6 +175,7 @@ impl From<Create> for crate::quadlet::Network {
             ip_range,
             ipv6,
             label,
+            network_name: None,
             options: opt,
             podman_args: (!podman_args.is_empty()).then_some(podman_args),
             subnet,
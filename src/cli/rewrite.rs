@@ -0,0 +1,329 @@
+use std::{
+    collections::HashSet,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{Args, Parser};
+use color_eyre::{
+    Help,
+    eyre::{OptionExt, WrapErr, ensure, eyre},
+};
+
+use crate::{
+    quadlet::{self, Downgrade, GenericSections, PodmanVersion, Resource, ResourceKind, Service},
+    serde::quadlet::{Entry, Section},
+};
+
+use super::PodmanCommands;
+
+/// Existing Quadlet files to rewrite for a Podman version.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct Rewrite {
+    /// The Quadlet files to rewrite
+    ///
+    /// The type of each file is determined by its extension, e.g. `.container` or `.network`.
+    /// The name of the file, without the extension, is used for the rewritten file.
+    #[arg(required = true, value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+}
+
+impl Rewrite {
+    /// Read and deserialize the Quadlet files.
+    ///
+    /// If given, `name` is used as the name of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the `sections` are set, `name` is given for more than one file,
+    /// or if a file could not be read.
+    pub fn try_into_files(
+        self,
+        name: Option<String>,
+        GenericSections {
+            unit,
+            quadlet,
+            install,
+        }: GenericSections,
+    ) -> color_eyre::Result<Vec<quadlet::File>> {
+        ensure!(
+            unit.is_empty() && quadlet.is_empty() && install.is_empty(),
+            "options for the [Unit], [Quadlet], and [Install] sections cannot be used when \
+                rewriting existing Quadlet files"
+        );
+
+        let mut files = self
+            .files
+            .iter()
+            .map(|path| read_quadlet_file(path))
+            .collect::<color_eyre::Result<Vec<_>>>()?;
+
+        if let Some(name) = name {
+            let [file] = files.as_mut_slice() else {
+                return Err(eyre!(
+                    "cannot set `--name` when rewriting more than one file"
+                ));
+            };
+            file.name = name;
+        }
+
+        Ok(files)
+    }
+}
+
+/// Read and deserialize a Quadlet file from `path`.
+///
+/// The file name and resource kind are taken from the file stem and extension.
+///
+/// # Errors
+///
+/// Returns an error if the path does not have a Quadlet file extension, the file could not be read,
+/// the file could not be deserialized, or if the resource section does not match the extension.
+pub fn read_quadlet_file(path: &Path) -> color_eyre::Result<quadlet::File> {
+    let (name, kind) = name_and_kind(path)
+        .ok_or_else(|| eyre!("`{}` is not a Quadlet file", path.display()))
+        .suggestion(EXTENSION_SUGGESTION)?;
+
+    let contents = fs::read_to_string(path)
+        .wrap_err_with(|| format!("error reading file `{}`", path.display()))?;
+
+    let file = quadlet::File::deserialize_from_quadlet(name.to_owned(), &contents)
+        .wrap_err_with(|| format!("error deserializing Quadlet file `{}`", path.display()))?;
+
+    let found = ResourceKind::from(&file.resource);
+    ensure!(
+        found == kind,
+        "Quadlet file `{}` is a {found} unit but has the `.{kind}` extension",
+        path.display()
+    );
+
+    Ok(file)
+}
+
+/// Suggestion for when a file does not have a Quadlet file extension.
+pub const EXTENSION_SUGGESTION: &str =
+    "Quadlet files must have an extension like `.container` or `.network`.";
+
+/// Returns the file name, without the extension, and the resource kind of the Quadlet file at
+/// `path`.
+///
+/// Returns [`None`] if the file name is not valid UTF-8 or the extension is not a Quadlet file
+/// extension.
+pub fn name_and_kind(path: &Path) -> Option<(&str, ResourceKind)> {
+    path.file_stem().and_then(OsStr::to_str).zip(
+        path.extension()
+            .and_then(OsStr::to_str)
+            .and_then(ResourceKind::from_extension),
+    )
+}
+
+/// Move flags in the `PodmanArgs=` Quadlet option of the `file`'s resource to their equivalent
+/// Quadlet options.
+///
+/// The file is left unchanged, and a warning is printed, if the flags could not be parsed or the
+/// upgraded file cannot be downgraded to the given Podman `version`.
+pub fn upgrade(file: &mut quadlet::File, version: PodmanVersion) {
+    match try_upgrade(file, version) {
+        Ok(Some(upgraded)) => *file = upgraded,
+        Ok(None) => {}
+        Err(error) => eprintln!(
+            "Warning: `PodmanArgs=` of `{}.{}` was not upgraded: {error:#}",
+            file.name,
+            file.resource.extension()
+        ),
+    }
+}
+
+/// Upgrade the `file`'s `PodmanArgs=` to Quadlet options.
+///
+/// The flags are parsed as arguments to the Podman command corresponding to the resource, e.g.
+/// `podman run` for containers. Only options which are set by the flags are added to the resource.
+///
+/// Returns [`None`] if there was nothing to upgrade.
+///
+/// # Errors
+///
+/// Returns an error if the flags could not be parsed or the upgraded file cannot be downgraded to
+/// the given Podman `version`.
+fn try_upgrade(
+    file: &quadlet::File,
+    version: PodmanVersion,
+) -> color_eyre::Result<Option<quadlet::File>> {
+    let Some(podman_args) = file.resource.podman_args() else {
+        return Ok(None);
+    };
+    let podman_args = shlex::split(podman_args).ok_or_eyre("invalid quoting")?;
+
+    let kind = ResourceKind::from(&file.resource);
+    let (parsed, service) = parse_podman_command(kind, &podman_args)?;
+    let (baseline, _) = parse_podman_command(kind, &[])?;
+
+    // Options set by the flags are all options which are not set without them.
+    let mut options = entries(&parsed)?;
+    for entry in entries(&baseline)? {
+        let index = options
+            .iter()
+            .position(|option| option.key == entry.key && option.value == entry.value)
+            .ok_or_else(|| eyre!("`{}=` cannot be set by `PodmanArgs=`", entry.key))?;
+        options.remove(index);
+    }
+
+    if options.iter().all(|option| option.key == "PodmanArgs") && service.restart.is_none() {
+        return Ok(None);
+    }
+
+    let mut section = Section {
+        name: String::new(),
+        line: 0,
+        entries: entries(&file.resource)?,
+    };
+    section.entries.retain(|entry| entry.key != "PodmanArgs");
+    // Options are added after the existing ones, as flags in `PodmanArgs=` take precedence.
+    section.entries.extend(options);
+
+    let (resource, _) = Resource::deserialize_section(kind, &section)?;
+    let mut upgraded = quadlet::File {
+        resource,
+        ..file.clone()
+    };
+    if upgraded.service.restart.is_none() {
+        upgraded.service.restart = service.restart;
+    }
+
+    upgraded
+        .clone()
+        .downgrade(version)
+        .wrap_err_with(|| format!("upgraded file is not supported by Podman v{version}"))?;
+
+    Ok(Some(upgraded))
+}
+
+/// Parser for the arguments of a Podman command.
+#[derive(Parser, Debug)]
+#[command(no_binary_name = true)]
+struct PodmanCommand {
+    #[command(subcommand)]
+    command: PodmanCommands,
+}
+
+/// Parse `args` as the flags of the Podman command which corresponds to the resource `kind`.
+///
+/// Placeholders are used for required arguments.
+///
+/// # Errors
+///
+/// Returns an error if `args` are not valid flags for the command.
+fn parse_podman_command(
+    kind: ResourceKind,
+    args: &[String],
+) -> color_eyre::Result<(Resource, Service)> {
+    let (command, positional): (&[&str], &[&str]) = match kind {
+        ResourceKind::Container => (&["run"], &["image"]),
+        ResourceKind::Pod => (&["pod", "create"], &["pod"]),
+        ResourceKind::Kube => (&["kube", "play"], &["pod.yaml"]),
+        ResourceKind::Network => (&["network", "create"], &["network"]),
+        ResourceKind::Volume => (&["volume", "create"], &["volume"]),
+        ResourceKind::Build => (&["build", "--tag", "image"], &["."]),
+        ResourceKind::Image => (&["image", "pull"], &["image"]),
+        ResourceKind::Artifact => (&["artifact", "pull"], &["artifact"]),
+    };
+
+    let args = command
+        .iter()
+        .copied()
+        .chain(args.iter().map(String::as_str))
+        .chain(["--"])
+        .chain(positional.iter().copied());
+
+    let PodmanCommand { command } = PodmanCommand::try_parse_from(args).map_err(|error| {
+        let error = error.to_string();
+        let message = error.lines().next().unwrap_or_default();
+        eyre!("{}", message.trim_start_matches("error: "))
+    })?;
+
+    let service = command.service().unwrap_or_default();
+    Ok((command.into(), service))
+}
+
+/// Serialize the `resource` and return its Quadlet options.
+pub fn entries(resource: &Resource) -> Result<Vec<Entry>, crate::serde::quadlet::Error> {
+    let resource = crate::serde::quadlet::to_string(resource, &HashSet::new())?;
+    Ok(crate::serde::quadlet::from_str(&resource)?
+        .into_iter()
+        .flat_map(|section| section.entries)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use compose_spec::service::Limit;
+
+    use crate::quadlet::Container;
+
+    use super::*;
+
+    #[test]
+    fn upgrade_podman_args() -> color_eyre::Result<()> {
+        let mut file = quadlet::File::new(
+            "test",
+            Container {
+                image: String::from("image"),
+                stop_timeout: Some(10),
+                podman_args: Some(String::from(
+                    "--stop-timeout 20 --restart always --env 'ONE=one two' --pids-limit=5",
+                )),
+                ..Container::default()
+            },
+        );
+        upgrade(&mut file, PodmanVersion::LATEST);
+
+        let Resource::Container(container) = &file.resource else {
+            return Err(eyre!("resource is not a container"));
+        };
+        assert_eq!(container.stop_timeout, Some(20));
+        assert_eq!(container.environment, ["ONE=one two"]);
+        assert_eq!(container.pids_limit, Some(Limit::Value(5)));
+        assert_eq!(container.podman_args, None);
+        assert!(file.service.restart.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn upgrade_then_downgrade() -> color_eyre::Result<()> {
+        let mut file = quadlet::File::new(
+            "test",
+            Container {
+                image: String::from("image"),
+                podman_args: Some(String::from("--stop-timeout 20")),
+                ..Container::default()
+            },
+        );
+        upgrade(&mut file, PodmanVersion::V4_8);
+        file.downgrade(PodmanVersion::V4_8)?;
+
+        let Resource::Container(container) = &file.resource else {
+            return Err(eyre!("resource is not a container"));
+        };
+        assert_eq!(container.stop_timeout, None);
+        assert_eq!(container.podman_args.as_deref(), Some("--stop-timeout 20"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn upgrade_unknown_flag() {
+        let original = quadlet::File::new(
+            "test",
+            Container {
+                image: String::from("image"),
+                podman_args: Some(String::from("--stop-timeout 20 --unknown")),
+                ..Container::default()
+            },
+        );
+        let mut file = original.clone();
+        upgrade(&mut file, PodmanVersion::LATEST);
+        assert_eq!(file, original);
+    }
+}
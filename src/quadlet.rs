@@ -9,6 +9,7 @@ mod network;
 mod pod;
 mod service;
 mod unit;
+mod unit_option;
 mod volume;
 
 use std::{
@@ -21,7 +22,10 @@ use std::{
 
 use clap::ValueEnum;
 use compose_spec::service::build::Context;
-use serde::{Serialize, Serializer, ser::SerializeSeq};
+use indexmap::{IndexMap, IndexSet};
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer, de::DeserializeOwned, ser::SerializeSeq,
+};
 use smart_default::SmartDefault;
 use thiserror::Error;
 
@@ -35,16 +39,21 @@ pub use self::{
     kube::Kube,
     network::{IpRange, Network},
     pod::{ExitPolicy, Pod},
-    service::Service,
+    service::{RestartConfig, Service, ServiceType},
     unit::Unit,
+    unit_option::UnitOption,
     volume::Volume,
 };
-use crate::serde::skip_true;
+use crate::serde::{
+    deserialize_from_str,
+    quadlet::{Entry, Section, option_names, reset_option_names},
+    skip_true,
+};
 
 /// Generic Quadlet sections able to be used by all Quadlet types.
 ///
 /// Commonly grouped together when creating Quadlet [`File`]s.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct GenericSections {
     /// The `[Unit]` section.
     pub unit: Unit,
@@ -63,6 +72,8 @@ pub struct File {
     pub quadlet: Quadlet,
     pub service: Service,
     pub install: Install,
+    /// Options added to the file's sections in addition to those set above.
+    pub options: Vec<UnitOption>,
 }
 
 impl File {
@@ -85,6 +96,171 @@ impl File {
         crate::serde::quadlet::to_string(self, join_options)
     }
 
+    /// Serialize this Quadlet [`File`] and return the values of each option, keyed by section and
+    /// option name.
+    ///
+    /// No options are joined together, so each value is separate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the contained values return an error while serializing.
+    pub fn option_values(
+        &self,
+    ) -> Result<IndexMap<(String, String), Vec<String>>, crate::serde::quadlet::Error> {
+        let file = self.serialize_to_quadlet(&HashSet::new())?;
+        let mut options: IndexMap<_, Vec<_>> = IndexMap::new();
+        for section in crate::serde::quadlet::from_str(&file)? {
+            for entry in section.entries {
+                options
+                    .entry((section.name.clone(), entry.key))
+                    .or_default()
+                    .push(entry.value);
+            }
+        }
+        Ok(options)
+    }
+
+    /// Deserialize a Quadlet [`File`] from the Quadlet file format.
+    ///
+    /// `name` is the name of the file without the extension.
+    ///
+    /// Options which podlet does not support, e.g. `KillMode=` in the `[Service]` section, and the
+    /// options of `[X-...]` sections are kept in [`File::options`] so they are not lost when the
+    /// file is serialized again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `input` has invalid syntax, has an unknown section, has an invalid
+    /// value, or does not have exactly one resource section, e.g. `[Container]`.
+    pub fn deserialize_from_quadlet(
+        name: String,
+        input: &str,
+    ) -> Result<Self, crate::serde::quadlet::Error> {
+        Self::deserialize_from_sections(name, &crate::serde::quadlet::from_str(input)?)
+    }
+
+    /// Deserialize a Quadlet [`File`] from already parsed `sections`.
+    ///
+    /// See [`File::deserialize_from_quadlet()`] for how unsupported options are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a section is unknown, has an invalid value, or if there is not exactly
+    /// one resource section.
+    pub fn deserialize_from_sections(
+        name: String,
+        sections: &[Section],
+    ) -> Result<Self, crate::serde::quadlet::Error> {
+        /// Add the `unknown` entries of the `section` to `options`.
+        fn extend(options: &mut Vec<UnitOption>, section: &Section, unknown: Vec<&Entry>) {
+            options.extend(unknown.into_iter().map(|entry| UnitOption {
+                section: section.name.clone(),
+                key: entry.key.clone(),
+                value: entry.value.clone(),
+            }));
+        }
+
+        let mut unit = Unit::default();
+        let mut resource: Option<(&Section, Resource, Globals)> = None;
+        let mut quadlet = Quadlet::default();
+        let mut service = Service::default();
+        let mut install = Install::default();
+        let mut options = Vec::new();
+
+        for section in sections {
+            match section.name.as_str() {
+                "Unit" => {
+                    let unknown;
+                    (unit, unknown) = section.deserialize_partial()?;
+                    extend(&mut options, section, unknown);
+                }
+                "Quadlet" => {
+                    let unknown;
+                    (quadlet, unknown) = section.deserialize_partial()?;
+                    extend(&mut options, section, unknown);
+                }
+                "Service" => {
+                    let unknown;
+                    (service, unknown) = section.deserialize_partial()?;
+                    extend(&mut options, section, unknown);
+                }
+                "Install" => {
+                    let unknown;
+                    (install, unknown) = section.deserialize_partial()?;
+                    extend(&mut options, section, unknown);
+                }
+                // Extension sections are ignored by systemd and Quadlet.
+                name if name.starts_with("X-") => {
+                    extend(&mut options, section, section.entries.iter().collect());
+                }
+                name => {
+                    let kind = ResourceKind::from_section_name(name).ok_or_else(|| {
+                        crate::serde::quadlet::Error::UnknownSection {
+                            line: section.line,
+                            section: name.to_owned(),
+                        }
+                    })?;
+                    if let Some((first, ..)) = resource {
+                        return Err(crate::serde::quadlet::Error::Syntax {
+                            line: section.line,
+                            message: format!(
+                                "only one resource section is allowed, found [{name}] after [{}]",
+                                first.name
+                            ),
+                        });
+                    }
+                    let (value, globals, unknown) =
+                        Resource::deserialize_section_partial(kind, section)?;
+                    extend(&mut options, section, unknown);
+                    resource = Some((section, value, globals));
+                }
+            }
+        }
+
+        let (_, resource, globals) =
+            resource.ok_or(crate::serde::quadlet::Error::MissingResource)?;
+
+        Ok(Self {
+            name,
+            unit,
+            resource,
+            globals,
+            quadlet,
+            service,
+            install,
+            options,
+        })
+    }
+
+    /// Create a [`File`] named `name` with the `resource` and otherwise empty sections.
+    #[cfg(test)]
+    pub fn new(name: &str, resource: impl Into<Resource>) -> Self {
+        Self {
+            name: name.to_owned(),
+            unit: Unit::default(),
+            resource: resource.into(),
+            globals: Globals::default(),
+            quadlet: Quadlet::default(),
+            service: Service::default(),
+            install: Install::default(),
+            options: Vec::new(),
+        }
+    }
+
+    /// Add the `option` to the file if it applies to the file's sections.
+    ///
+    /// Options for a resource section, e.g. `[Container]`, are only added to files of that
+    /// resource kind. Returns `true` if the option was added.
+    pub fn add_option(&mut self, option: &UnitOption) -> bool {
+        let applies = option
+            .resource_kind()
+            .is_none_or(|kind| kind == ResourceKind::from(&self.resource));
+        if applies {
+            self.options.push(option.clone());
+        }
+        applies
+    }
+
     /// If this Quadlet [`File`] is a [`Container`] unit, set the `StartWithPod=` Quadlet option.
     pub fn set_start_with_pod(&mut self, start_with_pod: bool) {
         if let Resource::Container(container) = &mut self.resource {
@@ -103,38 +279,120 @@ impl Serialize for File {
             quadlet,
             service,
             install,
+            options,
         } = self;
 
-        let len = usize::from(!unit.is_empty())
+        let unit = (unit, SectionOptions::new(options, "Unit"));
+        let resource = (
+            resource,
+            globals,
+            SectionOptions::new(options, ResourceKind::from(resource).section_name()),
+        );
+        let quadlet = (quadlet, SectionOptions::new(options, "Quadlet"));
+        let service = (service, SectionOptions::new(options, "Service"));
+        let install = (install, SectionOptions::new(options, "Install"));
+
+        let unit_empty = unit.0.is_empty() && unit.1.is_empty();
+        let quadlet_empty = quadlet.0.is_empty() && quadlet.1.is_empty();
+        let service_empty = service.0.is_empty() && service.1.is_empty();
+        let install_empty = install.0.is_empty() && install.1.is_empty();
+
+        let extension_sections: IndexSet<&str> = options
+            .iter()
+            .map(|option| option.section.as_str())
+            .filter(|section| section.starts_with("X-"))
+            .collect();
+
+        let len = usize::from(!unit_empty)
             + 1 // resource / globals
-            + usize::from(!quadlet.is_empty())
-            + usize::from(!service.is_empty())
-            + usize::from(!install.is_empty());
+            + usize::from(!quadlet_empty)
+            + usize::from(!service_empty)
+            + usize::from(!install_empty)
+            + extension_sections.len();
 
         let mut seq = serializer.serialize_seq(Some(len))?;
 
-        if !unit.is_empty() {
-            seq.serialize_element(unit)?;
+        if !unit_empty {
+            seq.serialize_element(&unit)?;
         }
 
-        seq.serialize_element(&(resource, globals))?;
+        seq.serialize_element(&resource)?;
 
-        if !quadlet.is_empty() {
-            seq.serialize_element(quadlet)?;
+        if !quadlet_empty {
+            seq.serialize_element(&quadlet)?;
         }
 
-        if !service.is_empty() {
-            seq.serialize_element(service)?;
+        if !service_empty {
+            seq.serialize_element(&service)?;
         }
 
-        if !install.is_empty() {
-            seq.serialize_element(install)?;
+        if !install_empty {
+            seq.serialize_element(&install)?;
+        }
+
+        for section in extension_sections {
+            seq.serialize_element(&(section, SectionOptions::new(options, section)))?;
         }
 
         seq.end()
     }
 }
 
+/// The [`UnitOption`]s of a [`File`] for a single section, serialized as a map of option names to
+/// values.
+///
+/// Values are quoted if they contain whitespace and the option is a [`JoinOption`].
+struct SectionOptions<'a> {
+    options: IndexMap<&'a str, Vec<&'a str>>,
+}
+
+impl<'a> SectionOptions<'a> {
+    /// Collect the values of the `options` for the `section`, grouped by option name.
+    fn new(options: &'a [UnitOption], section: &str) -> Self {
+        let mut section_options: IndexMap<_, Vec<_>> = IndexMap::new();
+        for option in options.iter().filter(|option| option.section == section) {
+            section_options
+                .entry(option.key.as_str())
+                .or_default()
+                .push(option.value.as_str());
+        }
+        Self {
+            options: section_options,
+        }
+    }
+
+    /// Returns `true` if there are no options for the section.
+    fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+}
+
+impl Serialize for SectionOptions<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        /// Values of a single option.
+        struct Values<'a> {
+            key: &'a str,
+            values: &'a [&'a str],
+        }
+
+        impl Serialize for Values<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                if self.key.parse::<JoinOption>().is_ok() {
+                    crate::serde::quadlet::seq_quote_whitespace(self.values, serializer)
+                } else {
+                    self.values.serialize(serializer)
+                }
+            }
+        }
+
+        serializer.collect_map(
+            self.options
+                .iter()
+                .map(|(key, values)| (key, Values { key, values })),
+        )
+    }
+}
+
 impl HostPaths for File {
     fn host_paths(&mut self) -> impl Iterator<Item = &mut PathBuf> {
         self.resource.host_paths().chain(self.globals.host_paths())
@@ -151,6 +409,7 @@ impl Downgrade for File {
             quadlet,
             service: _,
             install: _,
+            options: _,
         } = self;
 
         resource.downgrade(version)?;
@@ -171,6 +430,12 @@ pub enum JoinOption {
     /// `After=`, used in [Unit] sections.
     After,
 
+    /// `Alias=`, used in [Install] sections.
+    Alias,
+
+    /// `Also=`, used in [Install] sections.
+    Also,
+
     /// `Annotation=`, used in [Container] and [Build] sections.
     Annotation,
 
@@ -183,10 +448,16 @@ pub enum JoinOption {
     /// `BuildArg=`, used in [Build] sections.
     BuildArg,
 
+    /// `Conflicts=`, used in [Unit] sections.
+    Conflicts,
+
+    /// `Documentation=`, used in [Unit] sections.
+    Documentation,
+
     /// `DropCapability=`, used in [Container] sections.
     DropCapability,
 
-    /// `Environment=`, used in [Container] and [Build] sections.
+    /// `Environment=`, used in [Container], [Build], and [Service] sections.
     Environment,
 
     /// `Label=`, used in [Container], [Network], [Volume], and [Build] sections.
@@ -195,6 +466,12 @@ pub enum JoinOption {
     /// `Mask=`, used in [Container] sections.
     Mask,
 
+    /// `OnFailure=`, used in [Unit] sections.
+    OnFailure,
+
+    /// `OnSuccess=`, used in [Unit] sections.
+    OnSuccess,
+
     /// `PartOf=`, used in [Unit] sections.
     PartOf,
 
@@ -228,14 +505,20 @@ impl JoinOption {
     pub const ALL: &'static [Self] = &[
         Self::AddCapability,
         Self::After,
+        Self::Alias,
+        Self::Also,
         Self::Annotation,
         Self::Before,
         Self::BindsTo,
         Self::BuildArg,
+        Self::Conflicts,
+        Self::Documentation,
         Self::DropCapability,
         Self::Environment,
         Self::Label,
         Self::Mask,
+        Self::OnFailure,
+        Self::OnSuccess,
         Self::PartOf,
         Self::RequiredBy,
         Self::Requires,
@@ -257,14 +540,20 @@ impl JoinOption {
         match self {
             Self::AddCapability => "AddCapability",
             Self::After => "After",
+            Self::Alias => "Alias",
+            Self::Also => "Also",
             Self::Annotation => "Annotation",
             Self::Before => "Before",
             Self::BindsTo => "BindsTo",
             Self::BuildArg => "BuildArg",
+            Self::Conflicts => "Conflicts",
+            Self::Documentation => "Documentation",
             Self::DropCapability => "DropCapability",
             Self::Environment => "Environment",
             Self::Label => "Label",
             Self::Mask => "Mask",
+            Self::OnFailure => "OnFailure",
+            Self::OnSuccess => "OnSuccess",
             Self::PartOf => "PartOf",
             Self::RequiredBy => "RequiredBy",
             Self::Requires => "Requires",
@@ -291,14 +580,20 @@ impl FromStr for JoinOption {
         match s {
             "AddCapability" => Ok(Self::AddCapability),
             "After" => Ok(Self::After),
+            "Alias" => Ok(Self::Alias),
+            "Also" => Ok(Self::Also),
             "Annotation" => Ok(Self::Annotation),
             "Before" => Ok(Self::Before),
             "BindsTo" => Ok(Self::BindsTo),
             "BuildArg" => Ok(Self::BuildArg),
+            "Conflicts" => Ok(Self::Conflicts),
+            "Documentation" => Ok(Self::Documentation),
             "DropCapability" => Ok(Self::DropCapability),
             "Environment" => Ok(Self::Environment),
             "Label" => Ok(Self::Label),
             "Mask" => Ok(Self::Mask),
+            "OnFailure" => Ok(Self::OnFailure),
+            "OnSuccess" => Ok(Self::OnSuccess),
             "PartOf" => Ok(Self::PartOf),
             "RequiredBy" => Ok(Self::RequiredBy),
             "Requires" => Ok(Self::Requires),
@@ -392,6 +687,81 @@ impl From<Artifact> for Resource {
 }
 
 impl Resource {
+    /// Deserialize a [`Resource`] of the given `kind` and the [`Globals`] from a Quadlet file
+    /// [`Section`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deserializing either fails, or if the section has an option which
+    /// neither supports.
+    pub fn deserialize_section(
+        kind: ResourceKind,
+        section: &Section,
+    ) -> Result<(Self, Globals), crate::serde::quadlet::Error> {
+        let (resource, globals, unknown) = Self::deserialize_section_partial(kind, section)?;
+        unknown.first().map_or(Ok((resource, globals)), |entry| {
+            Err(section.unknown_option(entry))
+        })
+    }
+
+    /// Deserialize a [`Resource`] of the given `kind` and the [`Globals`] from a Quadlet file
+    /// [`Section`], returning the entries neither supports alongside them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deserializing either fails.
+    pub fn deserialize_section_partial(
+        kind: ResourceKind,
+        section: &Section,
+    ) -> Result<(Self, Globals, Vec<&Entry>), crate::serde::quadlet::Error> {
+        /// Deserialize `T` from the `section` and convert it into a [`Resource`].
+        fn partial<T: DeserializeOwned + Into<Resource>>(
+            section: &Section,
+        ) -> Result<(Resource, Vec<&Entry>), crate::serde::quadlet::Error> {
+            section
+                .deserialize_partial::<T>()
+                .map(|(resource, unknown)| (resource.into(), unknown))
+        }
+
+        let (resource, unknown) = match kind {
+            ResourceKind::Container => partial::<Container>(section)?,
+            ResourceKind::Pod => partial::<Pod>(section)?,
+            ResourceKind::Kube => partial::<Kube>(section)?,
+            ResourceKind::Network => partial::<Network>(section)?,
+            ResourceKind::Volume => partial::<Volume>(section)?,
+            ResourceKind::Build => partial::<Build>(section)?,
+            ResourceKind::Image => partial::<Image>(section)?,
+            ResourceKind::Artifact => partial::<Artifact>(section)?,
+        };
+        let (globals, globals_unknown) = section.deserialize_partial::<Globals>()?;
+
+        // Options are only unknown if neither the resource nor the globals support them.
+        let unknown = unknown
+            .into_iter()
+            .filter(|entry| {
+                globals_unknown
+                    .iter()
+                    .any(|unknown| unknown.key == entry.key)
+            })
+            .collect();
+
+        Ok((resource, globals, unknown))
+    }
+
+    /// The `PodmanArgs=` Quadlet option of the resource.
+    pub fn podman_args(&self) -> Option<&str> {
+        match self {
+            Self::Container(container) => container.podman_args.as_deref(),
+            Self::Pod(pod) => pod.podman_args.as_deref(),
+            Self::Kube(kube) => kube.podman_args.as_deref(),
+            Self::Network(network) => network.podman_args.as_deref(),
+            Self::Volume(volume) => volume.podman_args.as_deref(),
+            Self::Build(build) => build.podman_args.as_deref(),
+            Self::Image(image) => image.podman_args.as_deref(),
+            Self::Artifact(artifact) => artifact.podman_args.as_deref(),
+        }
+    }
+
     /// The extension that should be used for the generated file.
     pub fn extension(&self) -> &'static str {
         ResourceKind::from(self).as_str()
@@ -510,6 +880,85 @@ impl ResourceKind {
             Self::Artifact => "artifact",
         }
     }
+
+    /// Name of the resource kind's Quadlet file section, e.g. "Container".
+    pub const fn section_name(self) -> &'static str {
+        match self {
+            Self::Container => "Container",
+            Self::Pod => "Pod",
+            Self::Kube => "Kube",
+            Self::Network => "Network",
+            Self::Volume => "Volume",
+            Self::Build => "Build",
+            Self::Image => "Image",
+            Self::Artifact => "Artifact",
+        }
+    }
+
+    /// Parse a resource kind from the name of its Quadlet file section, e.g. "Container".
+    pub fn from_section_name(name: &str) -> Option<Self> {
+        match name {
+            "Container" => Some(Self::Container),
+            "Pod" => Some(Self::Pod),
+            "Kube" => Some(Self::Kube),
+            "Network" => Some(Self::Network),
+            "Volume" => Some(Self::Volume),
+            "Build" => Some(Self::Build),
+            "Image" => Some(Self::Image),
+            "Artifact" => Some(Self::Artifact),
+            _ => None,
+        }
+    }
+
+    /// Names of the Quadlet options supported in the resource kind's section.
+    ///
+    /// Does not include the [`Globals`] options.
+    pub fn option_names(self) -> Vec<&'static str> {
+        match self {
+            Self::Container => option_names::<Container>(),
+            Self::Pod => option_names::<Pod>(),
+            Self::Kube => option_names::<Kube>(),
+            Self::Network => option_names::<Network>(),
+            Self::Volume => option_names::<Volume>(),
+            Self::Build => option_names::<Build>(),
+            Self::Image => option_names::<Image>(),
+            Self::Artifact => option_names::<Artifact>(),
+        }
+        .to_vec()
+    }
+
+    /// Names of the Quadlet options in the resource kind's section which are reset by an empty
+    /// value, see [`reset_option_names()`].
+    ///
+    /// Does not include the [`Globals`] options.
+    pub fn reset_option_names(self) -> Vec<&'static str> {
+        match self {
+            Self::Container => reset_option_names::<Container>(),
+            Self::Pod => reset_option_names::<Pod>(),
+            Self::Kube => reset_option_names::<Kube>(),
+            Self::Network => reset_option_names::<Network>(),
+            Self::Volume => reset_option_names::<Volume>(),
+            Self::Build => reset_option_names::<Build>(),
+            Self::Image => reset_option_names::<Image>(),
+            Self::Artifact => reset_option_names::<Artifact>(),
+        }
+    }
+
+    /// Parse a resource kind from a Quadlet file extension (without the leading "."), e.g.
+    /// "container".
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "container" => Some(Self::Container),
+            "pod" => Some(Self::Pod),
+            "kube" => Some(Self::Kube),
+            "network" => Some(Self::Network),
+            "volume" => Some(Self::Volume),
+            "build" => Some(Self::Build),
+            "image" => Some(Self::Image),
+            "artifact" => Some(Self::Artifact),
+            _ => None,
+        }
+    }
 }
 
 impl Display for ResourceKind {
@@ -536,8 +985,8 @@ impl From<&Resource> for ResourceKind {
 /// The `[Quadlet]` section.
 ///
 /// Options shared between all unit types.
-#[derive(Serialize, SmartDefault, Debug, Clone, Copy, PartialEq)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Serialize, Deserialize, SmartDefault, Debug, Clone, Copy, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
 pub struct Quadlet {
     /// Whether to add Quadlet's default network dependencies to the unit (default is `true`).
     ///
@@ -557,6 +1006,17 @@ impl Quadlet {
 
         default_dependencies
     }
+
+    /// Merge `other` into `self`.
+    ///
+    /// Default dependencies are disabled if they are disabled in either.
+    pub fn merge(&mut self, other: Self) {
+        let Self {
+            default_dependencies,
+        } = other;
+
+        self.default_dependencies &= default_dependencies;
+    }
 }
 
 impl Downgrade for Quadlet {
@@ -779,6 +1239,12 @@ impl Serialize for AutoUpdate {
     }
 }
 
+impl<'de> Deserialize<'de> for AutoUpdate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
 impl FromStr for AutoUpdate {
     type Err = ParseAutoUpdateError;
 
@@ -861,3 +1327,190 @@ fn push_arg_display(podman_args: &mut String, flag: &str, arg: impl Display) {
 
     write!(podman_args, "--{flag} {arg}").expect("write to String cannot fail");
 }
+
+#[cfg(test)]
+mod tests {
+    use compose_spec::service::Limit;
+
+    use super::{service::RestartConfig, *};
+
+    #[test]
+    fn deserialize_round_trip() -> color_eyre::Result<()> {
+        let file = File {
+            name: "test".into(),
+            unit: Unit {
+                description: Some("test container".into()),
+                after: vec!["one.service".into(), "two.service".into()],
+                on_failure: vec!["notify@%n.service".into()],
+                stop_when_unneeded: true,
+                condition_path_exists: vec!["/etc/one".into(), "!/etc/two".into()],
+                ..Unit::default()
+            },
+            resource: Container {
+                add_device: vec!["/dev/fuse:rw".parse()?],
+                auto_update: Some(AutoUpdate::Registry),
+                dns: container::Dns::None,
+                environment: vec!["ONE=one".into(), "TWO=two two".into()],
+                http_proxy: false,
+                image: "quay.io/podman/hello".into(),
+                mount: vec!["type=tmpfs,destination=/tmp".parse()?],
+                notify: container::Notify::Healthy,
+                pids_limit: Some(Limit::Unlimited),
+                podman_args: Some("--one --two".into()),
+                publish_port: vec!["8080:80".into(), "8443:443".into()],
+                pull: Some(container::PullPolicy::Newer),
+                stop_timeout: Some(20),
+                volume: vec!["volume.volume:/data:Z".parse()?],
+                ..Container::default()
+            }
+            .into(),
+            globals: Globals {
+                service_name: Some("test-service".into()),
+                ..Globals::default()
+            },
+            quadlet: Quadlet {
+                default_dependencies: false,
+            },
+            service: RestartConfig::Always.into(),
+            install: Install {
+                wanted_by: vec!["default.target".into()],
+                alias: vec!["alias.service".into()],
+                default_instance: Some("1".into()),
+                ..Install::default()
+            },
+            options: Vec::new(),
+        };
+
+        for join_options in [HashSet::new(), JoinOption::all_set()] {
+            let quadlet = file.serialize_to_quadlet(&join_options)?;
+            assert_eq!(
+                File::deserialize_from_quadlet("test".into(), &quadlet)?,
+                file,
+                "{quadlet}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_resource_kinds() -> color_eyre::Result<()> {
+        let file = File::deserialize_from_quadlet("test".into(), "[Pod]\nPodName=test\n")?;
+        assert_eq!(ResourceKind::from(&file.resource), ResourceKind::Pod);
+
+        let file = File::deserialize_from_quadlet("test".into(), "[Build]\nImageTag=test\n")?;
+        let Resource::Build(build) = file.resource else {
+            color_eyre::eyre::bail!("expected build resource");
+        };
+        assert!(build.force_rm);
+
+        let error = File::deserialize_from_quadlet("test".into(), "[Kube]\nNetwork=test\n")
+            .err()
+            .map(|error| error.to_string());
+        assert_eq!(
+            error.as_deref(),
+            Some("line 1: invalid [Kube] section: missing option `Yaml=`")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_unknown_options() -> color_eyre::Result<()> {
+        let input = "[Volume]\nDriver=local\nC=d\n\n[Service]\nKillMode=mixed\n";
+        let file = File::deserialize_from_quadlet("test".into(), input)?;
+        assert_eq!(
+            file.options,
+            [
+                UnitOption {
+                    section: "Volume".into(),
+                    key: "C".into(),
+                    value: "d".into(),
+                },
+                UnitOption {
+                    section: "Service".into(),
+                    key: "KillMode".into(),
+                    value: "mixed".into(),
+                },
+            ]
+        );
+        assert_eq!(file.serialize_to_quadlet(&JoinOption::all_set())?, input);
+
+        Ok(())
+    }
+
+    #[test]
+    fn extension_sections() -> Result<(), crate::serde::quadlet::Error> {
+        let input = "[Unit]\n\
+            Description=test\n\
+            \n\
+            [Container]\n\
+            Image=image\n\
+            \n\
+            [X-Backup]\n\
+            Path=/data\n\
+            Path=/config\n\
+            \n\
+            [X-Owner]\n\
+            Team=web\n";
+
+        let file = File::deserialize_from_quadlet("test".into(), input)?;
+        assert_eq!(
+            file.options,
+            [
+                UnitOption {
+                    section: "X-Backup".into(),
+                    key: "Path".into(),
+                    value: "/data".into(),
+                },
+                UnitOption {
+                    section: "X-Backup".into(),
+                    key: "Path".into(),
+                    value: "/config".into(),
+                },
+                UnitOption {
+                    section: "X-Owner".into(),
+                    key: "Team".into(),
+                    value: "web".into(),
+                },
+            ]
+        );
+        assert_eq!(file.serialize_to_quadlet(&JoinOption::all_set())?, input);
+
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_errors() {
+        use crate::serde::quadlet::Error;
+
+        assert_eq!(
+            File::deserialize_from_quadlet("test".into(), "[Unit]\nDescription=test\n").err(),
+            Some(Error::MissingResource)
+        );
+        assert_eq!(
+            File::deserialize_from_quadlet("test".into(), "[Container]\n[Pod]\n").err(),
+            Some(Error::Syntax {
+                line: 2,
+                message: "only one resource section is allowed, found [Pod] after [Container]"
+                    .into(),
+            })
+        );
+        assert_eq!(
+            File::deserialize_from_quadlet("test".into(), "[Unknown]\n").err(),
+            Some(Error::UnknownSection {
+                line: 1,
+                section: "Unknown".into(),
+            })
+        );
+        assert_eq!(
+            File::deserialize_from_quadlet("test".into(), "[Container]\nNotify=maybe\n").err(),
+            Some(Error::Value {
+                line: 2,
+                key: "Notify".into(),
+                message: "invalid value: string \"maybe\", expected a boolean or \"healthy\""
+                    .into(),
+            })
+        );
+    }
+}
@@ -2,14 +2,14 @@
 
 use std::{ops::Not, path::PathBuf};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::quadlet::{
     Downgrade, DowngradeError, HostPaths, PodmanVersion, ResourceKind, image::DecryptionKey,
 };
 
 /// Options for the \[Artifact\] section of a `.artifact` Quadlet file.
-#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Artifact {
     /// The artifact to pull from a registry onto the local machine.
@@ -33,7 +33,7 @@ pub struct Artifact {
     pub podman_args: Option<String>,
 
     /// Suppress output information when pulling artifacts.
-    #[serde(skip_serializing_if = "Not::not")]
+    #[serde(default, skip_serializing_if = "Not::not")]
     pub quiet: bool,
 
     /// Number of times to retry the artifact pull when a HTTP error occurs.
@@ -7,9 +7,10 @@ use std::{
 
 use color_eyre::eyre::{OptionExt, bail, ensure};
 use compose_spec::service::build::Context;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use smart_default::SmartDefault;
 
-use crate::serde::{quadlet::seq_quote_whitespace, skip_true};
+use crate::serde::{deserialize_from_str, quadlet::seq_quote_whitespace, skip_true};
 
 use super::{
     Downgrade, DowngradeError, HostPaths, PodmanVersion, ResourceKind,
@@ -18,8 +19,8 @@ use super::{
 };
 
 /// Options for the \[Build\] section of a `.build` Quadlet file.
-#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Serialize, Deserialize, SmartDefault, Debug, Clone, PartialEq, Eq)]
+#[serde(default, rename_all = "PascalCase")]
 pub struct Build {
     /// Add an image annotation (e.g. annotation=value) to the image metadata.
     pub annotation: Vec<String>,
@@ -56,6 +57,7 @@ pub struct Build {
 
     /// Always remove intermediate containers after a build, even if the build fails.
     #[serde(rename = "ForceRM", skip_serializing_if = "skip_true")]
+    #[default = true]
     pub force_rm: bool,
 
     /// Assign additional groups to the primary user running within the container process.
@@ -222,6 +224,12 @@ impl Display for Secret {
     }
 }
 
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
 impl Serialize for Secret {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         serializer.collect_str(self)
@@ -15,10 +15,17 @@ use std::{
 use clap::ValueEnum;
 use color_eyre::eyre::eyre;
 use compose_spec::service::{self, Limit};
-use serde::{Serialize, Serializer};
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, Visitor},
+};
 use smart_default::SmartDefault;
 
-use crate::serde::{quadlet::seq_quote_whitespace, serialize_display_seq, skip_true};
+use crate::serde::{
+    deserialize_from_str_seq,
+    quadlet::{parse_bool, seq_quote_whitespace},
+    serialize_display_seq, skip_true,
+};
 
 use self::mount::Artifact;
 pub use self::{device::Device, mount::Mount, rootfs::Rootfs, volume::Volume};
@@ -26,8 +33,8 @@ pub use self::{device::Device, mount::Mount, rootfs::Rootfs, volume::Volume};
 use super::{AutoUpdate, Downgrade, DowngradeError, HostPaths, PodmanVersion, push_arg_display};
 
 #[allow(clippy::struct_excessive_bools)]
-#[derive(Serialize, SmartDefault, Debug, Clone, PartialEq)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Serialize, Deserialize, SmartDefault, Debug, Clone, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
 pub struct Container {
     /// Add these capabilities, in addition to the default Podman capability set, to the container.
     #[serde(serialize_with = "seq_quote_whitespace")]
@@ -182,7 +189,10 @@ pub struct Container {
     pub memory: Option<String>,
 
     /// Attach a filesystem mount to the container.
-    #[serde(serialize_with = "serialize_display_seq")]
+    #[serde(
+        serialize_with = "serialize_display_seq",
+        deserialize_with = "deserialize_from_str_seq"
+    )]
     pub mount: Vec<Mount>,
 
     /// Specify a custom network for the container.
@@ -851,6 +861,12 @@ impl Default for Dns {
     }
 }
 
+impl<'de> Deserialize<'de> for Dns {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str_seq::<DnsEntry, _>(deserializer).map(Self::from)
+    }
+}
+
 impl From<Vec<DnsEntry>> for Dns {
     fn from(value: Vec<DnsEntry>) -> Self {
         Self::from_iter(value)
@@ -947,11 +963,43 @@ impl Serialize for Notify {
     }
 }
 
+impl<'de> Deserialize<'de> for Notify {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(NotifyVisitor)
+    }
+}
+
+/// Deserialization [`Visitor`] for [`Notify`].
+struct NotifyVisitor;
+
+impl Visitor<'_> for NotifyVisitor {
+    type Value = Notify;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("a boolean or \"healthy\"")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(if v { Notify::Container } else { Notify::Conmon })
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        if v == "healthy" {
+            Ok(Notify::Healthy)
+        } else if let Some(bool) = parse_bool(v) {
+            self.visit_bool(bool)
+        } else {
+            Err(E::invalid_value(de::Unexpected::Str(v), &self))
+        }
+    }
+}
+
 /// Valid pull policies for container images.
 ///
 /// See the `--pull` [section](https://docs.podman.io/en/stable/markdown/podman-run.1.html#pull-policy)
 /// of the `podman run` documentation.
-#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(ValueEnum, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub enum PullPolicy {
     /// Always pull the image and throw an error if the pull fails.
     Always,
@@ -1042,6 +1090,14 @@ impl Serialize for Unmask {
     }
 }
 
+impl<'de> Deserialize<'de> for Unmask {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut unmask = Self::new();
+        unmask.extend(Vec::<String>::deserialize(deserializer)?);
+        Ok(unmask)
+    }
+}
+
 impl Display for Unmask {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
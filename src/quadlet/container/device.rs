@@ -9,10 +9,10 @@ use std::{
 };
 
 use compose_spec::service::{self, device::Permissions};
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
-use crate::quadlet::HostPaths;
+use crate::{quadlet::HostPaths, serde::deserialize_from_str};
 
 /// Device to attach to a [`Container`](super::Container).
 ///
@@ -170,6 +170,12 @@ impl Serialize for Device {
     }
 }
 
+impl<'de> Deserialize<'de> for Device {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
 impl From<service::Device> for Device {
     fn from(
         service::Device {
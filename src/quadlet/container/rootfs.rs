@@ -7,10 +7,10 @@ use std::{
     str::FromStr,
 };
 
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
-use crate::quadlet::HostPaths;
+use crate::{quadlet::HostPaths, serde::deserialize_from_str};
 
 use super::mount::{Idmap, idmap::ParseIdmapError};
 
@@ -123,6 +123,12 @@ impl Serialize for Rootfs {
     }
 }
 
+impl<'de> Deserialize<'de> for Rootfs {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
 /// Error returned when parsing [`Rootfs`] from a string.
 #[derive(Error, Debug)]
 pub enum ParseRootfsError {
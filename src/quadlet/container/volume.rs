@@ -15,10 +15,10 @@ use compose_spec::{
         mount::{self, Bind, BindOptions, Common, VolumeOptions},
     },
 };
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
-use crate::quadlet::HostPaths;
+use crate::{quadlet::HostPaths, serde::deserialize_from_str};
 
 use super::mount::{BindPropagation, Idmap, SELinuxRelabel, idmap::ParseIdmapError};
 
@@ -129,6 +129,12 @@ impl Serialize for Volume {
     }
 }
 
+impl<'de> Deserialize<'de> for Volume {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
 impl From<ShortVolume> for Volume {
     fn from(
         ShortVolume {
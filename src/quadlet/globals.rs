@@ -1,12 +1,12 @@
 use std::path::PathBuf;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use super::{Downgrade, DowngradeError, HostPaths, PodmanVersion};
 
 /// Global Quadlet options that apply to all resource types.
-#[derive(Serialize, Debug, Default, Clone, PartialEq)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
 pub struct Globals {
     /// Load the specified containers.conf module.
     pub containers_conf_module: Vec<PathBuf>,
@@ -7,18 +7,20 @@ use std::{
     str::FromStr,
 };
 
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::serde::deserialize_from_str;
 
 use super::{
     Downgrade, DowngradeError, HostPaths, PodmanVersion, ResourceKind, container::PullPolicy,
     push_arg, push_arg_display,
 };
 
-#[derive(Serialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Image {
     /// All tagged images in the repository are pulled.
-    #[serde(skip_serializing_if = "Not::not")]
+    #[serde(default, skip_serializing_if = "Not::not")]
     pub all_tags: bool,
 
     /// Override the architecture, defaults to hosts, of the image to be pulled.
@@ -197,6 +199,12 @@ impl FromStr for DecryptionKey {
     }
 }
 
+impl<'de> Deserialize<'de> for DecryptionKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
 impl Display for DecryptionKey {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let Self { key, passphrase } = self;
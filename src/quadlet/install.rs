@@ -1,11 +1,10 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::serde::quadlet::seq_quote_whitespace;
 
 /// The `[Install]` section of a systemd unit / Quadlet file.
-#[expect(clippy::struct_field_names, reason = "systemd directives")]
-#[derive(Serialize, Default, Debug, Clone, PartialEq)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
 pub struct Install {
     /// Add weak parent dependencies to the unit.
     #[serde(serialize_with = "seq_quote_whitespace")]
@@ -18,17 +17,59 @@ pub struct Install {
     /// Add stronger parent dependencies to the unit.
     #[serde(serialize_with = "seq_quote_whitespace")]
     pub upheld_by: Vec<String>,
+
+    /// Additional names the unit is installed under.
+    #[serde(serialize_with = "seq_quote_whitespace")]
+    pub alias: Vec<String>,
+
+    /// Additional units to install or remove when the unit is installed or removed.
+    #[serde(serialize_with = "seq_quote_whitespace")]
+    pub also: Vec<String>,
+
+    /// Instance of a template unit to enable if no instance is given.
+    pub default_instance: Option<String>,
 }
 
 impl Install {
-    /// Returns `true` if all fields are empty.
+    /// Returns `true` if all fields are empty or [`None`].
     pub fn is_empty(&self) -> bool {
         let Self {
             wanted_by,
             required_by,
             upheld_by,
+            alias,
+            also,
+            default_instance,
         } = self;
 
-        wanted_by.is_empty() && required_by.is_empty() && upheld_by.is_empty()
+        wanted_by.is_empty()
+            && required_by.is_empty()
+            && upheld_by.is_empty()
+            && alias.is_empty()
+            && also.is_empty()
+            && default_instance.is_none()
+    }
+
+    /// Merge `other` into `self` by extending each list.
+    ///
+    /// `default_instance` already set in `self` takes precedence.
+    pub fn merge(&mut self, other: Self) {
+        let Self {
+            wanted_by,
+            required_by,
+            upheld_by,
+            alias,
+            also,
+            default_instance,
+        } = other;
+
+        self.wanted_by.extend(wanted_by);
+        self.required_by.extend(required_by);
+        self.upheld_by.extend(upheld_by);
+        self.alias.extend(alias);
+        self.also.extend(also);
+        if self.default_instance.is_none() {
+            self.default_instance = default_instance;
+        }
     }
 }
@@ -6,25 +6,30 @@ use std::{
     str::FromStr,
 };
 
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use url::Url;
 
-use super::{Downgrade, DowngradeError, HostPaths, PodmanVersion, push_arg};
+use crate::serde::deserialize_from_str;
 
-#[derive(Serialize, Debug, Clone, PartialEq)]
+use super::{Downgrade, DowngradeError, HostPaths, ParseAutoUpdateError, PodmanVersion, push_arg};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Kube {
     /// Indicates whether containers will be auto-updated.
+    #[serde(default)]
     pub auto_update: Vec<AutoUpdate>,
 
     /// Pass the Kubernetes ConfigMap YAML at path to `podman kube play`.
     #[allow(clippy::doc_markdown)]
+    #[serde(default)]
     pub config_map: Vec<PathBuf>,
 
     /// Set the log-driver Podman uses when running the container.
     pub log_driver: Option<String>,
 
     /// Specify a custom network for the container.
+    #[serde(default)]
     pub network: Vec<String>,
 
     /// This key contains a list of arguments passed directly to the end of the `podman kube play`
@@ -32,6 +37,7 @@ pub struct Kube {
     pub podman_args: Option<String>,
 
     /// Exposes a port, or a range of ports, from the container to the host.
+    #[serde(default)]
     pub publish_port: Vec<String>,
 
     /// Set the user namespace mode for the container.
@@ -184,6 +190,27 @@ impl Serialize for AutoUpdate {
     }
 }
 
+impl<'de> Deserialize<'de> for AutoUpdate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
+impl FromStr for AutoUpdate {
+    type Err = ParseAutoUpdateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((container, auto_update)) = s.rsplit_once('/') {
+            Ok(Self::Container {
+                container: container.to_owned(),
+                auto_update: auto_update.parse()?,
+            })
+        } else {
+            s.parse().map(Self::All)
+        }
+    }
+}
+
 /// A [`Url`] or [`PathBuf`] to a Kubernetes YAML file.
 #[derive(Debug, Clone, PartialEq)]
 pub enum YamlFile {
@@ -246,6 +273,12 @@ impl Display for YamlFile {
     }
 }
 
+impl<'de> Deserialize<'de> for YamlFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
 impl Serialize for YamlFile {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         serializer.collect_str(self)
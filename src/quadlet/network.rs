@@ -7,15 +7,15 @@ use std::{
 use color_eyre::eyre::{Context, ensure, eyre};
 use compose_spec::network::{Ipam, IpamConfig};
 use ipnet::IpNet;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
-use crate::serde::quadlet::seq_quote_whitespace;
+use crate::serde::{deserialize_from_str, quadlet::seq_quote_whitespace};
 
 use super::{Downgrade, DowngradeError, PodmanVersion, push_arg};
 
-#[derive(Serialize, Debug, Default, Clone, PartialEq)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
 pub struct Network {
     /// If enabled, disables the DNS plugin for this network.
     #[serde(rename = "DisableDNS", skip_serializing_if = "Not::not")]
@@ -54,6 +54,12 @@ pub struct Network {
     #[serde(serialize_with = "seq_quote_whitespace")]
     pub label: Vec<String>,
 
+    /// The name of the Podman network.
+    ///
+    /// If not set, the default value is `systemd-%N`.
+    #[allow(clippy::struct_field_names)]
+    pub network_name: Option<String>,
+
     /// Set driver specific options.
     pub options: Vec<String>,
 
@@ -97,6 +103,16 @@ impl Downgrade for Network {
             }
         }
 
+        if version < PodmanVersion::V4_5 {
+            if let Some(network_name) = self.network_name.take() {
+                return Err(DowngradeError::Option {
+                    quadlet_option: "NetworkName",
+                    value: network_name,
+                    supported_version: PodmanVersion::V4_5,
+                });
+            }
+        }
+
         Ok(())
     }
 }
@@ -203,6 +219,12 @@ impl Serialize for IpRange {
     }
 }
 
+impl<'de> Deserialize<'de> for IpRange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
 impl FromStr for IpRange {
     type Err = ParseIpRangeError;
 
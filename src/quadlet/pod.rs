@@ -5,7 +5,7 @@ use std::{
 };
 
 use clap::ValueEnum;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::serde::{quadlet::seq_quote_whitespace, skip_default};
 
@@ -16,8 +16,8 @@ use super::{
 };
 
 /// Options for the \[Pod\] section of a `.pod` Quadlet file.
-#[derive(Serialize, Debug, Default, Clone, PartialEq)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
 pub struct Pod {
     /// Add host-to-IP mapping to `/etc/hosts`.
     pub add_host: Vec<String>,
@@ -215,7 +215,7 @@ impl Pod {
 }
 
 /// Supported values of the `ExitPolicy=` Quadlet option for [`Pod`] units.
-#[derive(ValueEnum, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum ExitPolicy {
     /// The pod continues running, by keeping its infra container alive, when the last container
@@ -1,25 +1,195 @@
 use clap::{Args, ValueEnum};
 use compose_spec::service::Restart;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use crate::serde::quadlet::seq_quote_whitespace;
 
 /// The `[Service]` section of a systemd unit / Quadlet file.
 ///
-/// Only includes options needed to convert [Podman CLI](crate::cli::PodmanCommands) and
-/// [`Compose`](compose_spec::Compose) files.
-#[derive(Args, Serialize, Default, Debug, Clone, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
+/// Includes common systemd service options.
+///
+/// From [systemd.service](https://www.freedesktop.org/software/systemd/man/systemd.service.html)
+/// and [systemd.resource-control](https://www.freedesktop.org/software/systemd/man/systemd.resource-control.html).
+#[allow(clippy::doc_markdown)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default, rename_all = "PascalCase")]
 pub struct Service {
     /// Configure if and when the service should be restarted.
+    ///
+    /// Converts to "Restart=POLICY".
     #[arg(long, value_name = "POLICY")]
     pub restart: Option<RestartConfig>,
+
+    /// Time to sleep before restarting the service, e.g. "5s".
+    ///
+    /// Converts to "RestartSec=RESTART_SEC".
+    #[arg(long, value_name = "TIME")]
+    pub restart_sec: Option<String>,
+
+    /// Configure the process start-up type of the service.
+    ///
+    /// Converts to "Type=TYPE".
+    #[arg(long)]
+    pub r#type: Option<ServiceType>,
+
+    /// Consider the service active even when its main process exits.
+    ///
+    /// Converts to "RemainAfterExit=true".
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    pub remain_after_exit: Option<bool>,
+
+    /// Time to wait for the service to start up, e.g. "900" for slow image pulls.
+    ///
+    /// Converts to "TimeoutStartSec=TIMEOUT_START_SEC".
+    #[arg(long, value_name = "TIME")]
+    pub timeout_start_sec: Option<String>,
+
+    /// Time to wait for the service to stop.
+    ///
+    /// Converts to "TimeoutStopSec=TIMEOUT_STOP_SEC".
+    #[arg(long, value_name = "TIME")]
+    pub timeout_stop_sec: Option<String>,
+
+    /// Command to run before the service's main process is started.
+    ///
+    /// Converts to "ExecStartPre=EXEC_START_PRE".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "COMMAND")]
+    pub exec_start_pre: Vec<String>,
+
+    /// Command to run after the service's main process is started.
+    ///
+    /// Converts to "ExecStartPost=EXEC_START_POST".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "COMMAND")]
+    pub exec_start_post: Vec<String>,
+
+    /// Command to run to reload the service's configuration.
+    ///
+    /// Converts to "ExecReload=EXEC_RELOAD".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "COMMAND")]
+    pub exec_reload: Vec<String>,
+
+    /// Set an environment variable for the service's commands, e.g. "KEY=value".
+    ///
+    /// Converts to "Environment=ENVIRONMENT[ ...]".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "ENV")]
+    #[serde(serialize_with = "seq_quote_whitespace")]
+    pub environment: Vec<String>,
+
+    /// Exit status or signal which, in addition to 0, is considered a successful exit.
+    ///
+    /// Converts to "SuccessExitStatus=SUCCESS_EXIT_STATUS".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "STATUS")]
+    pub success_exit_status: Vec<String>,
+
+    /// Absolute limit on the memory usage of the service, e.g. "1G".
+    ///
+    /// Converts to "MemoryMax=MEMORY_MAX".
+    #[arg(long, value_name = "BYTES")]
+    pub memory_max: Option<String>,
+
+    /// CPU time quota of the service, e.g. "20%".
+    ///
+    /// Converts to "CPUQuota=CPU_QUOTA".
+    #[arg(long, value_name = "PERCENT")]
+    #[serde(rename = "CPUQuota")]
+    pub cpu_quota: Option<String>,
+
+    /// Slice unit to place the service in.
+    ///
+    /// Converts to "Slice=SLICE".
+    #[arg(long)]
+    pub slice: Option<String>,
 }
 
 impl Service {
-    /// Returns `true` if all fields are [`None`].
+    /// Returns `true` if all fields are empty or [`None`].
     pub fn is_empty(&self) -> bool {
-        let Self { restart } = self;
+        let Self {
+            restart,
+            restart_sec,
+            r#type,
+            remain_after_exit,
+            timeout_start_sec,
+            timeout_stop_sec,
+            exec_start_pre,
+            exec_start_post,
+            exec_reload,
+            environment,
+            success_exit_status,
+            memory_max,
+            cpu_quota,
+            slice,
+        } = self;
 
         restart.is_none()
+            && restart_sec.is_none()
+            && r#type.is_none()
+            && remain_after_exit.is_none()
+            && timeout_start_sec.is_none()
+            && timeout_stop_sec.is_none()
+            && exec_start_pre.is_empty()
+            && exec_start_post.is_empty()
+            && exec_reload.is_empty()
+            && environment.is_empty()
+            && success_exit_status.is_empty()
+            && memory_max.is_none()
+            && cpu_quota.is_none()
+            && slice.is_none()
+    }
+
+    /// Merge `other` into `self`.
+    ///
+    /// Options already set in `self` take precedence, lists are extended.
+    pub fn merge(&mut self, other: &Self) {
+        let Self {
+            restart,
+            restart_sec,
+            r#type,
+            remain_after_exit,
+            timeout_start_sec,
+            timeout_stop_sec,
+            exec_start_pre,
+            exec_start_post,
+            exec_reload,
+            environment,
+            success_exit_status,
+            memory_max,
+            cpu_quota,
+            slice,
+        } = other;
+
+        self.restart = self.restart.or(*restart);
+        merge_option(&mut self.restart_sec, restart_sec.as_ref());
+        self.r#type = self.r#type.or(*r#type);
+        self.remain_after_exit = self.remain_after_exit.or(*remain_after_exit);
+        merge_option(&mut self.timeout_start_sec, timeout_start_sec.as_ref());
+        merge_option(&mut self.timeout_stop_sec, timeout_stop_sec.as_ref());
+        self.exec_start_pre.extend_from_slice(exec_start_pre);
+        self.exec_start_post.extend_from_slice(exec_start_post);
+        self.exec_reload.extend_from_slice(exec_reload);
+        self.environment.extend_from_slice(environment);
+        self.success_exit_status
+            .extend_from_slice(success_exit_status);
+        merge_option(&mut self.memory_max, memory_max.as_ref());
+        merge_option(&mut self.cpu_quota, cpu_quota.as_ref());
+        merge_option(&mut self.slice, slice.as_ref());
+    }
+}
+
+/// Set `option` to a clone of `other` if it is [`None`].
+fn merge_option(option: &mut Option<String>, other: Option<&String>) {
+    if option.is_none() {
+        *option = other.cloned();
     }
 }
 
@@ -27,6 +197,7 @@ impl From<RestartConfig> for Service {
     fn from(restart: RestartConfig) -> Self {
         Self {
             restart: Some(restart),
+            ..Self::default()
         }
     }
 }
@@ -40,7 +211,7 @@ impl From<Restart> for Service {
 /// Possible service restart configurations.
 ///
 /// From [systemd.service](https://www.freedesktop.org/software/systemd/man/systemd.service.html#Restart=).
-#[derive(ValueEnum, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum RestartConfig {
     No,
@@ -62,3 +233,19 @@ impl From<Restart> for RestartConfig {
         }
     }
 }
+
+/// Possible service process start-up types.
+///
+/// From [systemd.service](https://www.freedesktop.org/software/systemd/man/systemd.service.html#Type=).
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ServiceType {
+    Simple,
+    Exec,
+    Forking,
+    Oneshot,
+    Dbus,
+    Notify,
+    NotifyReload,
+    Idle,
+}
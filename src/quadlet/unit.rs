@@ -1,10 +1,9 @@
+use std::ops::Not;
+
 use clap::Args;
-use color_eyre::{
-    Section,
-    eyre::{self, bail, eyre},
-};
-use compose_spec::service::{Condition, Dependency};
-use serde::Serialize;
+use color_eyre::eyre::{self, bail};
+use compose_spec::service::Dependency;
+use serde::{Deserialize, Serialize};
 
 use crate::serde::quadlet::seq_quote_whitespace;
 
@@ -14,8 +13,8 @@ use crate::serde::quadlet::seq_quote_whitespace;
 ///
 /// From [systemd.unit](https://www.freedesktop.org/software/systemd/man/systemd.unit.html).
 #[allow(clippy::doc_markdown)]
-#[derive(Serialize, Args, Default, Debug, Clone, PartialEq)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Serialize, Deserialize, Args, Default, Debug, Clone, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
 pub struct Unit {
     /// Add a description to the unit.
     ///
@@ -25,6 +24,15 @@ pub struct Unit {
     #[arg(short, long)]
     pub description: Option<String>,
 
+    /// Add documentation references for the unit, e.g. "man:podman(1)" or a URL.
+    ///
+    /// Converts to "Documentation=DOCUMENTATION[ ...]".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "URI")]
+    #[serde(serialize_with = "seq_quote_whitespace")]
+    pub documentation: Vec<String>,
+
     /// Add (weak) requirement dependencies to the unit.
     ///
     /// Converts to "Wants=WANTS[ ...]".
@@ -70,6 +78,15 @@ pub struct Unit {
     #[serde(serialize_with = "seq_quote_whitespace")]
     pub upholds: Vec<String>,
 
+    /// Add negative requirement dependencies, starting this unit stops the conflicting units.
+    ///
+    /// Converts to "Conflicts=CONFLICTS[ ...]".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long)]
+    #[serde(serialize_with = "seq_quote_whitespace")]
+    pub conflicts: Vec<String>,
+
     /// Configure ordering dependency between units.
     ///
     /// Converts to "Before=BEFORE[ ...]".
@@ -87,57 +104,442 @@ pub struct Unit {
     #[arg(long)]
     #[serde(serialize_with = "seq_quote_whitespace")]
     pub after: Vec<String>,
+
+    /// Units to activate when this unit enters the failed state, e.g. a notification service.
+    ///
+    /// Converts to "OnFailure=ON_FAILURE[ ...]".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long)]
+    #[serde(serialize_with = "seq_quote_whitespace")]
+    pub on_failure: Vec<String>,
+
+    /// Units to activate when this unit enters the inactive state.
+    ///
+    /// Converts to "OnSuccess=ON_SUCCESS[ ...]".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long)]
+    #[serde(serialize_with = "seq_quote_whitespace")]
+    pub on_success: Vec<String>,
+
+    /// Stop the unit when it is no longer used by other units.
+    ///
+    /// Converts to "StopWhenUnneeded=true".
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Not::not")]
+    pub stop_when_unneeded: bool,
+
+    /// Interval in which the unit may only be started --start-limit-burst times.
+    ///
+    /// Converts to "StartLimitIntervalSec=START_LIMIT_INTERVAL_SEC".
+    #[arg(long, value_name = "TIME")]
+    pub start_limit_interval_sec: Option<String>,
+
+    /// Number of times the unit may be started within the start limit interval.
+    ///
+    /// Converts to "StartLimitBurst=START_LIMIT_BURST".
+    #[arg(long, value_name = "COUNT")]
+    pub start_limit_burst: Option<u64>,
+
+    /// Only start the unit if the path exists.
+    ///
+    /// Prefix with "!" to negate the condition.
+    ///
+    /// Converts to "ConditionPathExists=PATH".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub condition_path_exists: Vec<String>,
+
+    /// Only start the unit if a path matching the glob pattern exists.
+    ///
+    /// Prefix with "!" to negate the condition.
+    ///
+    /// Converts to "ConditionPathExistsGlob=PATTERN".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATTERN")]
+    pub condition_path_exists_glob: Vec<String>,
+
+    /// Only start the unit if the path exists and is a directory.
+    ///
+    /// Prefix with "!" to negate the condition.
+    ///
+    /// Converts to "ConditionPathIsDirectory=PATH".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub condition_path_is_directory: Vec<String>,
+
+    /// Only start the unit if the path exists and is a mount point.
+    ///
+    /// Prefix with "!" to negate the condition.
+    ///
+    /// Converts to "ConditionPathIsMountPoint=PATH".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub condition_path_is_mount_point: Vec<String>,
+
+    /// Only start the unit if the path exists and is a non-empty directory.
+    ///
+    /// Prefix with "!" to negate the condition.
+    ///
+    /// Converts to "ConditionDirectoryNotEmpty=PATH".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub condition_directory_not_empty: Vec<String>,
+
+    /// Only start the unit if the path exists and is a non-empty regular file.
+    ///
+    /// Prefix with "!" to negate the condition.
+    ///
+    /// Converts to "ConditionFileNotEmpty=PATH".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub condition_file_not_empty: Vec<String>,
+
+    /// Only start the unit if the path exists and is an executable file.
+    ///
+    /// Prefix with "!" to negate the condition.
+    ///
+    /// Converts to "ConditionFileIsExecutable=PATH".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub condition_file_is_executable: Vec<String>,
+
+    /// Only start the unit if the service manager runs as the user.
+    ///
+    /// Prefix with "!" to negate the condition.
+    ///
+    /// Converts to "ConditionUser=USER".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "USER")]
+    pub condition_user: Vec<String>,
+
+    /// Only start the unit if the hostname or machine ID matches.
+    ///
+    /// Prefix with "!" to negate the condition.
+    ///
+    /// Converts to "ConditionHost=HOST".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "HOST")]
+    pub condition_host: Vec<String>,
+
+    /// Only start the unit if the system is running in the virtualization environment.
+    ///
+    /// Prefix with "!" to negate the condition.
+    ///
+    /// Converts to "ConditionVirtualization=VIRTUALIZATION".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "VIRTUALIZATION")]
+    pub condition_virtualization: Vec<String>,
+
+    /// Only start the unit if the environment variable is set in the service manager's environment.
+    ///
+    /// Prefix with "!" to negate the condition.
+    ///
+    /// Converts to "ConditionEnvironment=ENV".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "ENV")]
+    pub condition_environment: Vec<String>,
+
+    /// Similar to --condition-path-exists, but the unit fails instead of being skipped.
+    ///
+    /// Converts to "AssertPathExists=PATH".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub assert_path_exists: Vec<String>,
+
+    /// Similar to --condition-path-exists-glob, but the unit fails instead of being skipped.
+    ///
+    /// Converts to "AssertPathExistsGlob=PATTERN".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATTERN")]
+    pub assert_path_exists_glob: Vec<String>,
+
+    /// Similar to --condition-path-is-directory, but the unit fails instead of being skipped.
+    ///
+    /// Converts to "AssertPathIsDirectory=PATH".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub assert_path_is_directory: Vec<String>,
+
+    /// Similar to --condition-path-is-mount-point, but the unit fails instead of being skipped.
+    ///
+    /// Converts to "AssertPathIsMountPoint=PATH".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub assert_path_is_mount_point: Vec<String>,
+
+    /// Similar to --condition-directory-not-empty, but the unit fails instead of being skipped.
+    ///
+    /// Converts to "AssertDirectoryNotEmpty=PATH".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub assert_directory_not_empty: Vec<String>,
+
+    /// Similar to --condition-file-not-empty, but the unit fails instead of being skipped.
+    ///
+    /// Converts to "AssertFileNotEmpty=PATH".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub assert_file_not_empty: Vec<String>,
+
+    /// Similar to --condition-file-is-executable, but the unit fails instead of being skipped.
+    ///
+    /// Converts to "AssertFileIsExecutable=PATH".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub assert_file_is_executable: Vec<String>,
+
+    /// Similar to --condition-user, but the unit fails instead of being skipped.
+    ///
+    /// Converts to "AssertUser=USER".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "USER")]
+    pub assert_user: Vec<String>,
+
+    /// Similar to --condition-host, but the unit fails instead of being skipped.
+    ///
+    /// Converts to "AssertHost=HOST".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "HOST")]
+    pub assert_host: Vec<String>,
+
+    /// Similar to --condition-virtualization, but the unit fails instead of being skipped.
+    ///
+    /// Converts to "AssertVirtualization=VIRTUALIZATION".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "VIRTUALIZATION")]
+    pub assert_virtualization: Vec<String>,
+
+    /// Similar to --condition-environment, but the unit fails instead of being skipped.
+    ///
+    /// Converts to "AssertEnvironment=ENV".
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "ENV")]
+    pub assert_environment: Vec<String>,
 }
 
 impl Unit {
-    /// Returns `true` if all fields are empty or [`None`].
+    /// Returns `true` if all fields are empty, [`None`], or `false`.
     pub fn is_empty(&self) -> bool {
         let Self {
             description,
+            documentation,
             wants,
             requires,
             binds_to,
             part_of,
             upholds,
+            conflicts,
             before,
             after,
+            on_failure,
+            on_success,
+            stop_when_unneeded,
+            start_limit_interval_sec,
+            start_limit_burst,
+            condition_path_exists,
+            condition_path_exists_glob,
+            condition_path_is_directory,
+            condition_path_is_mount_point,
+            condition_directory_not_empty,
+            condition_file_not_empty,
+            condition_file_is_executable,
+            condition_user,
+            condition_host,
+            condition_virtualization,
+            condition_environment,
+            assert_path_exists,
+            assert_path_exists_glob,
+            assert_path_is_directory,
+            assert_path_is_mount_point,
+            assert_directory_not_empty,
+            assert_file_not_empty,
+            assert_file_is_executable,
+            assert_user,
+            assert_host,
+            assert_virtualization,
+            assert_environment,
         } = self;
 
         description.is_none()
+            && documentation.is_empty()
             && wants.is_empty()
             && requires.is_empty()
             && binds_to.is_empty()
             && part_of.is_empty()
             && upholds.is_empty()
+            && conflicts.is_empty()
             && before.is_empty()
             && after.is_empty()
+            && on_failure.is_empty()
+            && on_success.is_empty()
+            && !stop_when_unneeded
+            && start_limit_interval_sec.is_none()
+            && start_limit_burst.is_none()
+            && condition_path_exists.is_empty()
+            && condition_path_exists_glob.is_empty()
+            && condition_path_is_directory.is_empty()
+            && condition_path_is_mount_point.is_empty()
+            && condition_directory_not_empty.is_empty()
+            && condition_file_not_empty.is_empty()
+            && condition_file_is_executable.is_empty()
+            && condition_user.is_empty()
+            && condition_host.is_empty()
+            && condition_virtualization.is_empty()
+            && condition_environment.is_empty()
+            && assert_path_exists.is_empty()
+            && assert_path_exists_glob.is_empty()
+            && assert_path_is_directory.is_empty()
+            && assert_path_is_mount_point.is_empty()
+            && assert_directory_not_empty.is_empty()
+            && assert_file_not_empty.is_empty()
+            && assert_file_is_executable.is_empty()
+            && assert_user.is_empty()
+            && assert_host.is_empty()
+            && assert_virtualization.is_empty()
+            && assert_environment.is_empty()
+    }
+
+    /// Merge `other` into `self`.
+    ///
+    /// Options already set in `self` take precedence, lists are extended.
+    pub fn merge(&mut self, other: Self) {
+        let Self {
+            description,
+            documentation,
+            wants,
+            requires,
+            binds_to,
+            part_of,
+            upholds,
+            conflicts,
+            before,
+            after,
+            on_failure,
+            on_success,
+            stop_when_unneeded,
+            start_limit_interval_sec,
+            start_limit_burst,
+            condition_path_exists,
+            condition_path_exists_glob,
+            condition_path_is_directory,
+            condition_path_is_mount_point,
+            condition_directory_not_empty,
+            condition_file_not_empty,
+            condition_file_is_executable,
+            condition_user,
+            condition_host,
+            condition_virtualization,
+            condition_environment,
+            assert_path_exists,
+            assert_path_exists_glob,
+            assert_path_is_directory,
+            assert_path_is_mount_point,
+            assert_directory_not_empty,
+            assert_file_not_empty,
+            assert_file_is_executable,
+            assert_user,
+            assert_host,
+            assert_virtualization,
+            assert_environment,
+        } = other;
+
+        if self.description.is_none() {
+            self.description = description;
+        }
+        self.documentation.extend(documentation);
+        self.wants.extend(wants);
+        self.requires.extend(requires);
+        self.binds_to.extend(binds_to);
+        self.part_of.extend(part_of);
+        self.upholds.extend(upholds);
+        self.conflicts.extend(conflicts);
+        self.before.extend(before);
+        self.after.extend(after);
+        self.on_failure.extend(on_failure);
+        self.on_success.extend(on_success);
+        self.stop_when_unneeded |= stop_when_unneeded;
+        if self.start_limit_interval_sec.is_none() {
+            self.start_limit_interval_sec = start_limit_interval_sec;
+        }
+        self.start_limit_burst = self.start_limit_burst.or(start_limit_burst);
+        self.condition_path_exists.extend(condition_path_exists);
+        self.condition_path_exists_glob
+            .extend(condition_path_exists_glob);
+        self.condition_path_is_directory
+            .extend(condition_path_is_directory);
+        self.condition_path_is_mount_point
+            .extend(condition_path_is_mount_point);
+        self.condition_directory_not_empty
+            .extend(condition_directory_not_empty);
+        self.condition_file_not_empty
+            .extend(condition_file_not_empty);
+        self.condition_file_is_executable
+            .extend(condition_file_is_executable);
+        self.condition_user.extend(condition_user);
+        self.condition_host.extend(condition_host);
+        self.condition_virtualization
+            .extend(condition_virtualization);
+        self.condition_environment.extend(condition_environment);
+        self.assert_path_exists.extend(assert_path_exists);
+        self.assert_path_exists_glob.extend(assert_path_exists_glob);
+        self.assert_path_is_directory
+            .extend(assert_path_is_directory);
+        self.assert_path_is_mount_point
+            .extend(assert_path_is_mount_point);
+        self.assert_directory_not_empty
+            .extend(assert_directory_not_empty);
+        self.assert_file_not_empty.extend(assert_file_not_empty);
+        self.assert_file_is_executable
+            .extend(assert_file_is_executable);
+        self.assert_user.extend(assert_user);
+        self.assert_host.extend(assert_host);
+        self.assert_virtualization.extend(assert_virtualization);
+        self.assert_environment.extend(assert_environment);
     }
 
     /// Add a compose [`Service`](compose_spec::Service) [`Dependency`] to the unit.
     ///
+    /// The unit is ordered after the dependency. The dependency's
+    /// [`Condition`](compose_spec::service::Condition) is not handled here, as it requires
+    /// changes to the dependency's unit, i.e. `Notify=healthy` in its \[Container\] section or
+    /// `Type=oneshot` in its \[Service\] section.
+    ///
     /// # Errors
     ///
-    /// Returns an error if the [`Condition`] is not [`ServiceStarted`](Condition::ServiceStarted)
-    /// or the [`Dependency`] is set to `restart` but is not `required`.
+    /// Returns an error if the [`Dependency`] is set to `restart` but is not `required`.
     pub fn add_dependency(
         &mut self,
         mut name: String,
         Dependency {
-            condition,
+            condition: _,
             restart,
             required,
         }: Dependency,
     ) -> eyre::Result<()> {
-        match condition {
-            Condition::ServiceStarted => {}
-            Condition::ServiceHealthy => {
-                return Err(condition_eyre(condition, "Notify=healthy", "Container"));
-            }
-            Condition::ServiceCompletedSuccessfully => {
-                return Err(condition_eyre(condition, "Type=oneshot", "Service"));
-            }
-        }
-
         // Which list to add the dependency to depends on whether to restart this unit and if the
         // dependency is required.
         let list = match (restart, required) {
@@ -157,11 +559,63 @@ impl Unit {
     }
 }
 
-/// Create an [`eyre::Report`] for an unsupported compose [`Dependency`] [`Condition`].
-///
-/// Suggests using `option` in `section` instead.
-fn condition_eyre(condition: Condition, option: &str, section: &str) -> eyre::Report {
-    eyre!("dependency condition `{condition}` is not directly supported").suggestion(format!(
-        "try using `{option}` in the [{section}] section of the dependency"
-    ))
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::OptionExt;
+
+    use crate::serde::quadlet::from_str;
+
+    use super::*;
+
+    #[test]
+    fn merge() {
+        let mut unit = Unit {
+            description: Some(String::from("one")),
+            after: vec![String::from("one.service")],
+            start_limit_burst: Some(3),
+            ..Unit::default()
+        };
+        unit.merge(Unit {
+            description: Some(String::from("two")),
+            after: vec![String::from("two.service")],
+            on_failure: vec![String::from("notify@%n.service")],
+            stop_when_unneeded: true,
+            start_limit_interval_sec: Some(String::from("1min")),
+            start_limit_burst: Some(5),
+            ..Unit::default()
+        });
+
+        assert_eq!(
+            unit,
+            Unit {
+                description: Some(String::from("one")),
+                after: vec![String::from("one.service"), String::from("two.service")],
+                on_failure: vec![String::from("notify@%n.service")],
+                stop_when_unneeded: true,
+                start_limit_interval_sec: Some(String::from("1min")),
+                start_limit_burst: Some(3),
+                ..Unit::default()
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize() -> color_eyre::Result<()> {
+        let unit: Unit = from_str(
+            "[Unit]\n\
+            StopWhenUnneeded=yes\n\
+            ConditionPathExists=/etc/one\n\
+            ConditionPathExists=!/etc/two\n\
+            AssertUser=root\n",
+        )?
+        .first()
+        .ok_or_eyre("expected a section")?
+        .deserialize()?;
+
+        assert!(unit.stop_when_unneeded);
+        assert_eq!(unit.condition_path_exists, ["/etc/one", "!/etc/two"]);
+        assert_eq!(unit.assert_user, ["root"]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,165 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer};
+use thiserror::Error;
+
+use crate::serde::{deserialize_from_str, quadlet::option_names};
+
+use super::{Globals, Install, Quadlet, ResourceKind, Service, Unit};
+
+/// An arbitrary option added to a section of a Quadlet file, e.g. `Service.KillMode=mixed`.
+///
+/// Used to set systemd or Quadlet options which podlet does not otherwise support.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitOption {
+    /// Name of the section the option is added to, e.g. `Service`.
+    pub section: String,
+
+    /// Name of the option, e.g. `KillMode`.
+    pub key: String,
+
+    /// Value of the option.
+    pub value: String,
+}
+
+impl UnitOption {
+    /// The [`ResourceKind`] of the option's section, if it is a resource section, e.g.
+    /// `[Container]`.
+    pub fn resource_kind(&self) -> Option<ResourceKind> {
+        ResourceKind::from_section_name(&self.section)
+    }
+
+    /// Returns `true` if the option is one podlet supports directly in its section.
+    ///
+    /// Setting such an option may conflict with the value podlet sets for it.
+    pub fn is_modeled(&self) -> bool {
+        let options = match self.section.as_str() {
+            "Unit" => option_names::<Unit>().to_vec(),
+            "Quadlet" => option_names::<Quadlet>().to_vec(),
+            "Service" => option_names::<Service>().to_vec(),
+            "Install" => option_names::<Install>().to_vec(),
+            _ => self.resource_kind().map_or_else(Vec::new, |kind| {
+                let mut options = kind.option_names();
+                options.extend_from_slice(option_names::<Globals>());
+                options
+            }),
+        };
+        options.contains(&self.key.as_str())
+    }
+}
+
+impl FromStr for UnitOption {
+    type Err = ParseUnitOptionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (section, option) = s
+            .split_once('.')
+            .ok_or(ParseUnitOptionError::MissingSection)?;
+        let (key, value) = option
+            .split_once('=')
+            .ok_or(ParseUnitOptionError::MissingValue)?;
+
+        if !matches!(section, "Unit" | "Quadlet" | "Service" | "Install")
+            && ResourceKind::from_section_name(section).is_none()
+        {
+            return Err(ParseUnitOptionError::UnknownSection(section.to_owned()));
+        }
+
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|char| char.is_ascii_alphanumeric() || char == '-')
+        {
+            return Err(ParseUnitOptionError::InvalidKey(key.to_owned()));
+        }
+
+        if value.contains('\n') {
+            return Err(ParseUnitOptionError::Newline);
+        }
+
+        Ok(Self {
+            section: section.to_owned(),
+            key: key.to_owned(),
+            value: value.to_owned(),
+        })
+    }
+}
+
+impl Display for UnitOption {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Self {
+            section,
+            key,
+            value,
+        } = self;
+        write!(f, "{section}.{key}={value}")
+    }
+}
+
+impl<'de> Deserialize<'de> for UnitOption {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
+/// Error returned when parsing a [`UnitOption`] from a string in the form `Section.Key=Value`.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseUnitOptionError {
+    #[error("missing section, must be in the form `Section.Key=Value`")]
+    MissingSection,
+
+    #[error("missing value, must be in the form `Section.Key=Value`")]
+    MissingValue,
+
+    #[error(
+        "unknown section `{0}`, must be `Unit`, `Quadlet`, `Service`, `Install`, \
+            or a resource section, e.g. `Container`"
+    )]
+    UnknownSection(String),
+
+    #[error("invalid option name `{0}`, must only contain ASCII letters, digits, and `-`")]
+    InvalidKey(String),
+
+    #[error("value must not contain a newline")]
+    Newline,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() -> Result<(), ParseUnitOptionError> {
+        let option: UnitOption = "Service.KillMode=mixed".parse()?;
+        assert_eq!(option.section, "Service");
+        assert_eq!(option.key, "KillMode");
+        assert_eq!(option.value, "mixed");
+        assert!(!option.is_modeled());
+        assert_eq!(option.to_string(), "Service.KillMode=mixed");
+
+        let option: UnitOption = "Container.Image=test=1".parse()?;
+        assert_eq!(option.value, "test=1");
+        assert_eq!(option.resource_kind(), Some(ResourceKind::Container));
+        assert!(option.is_modeled());
+
+        assert_eq!(
+            "Unknown.Key=value".parse::<UnitOption>(),
+            Err(ParseUnitOptionError::UnknownSection(String::from(
+                "Unknown"
+            )))
+        );
+        assert_eq!(
+            "Unit.Description".parse::<UnitOption>(),
+            Err(ParseUnitOptionError::MissingValue)
+        );
+        assert_eq!(
+            "Unit.Bad Key=value".parse::<UnitOption>(),
+            Err(ParseUnitOptionError::InvalidKey(String::from("Bad Key")))
+        );
+
+        Ok(())
+    }
+}
@@ -1,14 +1,14 @@
 use std::{ops::Not, path::PathBuf};
 
 use color_eyre::eyre::{Context, ensure};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{cli::volume::Opt, serde::quadlet::seq_quote_whitespace};
 
 use super::{Downgrade, DowngradeError, HostPaths, PodmanVersion, push_arg};
 
-#[derive(Serialize, Debug, Default, Clone, PartialEq)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
 pub struct Volume {
     /// If enabled, the content of the image located at the mount point of the volume
     /// is copied into the volume on the first run.
@@ -44,6 +44,12 @@ pub struct Volume {
 
     /// The host (numeric) UID, or user name to use as the owner for the volume.
     pub user: Option<String>,
+
+    /// The name of the Podman volume.
+    ///
+    /// If not set, the default value is `systemd-%N`.
+    #[allow(clippy::struct_field_names)]
+    pub volume_name: Option<String>,
 }
 
 impl HostPaths for Volume {
@@ -82,6 +88,16 @@ impl Downgrade for Volume {
             }
         }
 
+        if version < PodmanVersion::V4_5 {
+            if let Some(volume_name) = self.volume_name.take() {
+                return Err(DowngradeError::Option {
+                    quadlet_option: "VolumeName",
+                    value: volume_name,
+                    supported_version: PodmanVersion::V4_5,
+                });
+            }
+        }
+
         Ok(())
     }
 }
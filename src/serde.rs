@@ -1,12 +1,21 @@
 //! Provides [`serde::Serializer`]s for serializing command line args and Quadlet files,
-//! accessible through [`args::to_string()`] and [`quadlet::to_string()`].
+//! accessible through [`args::to_string()`] and [`quadlet::to_string()`]. Quadlet files can be
+//! parsed back into sections with [`quadlet::from_str()`].
 //!
 //! Also provides a [`serde::Serializer`] and [`serde::Deserializer`] for (de)serializing mount
 //! options via [`mount_options::to_string()`] and [`mount_options::from_str()`].
 
-use std::fmt::Display;
+use std::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    str::FromStr,
+};
 
-use serde::{Serializer, ser::SerializeSeq};
+use serde::{
+    Deserialize, Deserializer, Serializer,
+    de::{self, Visitor},
+    ser::SerializeSeq,
+};
 
 /// Implement [`serde::Serializer`]'s `serialize_*` functions by returning `Err($error)`.
 macro_rules! serialize_invalid_primitives {
@@ -91,3 +100,70 @@ where
 
     state.end()
 }
+
+/// Deserialize a value from a string using its [`FromStr`] implementation.
+///
+/// For use with `#[serde(deserialize_with = "deserialize_from_str")]` or within a [`Deserialize`]
+/// implementation.
+///
+/// # Errors
+///
+/// Returns an error if the `deserializer` does not provide a string or the string cannot be parsed.
+pub fn deserialize_from_str<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(FromStrVisitor(PhantomData))
+}
+
+/// Deserialize a sequence of strings into items using their [`FromStr`] implementation.
+///
+/// The inverse of [`serialize_display_seq()`].
+///
+/// # Errors
+///
+/// Returns an error if the `deserializer` does not provide a sequence of strings or any of the
+/// strings cannot be parsed.
+pub fn deserialize_from_str_seq<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    /// Wrapper for deserializing each item with [`deserialize_from_str()`].
+    struct Item<T>(T);
+
+    impl<'de, T> Deserialize<'de> for Item<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserialize_from_str(deserializer).map(Self)
+        }
+    }
+
+    Vec::<Item<T>>::deserialize(deserializer)
+        .map(|items| items.into_iter().map(|Item(item)| item).collect())
+}
+
+/// [`Visitor`] for deserializing types from strings with their [`FromStr`] implementation.
+struct FromStrVisitor<T>(PhantomData<T>);
+
+impl<T> Visitor<'_> for FromStrVisitor<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(E::custom)
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     collections::HashSet,
     fmt::{self, Display, Formatter, Write},
+    mem,
 };
 
 use serde::{
@@ -11,6 +12,10 @@ use thiserror::Error;
 
 use crate::quadlet::JoinOption;
 
+pub use self::de::{Entry, Section, option_names, parse_bool, reset_option_names};
+
+mod de;
+
 /// Serialize a sequence of strings, adding quotes to each string that contains whitespace.
 ///
 /// # Errors
@@ -28,7 +33,7 @@ where
 /// String slice wrapper that adds quotes in its [`Display`] impl if the string contains whitespace.
 ///
 /// Newline characters are also rewritten to a literal "\n".
-struct QuoteWhitespace<'a>(&'a str);
+pub struct QuoteWhitespace<'a>(pub &'a str);
 
 impl Display for QuoteWhitespace<'_> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
@@ -71,7 +76,9 @@ pub fn to_string_join_all<T: Serialize>(value: T) -> Result<String, Error> {
 /// Only structs, sequences, and tuples are allowed at the top level. Elements in a sequence are
 /// serialized as separate sections with a new line inserted between them. Tuples can be used to
 /// combine structs into a single section; the name from the first type in the tuple is used as the
-/// section name.
+/// section name. Maps with string keys may follow a struct in a tuple to add arbitrary options to
+/// the section. A string may be used as the first element of a tuple instead of a struct to name a
+/// section that only has arbitrary options, e.g. `("X-Section", map)`.
 ///
 /// `join_options` should be a set of all Quadlet options for which sequence values should be joined
 /// together by a space.
@@ -117,12 +124,94 @@ where
     Ok(serializer.output)
 }
 
+/// Parses `input` into the [`Section`]s of a Quadlet file.
+///
+/// Comments and empty lines are skipped, lines ending with a backslash are continued on the next
+/// line, and sections which appear multiple times are merged together. Use
+/// [`Section::deserialize()`] to convert a section into a struct.
+///
+/// When deserializing, options set multiple times are collected into sequences, or the last value
+/// is used for single values. Setting an option to an empty value resets it. The values of
+/// [`JoinOption`]s are split on whitespace, respecting quotes.
+///
+/// # Errors
+///
+/// Returns an error if `input` is not a valid Quadlet file, e.g. an option is set outside of a
+/// section or a line is not a section header, comment, or `Key=Value` pair.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Deserialize)]
+/// #[serde(rename_all = "PascalCase")]
+/// struct Example {
+///     str: String,
+///     vec: Vec<u8>,
+///     environment: Vec<String>,
+/// }
+///
+/// let sections = from_str(
+///     "[Example]\n\
+///     Str=Hello world!\n\
+///     Vec=1\n\
+///     Vec=2\n\
+///     Environment=ONE=one \"TWO=two two\"\n",
+/// )?;
+/// let example: Example = sections[0].deserialize()?;
+/// assert_eq!(example.str, "Hello world!");
+/// assert_eq!(example.vec, [1, 2]);
+/// assert_eq!(example.environment, ["ONE=one", "TWO=two two"]);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn from_str(input: &str) -> Result<Vec<Section>, Error> {
+    de::parse(input)
+}
+
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum Error {
-    #[error("error while serializing: {0}")]
+    /// An error occurred while (de)serializing.
+    #[error("error while (de)serializing: {0}")]
     Custom(String),
-    #[error("type cannot be serialized")]
+
+    /// A type that cannot be (de)serialized was encountered.
+    #[error("type cannot be (de)serialized")]
     InvalidType,
+
+    /// The Quadlet file has invalid syntax.
+    #[error("line {line}: {message}")]
+    Syntax { line: usize, message: String },
+
+    /// A section could not be deserialized, e.g. because it is missing a required option.
+    #[error("line {line}: invalid [{section}] section: {message}")]
+    Section {
+        line: usize,
+        section: String,
+        message: String,
+    },
+
+    /// The Quadlet file has a section which is not supported.
+    #[error("line {line}: unknown section [{section}]")]
+    UnknownSection { line: usize, section: String },
+
+    /// A section has an option which is not supported.
+    #[error("line {line}: unknown option `{key}=` in the [{section}] section")]
+    UnknownOption {
+        line: usize,
+        section: String,
+        key: String,
+    },
+
+    /// The value of an option could not be deserialized.
+    #[error("line {line}: invalid value for `{key}=`: {message}")]
+    Value {
+        line: usize,
+        key: String,
+        message: String,
+    },
+
+    /// The Quadlet file does not have a resource section, e.g. [Container].
+    #[error("missing resource section, e.g. [Container]")]
+    MissingResource,
 }
 
 impl ser::Error for Error {
@@ -134,11 +223,45 @@ impl ser::Error for Error {
     }
 }
 
+impl serde::de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self::Custom(msg.to_string())
+    }
+
+    fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+        if expected.is_empty() {
+            Self::Custom(format!("unknown option `{field}=`, there are no options"))
+        } else {
+            let expected: Vec<String> = expected
+                .iter()
+                .map(|option| format!("`{option}=`"))
+                .collect();
+            Self::Custom(format!(
+                "unknown option `{field}=`, expected one of {}",
+                expected.join(", ")
+            ))
+        }
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self::Custom(format!("missing option `{field}=`"))
+    }
+
+    fn duplicate_field(field: &'static str) -> Self {
+        Self::Custom(format!("duplicate option `{field}=`"))
+    }
+}
+
 /// A serializer for converting structs to Quadlet file sections.
 struct Serializer<'a> {
     output: String,
     skip_section_name: bool,
     join_options: &'a HashSet<JoinOption>,
+    /// The key of the map entry currently being serialized.
+    map_key: String,
 }
 
 impl<'a> Serializer<'a> {
@@ -147,6 +270,7 @@ impl<'a> Serializer<'a> {
             output: String::new(),
             skip_section_name: false,
             join_options,
+            map_key: String::new(),
         }
     }
 }
@@ -164,7 +288,7 @@ impl ser::Serializer for &mut Serializer<'_> {
 
     type SerializeTupleVariant = Impossible<(), Error>;
 
-    type SerializeMap = Impossible<(), Error>;
+    type SerializeMap = Self;
 
     type SerializeStruct = Self;
 
@@ -186,10 +310,18 @@ impl ser::Serializer for &mut Serializer<'_> {
         serialize_f32: f32,
         serialize_f64: f64,
         serialize_char: char,
-        serialize_str: &str,
         serialize_bytes: &[u8],
     }
 
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        // Section name for the following elements of a tuple.
+        if self.skip_section_name {
+            return Err(Error::InvalidType);
+        }
+        writeln!(self.output, "[{v}]").expect("write to String never fails");
+        Ok(())
+    }
+
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
         Err(Error::InvalidType)
     }
@@ -260,7 +392,12 @@ impl ser::Serializer for &mut Serializer<'_> {
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        Err(Error::InvalidType)
+        // Maps do not have a section name, so they must follow a struct in a tuple.
+        if self.skip_section_name {
+            Ok(self)
+        } else {
+            Err(Error::InvalidType)
+        }
     }
 
     fn serialize_struct(
@@ -319,6 +456,29 @@ impl ser::SerializeTuple for &mut Serializer<'_> {
     }
 }
 
+impl ser::SerializeMap for &mut Serializer<'_> {
+    type Ok = ();
+
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.map_key = key.serialize(MapKeySerializer)?;
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = mem::take(&mut self.map_key);
+        value.serialize(&mut ValueSerializer {
+            serializer: self,
+            key: &key,
+        })
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+}
+
 impl ser::SerializeStruct for &mut Serializer<'_> {
     type Ok = ();
 
@@ -362,7 +522,7 @@ impl ser::SerializeStructVariant for &mut Serializer<'_> {
 /// in `serializer.join_options`. Nested sequences result in an error.
 struct ValueSerializer<'a, 'b> {
     serializer: &'a mut Serializer<'b>,
-    key: &'static str,
+    key: &'a str,
 }
 
 impl ValueSerializer<'_, '_> {
@@ -534,7 +694,7 @@ struct SeqValueSerializer<'a> {
     output: &'a mut String,
 
     /// The Quadlet option currently being serialized.
-    key: &'static str,
+    key: &'a str,
 
     /// Whether this Quadlet option sequence should be joined together with spaces into one value.
     join_option: bool,
@@ -548,7 +708,7 @@ struct SeqValueSerializer<'a> {
 
 impl<'a> SeqValueSerializer<'a> {
     /// Create a new [`SeqValueSerializer`].
-    fn new(output: &'a mut String, key: &'static str, join_options: &HashSet<JoinOption>) -> Self {
+    fn new(output: &'a mut String, key: &'a str, join_options: &HashSet<JoinOption>) -> Self {
         Self {
             output,
             key,
@@ -774,10 +934,160 @@ impl ser::Serializer for &mut SeqValueSerializer<'_> {
     }
 }
 
+/// Serializes map keys for [`Serializer`] into Quadlet option names.
+///
+/// Only strings are supported.
+struct MapKeySerializer;
+
+impl ser::Serializer for MapKeySerializer {
+    type Ok = String;
+
+    type Error = Error;
+
+    type SerializeSeq = Impossible<String, Error>;
+
+    type SerializeTuple = Impossible<String, Error>;
+
+    type SerializeTupleStruct = Impossible<String, Error>;
+
+    type SerializeTupleVariant = Impossible<String, Error>;
+
+    type SerializeMap = Impossible<String, Error>;
+
+    type SerializeStruct = Impossible<String, Error>;
+
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    serialize_invalid_primitives! {
+        Error::InvalidType,
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_bytes: &[u8],
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_owned())
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(Error::InvalidType)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<Self::Ok, Self::Error> {
+        Err(Error::InvalidType)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Err(Error::InvalidType)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Err(Error::InvalidType)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(Error::InvalidType)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(Error::InvalidType)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(Error::InvalidType)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(Error::InvalidType)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(Error::InvalidType)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(Error::InvalidType)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Err(Error::InvalidType)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(Error::InvalidType)
+    }
+
+    fn collect_str<T: ?Sized + Display>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        Ok(value.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
+    use color_eyre::eyre::OptionExt;
+    use indexmap::IndexMap;
+    use serde::Deserialize;
+
     use super::*;
 
     #[test]
@@ -844,6 +1154,29 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn tuple_map() -> Result<(), Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Test {
+            one: u8,
+        }
+
+        let map = IndexMap::from([("Two", vec!["2"]), ("After", vec!["a", "b"])]);
+        assert_eq!(
+            to_string_join_all((Test { one: 1 }, map))?,
+            "[Test]\n\
+            One=1\n\
+            Two=2\n\
+            After=a b\n"
+        );
+
+        let map = HashMap::from([("One", 1)]);
+        assert_eq!(to_string_join_all(map), Err(Error::InvalidType));
+
+        Ok(())
+    }
+
     #[test]
     fn sequence() -> Result<(), Error> {
         #[derive(Serialize)]
@@ -926,4 +1259,173 @@ mod tests {
         let quoted = QuoteWhitespace(r"path=C:\Users\test dir");
         assert_eq!(quoted.to_string(), r#""path=C:\\Users\\test dir""#);
     }
+
+    #[test]
+    fn deserialize_struct() -> color_eyre::Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        #[serde(rename_all = "PascalCase")]
+        struct Test {
+            str: String,
+            option: Option<String>,
+            bool: bool,
+            vec: Vec<u8>,
+            after: Vec<String>,
+            podman_args: String,
+        }
+
+        let sections = from_str(
+            "# comment\n\
+            [Test]\n\
+            Str=one\n\
+            Str=two\n\
+            ; comment\n\
+            Option=\n\
+            Bool=yes\n\
+            Vec=1\n\
+            Vec=\n\
+            Vec=2\n\
+            Vec=3\n\
+            After=one \"two three\" 'four\\\\'\n\
+            PodmanArgs=--one \\\n\
+            # comment\n\
+            \t--two\n\
+            PodmanArgs=--three\n",
+        )?;
+        let section = sections.first().ok_or_eyre("missing section")?;
+        assert_eq!(section.name, "Test");
+        assert_eq!(
+            section.deserialize::<Test>()?,
+            Test {
+                str: "two".into(),
+                option: None,
+                bool: true,
+                vec: vec![2, 3],
+                after: vec!["one".into(), "two three".into(), "four\\".into()],
+                podman_args: "--one --two --three".into(),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_round_trip() -> color_eyre::Result<()> {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        #[serde(rename_all = "PascalCase")]
+        struct Test {
+            #[serde(serialize_with = "seq_quote_whitespace")]
+            environment: Vec<String>,
+            #[serde(serialize_with = "seq_quote_whitespace")]
+            label: Vec<String>,
+        }
+
+        let sut = Test {
+            environment: vec!["ONE=one".into(), "TWO=two\ntwo".into()],
+            label: vec![r#"key={"foo": "bar"}"#.into(), r"path=C:\test dir".into()],
+        };
+        let sections = from_str(&to_string_join_all(&sut)?)?;
+        let section = sections.first().ok_or_eyre("missing section")?;
+        assert_eq!(section.deserialize::<Test>()?, sut);
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_round_trip_split() -> color_eyre::Result<()> {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        #[serde(rename_all = "PascalCase")]
+        struct Test {
+            #[serde(serialize_with = "seq_quote_whitespace")]
+            label: Vec<String>,
+            #[serde(serialize_with = "seq_quote_whitespace")]
+            volume: Vec<String>,
+        }
+
+        let sut = Test {
+            label: vec!["one=1".into(), "two=two 2".into()],
+            volume: vec![
+                "/a dir:/a".into(),
+                "data:/data".into(),
+                r#"say "hi""#.into(),
+            ],
+        };
+        let sections = from_str(&to_string(&sut, &HashSet::new())?)?;
+        let section = sections.first().ok_or_eyre("missing section")?;
+        assert_eq!(section.deserialize::<Test>()?, sut);
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_missing_option() -> color_eyre::Result<()> {
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct Test {
+            #[expect(dead_code, reason = "only used for deserialization")]
+            image: String,
+            #[expect(dead_code, reason = "only used for deserialization")]
+            field: Option<String>,
+        }
+
+        let sections = from_str("[Test]\nField=a field\n")?;
+        let section = sections.first().ok_or_eyre("missing section")?;
+        assert_eq!(
+            section.deserialize::<Test>().err(),
+            Some(Error::Section {
+                line: 1,
+                section: "Test".into(),
+                message: "missing option `Image=`".into(),
+            })
+        );
+        assert_eq!(
+            <Error as serde::de::Error>::custom("invalid field"),
+            Error::Custom("invalid field".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_unknown_option() -> color_eyre::Result<()> {
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct Test {
+            #[expect(dead_code, reason = "only used for deserialization")]
+            known: Option<String>,
+        }
+
+        let sections = from_str("[Test]\nKnown=one\nUnknown=two\n")?;
+        let section = sections.first().ok_or_eyre("missing section")?;
+
+        let (_, unknown) = section.deserialize_partial::<Test>()?;
+        assert_eq!(
+            unknown,
+            [&Entry {
+                key: "Unknown".into(),
+                value: "two".into(),
+                line: 3,
+            }]
+        );
+        assert_eq!(
+            section.deserialize::<Test>().err(),
+            Some(Error::UnknownOption {
+                line: 3,
+                section: "Test".into(),
+                key: "Unknown".into(),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_syntax_err() {
+        assert!(matches!(
+            from_str("Option=value\n"),
+            Err(Error::Syntax { line: 1, .. })
+        ));
+        assert!(matches!(
+            from_str("[Test]\n\nNoValue\n"),
+            Err(Error::Syntax { line: 3, .. })
+        ));
+        assert!(matches!(
+            from_str("[Test\n"),
+            Err(Error::Syntax { line: 1, .. })
+        ));
+    }
 }
@@ -0,0 +1,851 @@
+//! Provides [`Section`]s parsed by [`from_str()`](super::from_str()) and the deserializers used by
+//! [`Section::deserialize()`].
+
+use std::{borrow::Cow, str::FromStr, vec};
+
+use indexmap::IndexMap;
+use serde::{
+    de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor},
+    forward_to_deserialize_any,
+};
+
+use crate::quadlet::JoinOption;
+
+use super::Error;
+
+/// Quadlet options whose values are concatenated together with a space when set multiple times.
+const CONCAT_OPTIONS: &[&str] = &["GlobalArgs", "PodmanArgs"];
+
+/// A section of a Quadlet file, e.g. `[Container]`, and its entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// Name of the section, without the surrounding brackets.
+    pub name: String,
+    /// Line number of the section's header.
+    pub line: usize,
+    /// Entries of the section in the order they appear.
+    pub entries: Vec<Entry>,
+}
+
+impl Section {
+    /// Deserialize the section's entries into `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `T` does not have an option for every entry in the section, or if
+    /// deserializing any of the values fails.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        let (value, unknown) = self.deserialize_partial()?;
+        unknown
+            .first()
+            .map_or(Ok(value), |entry| Err(self.unknown_option(entry)))
+    }
+
+    /// Deserialize the section's entries into `T`, returning the entries `T` does not have an
+    /// option for alongside it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deserializing any of the values fails.
+    pub fn deserialize_partial<T: DeserializeOwned>(&self) -> Result<(T, Vec<&Entry>), Error> {
+        let mut unknown = Vec::new();
+        let value = T::deserialize(SectionDeserializer {
+            section: self,
+            unknown: &mut unknown,
+        })
+        .map_err(|error| match error {
+            Error::Custom(message) => Error::Section {
+                line: self.line,
+                section: self.name.clone(),
+                message,
+            },
+            error => error,
+        })?;
+
+        Ok((value, unknown))
+    }
+
+    /// Create an [`Error::UnknownOption`] for an `entry` of this section.
+    pub fn unknown_option(&self, entry: &Entry) -> Error {
+        Error::UnknownOption {
+            line: entry.line,
+            section: self.name.clone(),
+            key: entry.key.clone(),
+        }
+    }
+}
+
+/// A `Key=Value` entry of a Quadlet file [`Section`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The Quadlet option.
+    pub key: String,
+    /// The value of the option with any line continuations joined.
+    pub value: String,
+    /// Line number the entry starts on.
+    pub line: usize,
+}
+
+/// Parse `input` into the [`Section`]s of a Quadlet file.
+///
+/// Sections which appear multiple times are merged together.
+pub fn parse(input: &str) -> Result<Vec<Section>, Error> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut current = None;
+    let mut lines = input.lines().zip(1..);
+
+    while let Some((line, line_number)) = lines.next() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            let name = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+                .filter(|name| !name.is_empty())
+                .ok_or_else(|| Error::Syntax {
+                    line: line_number,
+                    message: format!("invalid section header `{line}`"),
+                })?;
+
+            let index = sections
+                .iter()
+                .position(|section| section.name == name)
+                .unwrap_or_else(|| {
+                    sections.push(Section {
+                        name: name.to_owned(),
+                        line: line_number,
+                        entries: Vec::new(),
+                    });
+                    sections.len() - 1
+                });
+            current = Some(index);
+
+            continue;
+        }
+
+        let section = current
+            .and_then(|index| sections.get_mut(index))
+            .ok_or_else(|| Error::Syntax {
+                line: line_number,
+                message: "options must be within a section, e.g. `[Container]`".to_owned(),
+            })?;
+
+        let (key, value) = line.split_once('=').ok_or_else(|| Error::Syntax {
+            line: line_number,
+            message: format!("expected `Key=Value`, found `{line}`"),
+        })?;
+
+        let key = key.trim_end();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(Error::Syntax {
+                line: line_number,
+                message: format!("invalid option name `{key}`"),
+            });
+        }
+
+        // Lines ending with a backslash are continued on the next non-comment line.
+        let mut value = value.trim_start().to_owned();
+        while value.ends_with('\\') {
+            value.pop();
+            value.truncate(value.trim_end().len());
+            value.push(' ');
+            let Some(next) = lines
+                .by_ref()
+                .map(|(line, _)| line.trim())
+                .find(|line| !line.starts_with(['#', ';']))
+            else {
+                break;
+            };
+            value.push_str(next);
+        }
+        value.truncate(value.trim_end().len());
+
+        section.entries.push(Entry {
+            key: key.to_owned(),
+            value,
+            line: line_number,
+        });
+    }
+
+    Ok(sections)
+}
+
+/// Parse a systemd boolean value.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "yes" | "y" | "true" | "t" | "on" => Some(true),
+        "0" | "no" | "n" | "false" | "f" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns the names of the Quadlet options of the struct `T`.
+///
+/// Returns an empty slice if `T` is not deserialized from a struct.
+pub fn option_names<T: DeserializeOwned>() -> &'static [&'static str] {
+    let mut names = &[][..];
+    if T::deserialize(OptionNamesDeserializer { names: &mut names }).is_ok() {
+        // `OptionNamesDeserializer` always returns an error.
+        return &[];
+    }
+    names
+}
+
+/// Records the field names of a struct in `names` and returns an error.
+struct OptionNamesDeserializer<'a> {
+    names: &'a mut &'static [&'static str],
+}
+
+impl<'de> de::Deserializer<'de> for OptionNamesDeserializer<'_> {
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct
+        map enum identifier ignored_any
+    }
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::InvalidType)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        *self.names = fields;
+        Err(Error::InvalidType)
+    }
+}
+
+/// Returns the names of the Quadlet options of the struct `T` which are reset by an empty value.
+///
+/// These are the options deserialized from a sequence and the options in [`CONCAT_OPTIONS`], i.e.
+/// options whose values accumulate when set multiple times. An empty value for other options is
+/// used as is.
+pub fn reset_option_names<T: DeserializeOwned>() -> Vec<&'static str> {
+    option_names::<T>()
+        .iter()
+        .copied()
+        .filter(|&name| {
+            if CONCAT_OPTIONS.contains(&name) {
+                return true;
+            }
+            let mut seq = false;
+            // `SeqProbeDeserializer` always returns an error.
+            let _ = T::deserialize(SeqProbeDeserializer {
+                name: Some(name),
+                seq: &mut seq,
+            });
+            seq
+        })
+        .collect()
+}
+
+/// Deserializes a struct with only the option `name` set, recording in `seq` whether its value is
+/// deserialized from a sequence, and returns an error.
+struct SeqProbeDeserializer<'a> {
+    name: Option<&'static str>,
+    seq: &'a mut bool,
+}
+
+impl<'de> de::Deserializer<'de> for SeqProbeDeserializer<'_> {
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct
+        map enum identifier ignored_any
+    }
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::InvalidType)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(self)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for SeqProbeDeserializer<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.name
+            .map(|name| seed.deserialize(name.into_deserializer()))
+            .transpose()
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.name = None;
+        seed.deserialize(SeqProbeValueDeserializer { seq: self.seq })
+    }
+}
+
+/// Records in `seq` whether a value is deserialized from a sequence and returns an error.
+struct SeqProbeValueDeserializer<'a> {
+    seq: &'a mut bool,
+}
+
+impl<'de> de::Deserializer<'de> for SeqProbeValueDeserializer<'_> {
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple tuple_struct
+        map struct enum identifier ignored_any
+    }
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::InvalidType)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        *self.seq = true;
+        Err(Error::InvalidType)
+    }
+}
+
+/// Split a space separated list of words, removing quotes and escapes.
+///
+/// The inverse of joining [`QuoteWhitespace`](super::QuoteWhitespace) values with a space.
+fn split_words(input: &str) -> Result<Vec<String>, Error> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    let mut chars = input.chars();
+
+    while let Some(char) = chars.next() {
+        match (quote, char) {
+            (None, char) if char.is_whitespace() => words.extend(word.take()),
+            (None, '"' | '\'') => {
+                quote = Some(char);
+                word.get_or_insert_default();
+            }
+            (Some(quote_char), char) if quote_char == char => quote = None,
+            (_, '\\') => {
+                let escaped = chars
+                    .next()
+                    .ok_or_else(|| Error::Custom("value ends with a backslash".to_owned()))?;
+                word.get_or_insert_default().push(match escaped {
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    char => char,
+                });
+            }
+            (_, char) => word.get_or_insert_default().push(char),
+        }
+    }
+
+    if quote.is_some() {
+        return Err(Error::Custom("unterminated quote".to_owned()));
+    }
+    words.extend(word);
+
+    Ok(words)
+}
+
+/// Remove the quotes and escapes from `value` if it is a single quoted word, as written by
+/// [`seq_quote_whitespace()`](super::seq_quote_whitespace()) for values containing whitespace.
+///
+/// Other values are returned as is.
+fn unquote(value: &str) -> String {
+    let quoted = value.len() > 1
+        && (value.starts_with('"') && value.ends_with('"')
+            || value.starts_with('\'') && value.ends_with('\''));
+    if quoted {
+        if let Ok(mut words) = split_words(value) {
+            if let (Some(word), None) = (words.pop(), words.first()) {
+                return word;
+            }
+        }
+    }
+    value.to_owned()
+}
+
+/// Deserializes a [`Section`] as a map.
+struct SectionDeserializer<'a, 'b> {
+    section: &'a Section,
+    unknown: &'b mut Vec<&'a Entry>,
+}
+
+impl<'de> de::Deserializer<'de> for SectionDeserializer<'_, '_> {
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct
+        enum identifier ignored_any
+    }
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::InvalidType)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let mut options: IndexMap<&str, Vec<&Entry>> = IndexMap::new();
+        for entry in &self.section.entries {
+            options.entry(entry.key.as_str()).or_default().push(entry);
+        }
+
+        visitor.visit_map(MapAccess {
+            options: options.into_iter(),
+            next_value: None,
+            unknown: self.unknown,
+        })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+}
+
+/// Deserializes the options of a [`Section`] for [`SectionDeserializer`].
+///
+/// Entries with the same key are grouped together into a single value.
+struct MapAccess<'a, 'b> {
+    options: indexmap::map::IntoIter<&'a str, Vec<&'a Entry>>,
+    next_value: Option<(&'a str, Vec<&'a Entry>)>,
+    unknown: &'b mut Vec<&'a Entry>,
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess<'_, '_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let Some((key, entries)) = self.options.next() else {
+            return Ok(None);
+        };
+        self.next_value = Some((key, entries));
+
+        seed.deserialize(key.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (key, entries) = self
+            .next_value
+            .take()
+            .ok_or_else(|| Error::Custom("value requested before key".to_owned()))?;
+
+        let mut ignored = false;
+        let result = seed.deserialize(ValueDeserializer {
+            key,
+            entries: &entries,
+            ignored: &mut ignored,
+        });
+
+        if ignored {
+            self.unknown.extend(&entries);
+        }
+
+        result.map_err(|error| match error {
+            Error::Custom(message) => Error::Value {
+                line: entries.last().map_or(0, |entry| entry.line),
+                key: key.to_owned(),
+                message,
+            },
+            error => error,
+        })
+    }
+}
+
+/// Implement [`de::Deserializer`]'s `deserialize_*` functions by forwarding to a
+/// [`ScalarDeserializer`] of the option's value.
+macro_rules! forward_to_scalar {
+    ($($f:ident)*) => {
+        $(
+            fn $f<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                ScalarDeserializer::new(&self.value()).$f(visitor)
+            }
+        )*
+    };
+}
+
+/// Deserializes the value of all entries of an option for [`MapAccess`].
+struct ValueDeserializer<'a, 'b> {
+    key: &'a str,
+    entries: &'b [&'a Entry],
+    ignored: &'b mut bool,
+}
+
+impl ValueDeserializer<'_, '_> {
+    /// The value of the option when deserializing a single value.
+    ///
+    /// The last value set wins, unless the option is in [`CONCAT_OPTIONS`], in which case all
+    /// values set since the last empty value are joined with a space.
+    fn value(&self) -> Cow<'_, str> {
+        if CONCAT_OPTIONS.contains(&self.key) {
+            let start = self
+                .entries
+                .iter()
+                .rposition(|entry| entry.value.is_empty())
+                .map_or(0, |index| index + 1);
+            let values: Vec<&str> = self
+                .entries
+                .iter()
+                .skip(start)
+                .map(|entry| entry.value.as_str())
+                .collect();
+            Cow::Owned(values.join(" "))
+        } else {
+            self.entries
+                .last()
+                .map_or(Cow::Borrowed(""), |entry| Cow::Borrowed(&entry.value))
+        }
+    }
+
+    /// The values of the option when deserializing a sequence.
+    ///
+    /// An empty value resets the sequence. Values of [`JoinOption`]s are split into words. Values
+    /// of other options are unquoted if they are a single quoted word.
+    fn values(&self) -> Result<Vec<String>, Error> {
+        let split = self.key.parse::<JoinOption>().is_ok();
+        let mut values = Vec::new();
+
+        for entry in self.entries {
+            if entry.value.is_empty() {
+                values.clear();
+            } else if split {
+                values.extend(split_words(&entry.value)?);
+            } else {
+                values.push(unquote(&entry.value));
+            }
+        }
+
+        Ok(values)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'_, '_> {
+    type Error = Error;
+
+    forward_to_scalar! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf
+        deserialize_identifier
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.value().is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(SeqAccess {
+            values: self.values()?.into_iter(),
+        })
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::InvalidType)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::InvalidType)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        ScalarDeserializer::new(&self.value()).deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        *self.ignored = true;
+        visitor.visit_unit()
+    }
+}
+
+/// Deserializes sequences for [`ValueDeserializer`].
+struct SeqAccess {
+    values: vec::IntoIter<String>,
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAccess {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.values
+            .next()
+            .map(|value| seed.deserialize(ScalarDeserializer::new(&value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+/// Deserializes a single string value.
+struct ScalarDeserializer<'a> {
+    input: &'a str,
+}
+
+impl<'a> ScalarDeserializer<'a> {
+    /// Create a [`ScalarDeserializer`] from a string.
+    fn new(input: &'a str) -> Self {
+        Self { input }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ScalarDeserializer<'_> {
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        bytes byte_buf unit unit_struct newtype_struct seq tuple tuple_struct
+        map struct identifier ignored_any
+    }
+
+    deserialize_parse! {
+        'de, input,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if let Ok(u64) = u64::from_str(self.input) {
+            visitor.visit_u64(u64)
+        } else if let Ok(i64) = i64::from_str(self.input) {
+            visitor.visit_i64(i64)
+        } else {
+            self.deserialize_str(visitor)
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if let Some(bool) = parse_bool(self.input) {
+            visitor.visit_bool(bool)
+        } else {
+            Err(de::Error::invalid_value(
+                de::Unexpected::Str(self.input),
+                &visitor,
+            ))
+        }
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let mut chars = self.input.chars();
+        match chars.next() {
+            Some(char) if chars.next().is_none() => visitor.visit_char(char),
+            _ => Err(de::Error::invalid_type(
+                de::Unexpected::Str(self.input),
+                &visitor,
+            )),
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str(self.input)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_string(self.input.to_owned())
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.input.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(self.input.into_deserializer())
+    }
+}